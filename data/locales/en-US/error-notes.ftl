@@ -6,4 +6,5 @@ export-only-allowed-in-modules = The 'export' keyword can only be used inside mo
 export-only-allowed-in-top-level-scope = The 'export' keyword can only be used in the top level scope of a module.
 import-requires-items-to-be-imported = The 'import' keyword requires either a scoped namespace or list of import items.
 import-requires-items-to-be-imported-help = Try using 'import * as module from "module.dm"' or 'import {"{"} item {"}"} from "module.dm"'
-mismatched-type-assertions = Expected: {$expected}, Found: {$actual}
\ No newline at end of file
+mismatched-type-assertions = Expected: {$expected}, Found: {$actual}
+trace-truncated = {$count} calls were left out of the trace.
@@ -14,6 +14,7 @@ E2000 = Internal Compiler Error (please report this as a bug with code to reprod
 
 E2100 = The maximum number of upvalues (256) has been exceeded for this compilation unit.
 E2101 = The maximum number of constants (256) has been exceeded for this compilation unit.
+E2102 = The code a jump skips over is too large (32KB). Try moving some of it into functions.
 
 E2200 = The new method cannot specify a return type.
 E2201 = The new method must specify self as the first parameter.
@@ -38,6 +39,9 @@ E2502 = The 'continue' keyword can only be used inside of loops.
 E2503 = The error propagate operator '!!' can only be used inside of functions or methods.
 E2504 = Invalid usage of the 'export' keyword.
 E2505 = Invalid usage of the 'import' keyword.
+E2506 = The 'is' operator can only check against a single class. Found: {$name}
+E2600 = Mismatched types. Expected: {$expected}, Found: {$actual}
+E2700 = This expression is not supported by the register-based backend.
 
 # Runtime errors
 E3000 = The value cannot be converted to a boolean.
@@ -48,6 +52,10 @@ E3004 = The value cannot be converted to a string.
 E3005 = The value cannot be converted to a symbol.
 E3006 = The value cannot be converted to an object.
 E3007 = The value cannot be converted to a class.
+E3008 = The constant cannot be converted to a type shape.
+E3009 = The value cannot be converted to a map.
+E3010 = The value cannot be used as a map key.  Only null, unit, bools, ints, strings, symbols and arrays of those can be map keys.
+E3011 = The constant cannot be converted to a value.
 
 E3100 = Type assertion failed due to mismatching types.
 E3101 = Type assertion failed due to unexpected null value.
@@ -66,7 +74,35 @@ E3400 = The 'new' function cannot be accessed directly on class instances.  To c
 E3401 = If a super class has a 'new' function the subclass must also have a 'new' function.
 E3402 = Classes cannot inherit from value types.
 
+E3500 = Index {$index} is out of bounds for a length of {$length}.
+
+E3600 = The script exceeded its limit of {$limit} instructions.
+E3601 = The script exceeded its time limit of {$limit} ms.
+E3602 = The script exceeded its limit of {$limit} nested calls.
+E3603 = The script exceeded its heap limit of {$limit} bytes.
+E3604 = Stack overflow. The script recursed too deeply.
+E3605 = The script was interrupted.
+
 # System errors
 E4000 = A panic has occurred. {$message}
 E4001 = IO error occurred. {$message}
-E4002 = Modules cannot be loaded from outside the modules directory. Module must be inside of {$directory}
\ No newline at end of file
+E4002 = Modules cannot be loaded from outside the modules directory. Module must be inside of {$directory}
+
+E4100 = The bytecode is malformed or has been truncated.
+E4101 = The bytecode format version is not supported. Expected: {$expected}, Found: {$actual}
+E4102 = The bytecode checksum does not match its contents.
+E4103 = Invalid opcode {$opcode} found at offset {$offset}.
+E4104 = The instruction at offset {$offset} is missing its operands.
+E4105 = The instruction at offset {$offset} references constant {$index}, but there are only {$length} constants.
+E4106 = The instruction at offset {$offset} expected constant {$index} to be a {$expected}.
+E4107 = The instruction at offset {$offset} references local slot {$slot}, but there are only {$length} slots.
+E4108 = The instruction at offset {$offset} references upvalue {$index}, but there are only {$length} upvalues.
+E4109 = The jump at offset {$offset} targets {$target}, which is not the start of an instruction.
+E4110 = The instruction at offset {$offset} requires {$required} values on the stack, but only {$depth} are available.
+E4111 = The stack depth at offset {$offset} is inconsistent. Expected: {$expected}, Found: {$actual}
+E4112 = The instruction at offset {$offset} has an invalid flag {$flag}.
+E4113 = The instruction at offset {$offset} references inline cache {$index}, but there are only {$length} caches.
+
+E4200 = Native functions can only be included in a snapshot when they are defined as a global.
+E4201 = The snapshot is malformed or was taken by an incompatible version.
+E4202 = The snapshot refers to the native function {$name}, but no such global is defined.
\ No newline at end of file
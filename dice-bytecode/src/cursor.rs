@@ -5,6 +5,7 @@ use std::io::Cursor;
 pub struct BytecodeCursor<'a> {
     cursor: Cursor<&'a [u8]>,
     last_instruction_offset: u64,
+    is_wide: bool,
 }
 
 impl<'a> BytecodeCursor<'a> {
@@ -12,6 +13,7 @@ impl<'a> BytecodeCursor<'a> {
         Self {
             cursor: Cursor::new(data),
             last_instruction_offset: 0,
+            is_wide: false,
         }
     }
 
@@ -25,7 +27,15 @@ impl<'a> BytecodeCursor<'a> {
         if self.cursor.has_remaining() {
             self.last_instruction_offset = self.position();
 
-            Some(self.cursor.get_u8().into())
+            let instruction = self.cursor.get_u8().into();
+            self.is_wide = matches!(instruction, Instruction::Wide);
+
+            // NOTE: The wide prefix is consumed here, so the caller only ever sees the instruction it applies to.
+            if self.is_wide {
+                Some(self.cursor.get_u8().into())
+            } else {
+                Some(instruction)
+            }
         } else {
             None
         }
@@ -36,6 +46,16 @@ impl<'a> BytecodeCursor<'a> {
         self.cursor.get_u8()
     }
 
+    /// Read an index or count operand, which is widened to 32 bits when the instruction has a wide prefix.
+    #[inline]
+    pub fn read_arg(&mut self) -> usize {
+        if self.is_wide {
+            self.cursor.get_u32() as usize
+        } else {
+            self.cursor.get_u8() as usize
+        }
+    }
+
     #[inline]
     pub fn read_offset(&mut self) -> i16 {
         self.cursor.get_i16()
@@ -51,6 +71,11 @@ impl<'a> BytecodeCursor<'a> {
         self.cursor.position()
     }
 
+    #[inline]
+    pub fn is_wide(&self) -> bool {
+        self.is_wide
+    }
+
     #[inline]
     pub fn last_instruction_offset(&self) -> u64 {
         self.last_instruction_offset
@@ -0,0 +1,465 @@
+use crate::{Bytecode, ConstantValue, Instruction};
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt::Display};
+
+impl Bytecode {
+    /// Disassemble the bytecode, along with every nested function, into a structured form.
+    ///
+    /// The result can be printed for people to read, or serialized to JSON for tooling.
+    pub fn disassemble(&self) -> Disassembly {
+        Disassembler::new(self).disassemble()
+    }
+}
+
+/// The disassembly of a single function and, recursively, every function it defines.
+#[derive(Debug, Clone, Serialize)]
+pub struct Disassembly {
+    pub name: String,
+    pub slot_count: usize,
+    pub upvalue_count: usize,
+    pub instructions: Vec<DisassembledInstruction>,
+    pub labels: Vec<Label>,
+    /// The text of every source line referenced by the instructions, keyed by its one-based line number.
+    pub source_lines: BTreeMap<usize, String>,
+    pub constants: Vec<String>,
+    pub functions: Vec<Disassembly>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DisassembledInstruction {
+    pub offset: u64,
+    pub opcode: u8,
+    pub name: &'static str,
+    pub is_wide: bool,
+    pub operands: Vec<Operand>,
+    /// The one-based line of source the instruction was compiled from, if known.
+    pub line: Option<usize>,
+}
+
+/// A named jump target.  Labels are numbered in the order they appear in the code.
+#[derive(Debug, Clone, Serialize)]
+pub struct Label {
+    pub name: String,
+    pub offset: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Operand {
+    Constant { index: usize, value: String },
+    Slot { index: usize },
+    Upvalue { index: usize },
+    Cache { index: usize },
+    Count { value: usize },
+    Inclusive { value: bool },
+    Target { label: String, offset: u64 },
+    Capture { is_parent_local: bool, index: usize },
+}
+
+impl Disassembly {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Disassembly should always be serializable.")
+    }
+}
+
+impl Display for Disassembly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Function: {:?} (slots={}, upvalues={})",
+            self.name, self.slot_count, self.upvalue_count
+        )?;
+        writeln!(f, "--------")?;
+
+        let labels = self
+            .labels
+            .iter()
+            .map(|label| (label.offset, label.name.as_str()))
+            .collect::<BTreeMap<_, _>>();
+        let mut current_line = None;
+
+        for instruction in &self.instructions {
+            if instruction.line.is_some() && instruction.line != current_line {
+                current_line = instruction.line;
+
+                if let Some((line, text)) = instruction.line.and_then(|line| self.source_lines.get_key_value(&line)) {
+                    writeln!(f, "{:6} | {}: {}", "", line, text)?;
+                }
+            }
+
+            if let Some(label) = labels.get(&instruction.offset) {
+                writeln!(f, "{}:", label)?;
+            }
+
+            let name = if instruction.is_wide {
+                format!("{} (WIDE)", instruction.name)
+            } else {
+                instruction.name.to_string()
+            };
+            let operands = instruction
+                .operands
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            let line = format!("{:6} | {:<24} | {}", instruction.offset, name, operands);
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        // NOTE: Jumps to the very end of the code have no instruction to attach their label to.
+        for (offset, label) in labels.range(self.instructions.last().map_or(0, |last| last.offset + 1)..) {
+            writeln!(f, "{}: (end={})", label, offset)?;
+        }
+
+        writeln!(f)?;
+
+        if !self.constants.is_empty() {
+            writeln!(f, "Constants")?;
+            writeln!(f, "--------")?;
+
+            for (index, constant) in self.constants.iter().enumerate() {
+                writeln!(f, "{:6} | {}", index, constant)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        for function in &self.functions {
+            function.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Operand {
+    // NOTE: Jump offsets are relative to the end of the jump.  The label is filled in once every jump is known.
+    fn target(jump_offset: i16, position: u64) -> Self {
+        Operand::Target {
+            label: String::new(),
+            offset: position.wrapping_add(jump_offset as u64),
+        }
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operand::Constant { index, value } => write!(f, "const={} ({})", index, value),
+            Operand::Slot { index } => write!(f, "slot={}", index),
+            Operand::Upvalue { index } => write!(f, "upvalue={}", index),
+            Operand::Cache { index } => write!(f, "cache={}", index),
+            Operand::Count { value } => write!(f, "count={}", value),
+            Operand::Inclusive { value } => write!(f, "inclusive={}", value),
+            Operand::Target { label, .. } => write!(f, "{}", label),
+            Operand::Capture {
+                is_parent_local: true,
+                index,
+            } => write!(f, "(parent_local={})", index),
+            Operand::Capture { index, .. } => write!(f, "(upvalue={})", index),
+        }
+    }
+}
+
+struct Disassembler<'a> {
+    bytecode: &'a Bytecode,
+    source: Vec<&'a str>,
+}
+
+impl<'a> Disassembler<'a> {
+    fn new(bytecode: &'a Bytecode) -> Self {
+        Self {
+            bytecode,
+            source: bytecode.source().source().lines().collect(),
+        }
+    }
+
+    fn disassemble(&self) -> Disassembly {
+        let mut instructions = Vec::new();
+        let mut cursor = self.bytecode.cursor();
+
+        while let Some(instruction) = cursor.read_instruction() {
+            let offset = cursor.last_instruction_offset();
+            let mut operands = Vec::new();
+
+            match instruction {
+                Instruction::PushConst
+                | Instruction::LoadModule
+                | Instruction::LoadGlobal
+                | Instruction::StoreGlobal
+                | Instruction::StoreMethod
+                | Instruction::InheritClass
+                | Instruction::AssertTypeAndReturn
+                | Instruction::AssertTypeOrNullAndReturn => operands.push(self.constant(cursor.read_arg())),
+                Instruction::LoadLocal
+                | Instruction::StoreLocal
+                | Instruction::AssignLocal
+                | Instruction::CloseUpvalue => operands.push(Operand::Slot {
+                    index: cursor.read_arg(),
+                }),
+                Instruction::LoadUpvalue | Instruction::StoreUpvalue | Instruction::AssignUpvalue => {
+                    operands.push(Operand::Upvalue {
+                        index: cursor.read_arg(),
+                    })
+                }
+                Instruction::Dup
+                | Instruction::CreateArray
+                | Instruction::Call
+                | Instruction::TailCall
+                | Instruction::CallSuper => operands.push(Operand::Count {
+                    value: cursor.read_arg(),
+                }),
+                Instruction::LoadSlice => operands.push(Operand::Inclusive {
+                    value: cursor.read_u8() != 0,
+                }),
+                Instruction::Jump | Instruction::JumpIfFalse | Instruction::JumpIfTrue => {
+                    operands.push(Operand::target(cursor.read_offset(), cursor.position()));
+                }
+                Instruction::LoadField
+                | Instruction::StoreField
+                | Instruction::AssignField
+                | Instruction::LoadMethod => {
+                    operands.push(self.constant(cursor.read_arg()));
+                    operands.push(Operand::Cache {
+                        index: cursor.read_arg(),
+                    });
+                }
+                Instruction::LoadFieldToLocal => {
+                    operands.push(self.constant(cursor.read_arg()));
+                    operands.push(Operand::Slot {
+                        index: cursor.read_arg(),
+                    });
+                    operands.push(Operand::Cache {
+                        index: cursor.read_arg(),
+                    });
+                }
+                Instruction::LoadLocalLoadLocal => {
+                    operands.push(Operand::Slot {
+                        index: cursor.read_arg(),
+                    });
+                    operands.push(Operand::Slot {
+                        index: cursor.read_arg(),
+                    });
+                }
+                Instruction::AddLocalConst => {
+                    operands.push(Operand::Slot {
+                        index: cursor.read_arg(),
+                    });
+                    operands.push(self.constant(cursor.read_arg()));
+                }
+                Instruction::JumpUnlessLessThanLocal => {
+                    operands.push(Operand::Slot {
+                        index: cursor.read_arg(),
+                    });
+                    operands.push(self.constant(cursor.read_arg()));
+
+                    operands.push(Operand::target(cursor.read_offset(), cursor.position()));
+                }
+                Instruction::AssertTypeForLocal | Instruction::AssertTypeOrNullForLocal => {
+                    operands.push(self.constant(cursor.read_arg()));
+                    operands.push(Operand::Slot {
+                        index: cursor.read_arg(),
+                    });
+                }
+                Instruction::CreateClosure => {
+                    let const_index = cursor.read_arg();
+                    operands.push(self.constant(const_index));
+
+                    if let ConstantValue::Function(function) = &self.bytecode.constants()[const_index] {
+                        for _ in 0..function.bytecode.upvalue_count() {
+                            let is_parent_local = cursor.read_u8() == 1;
+
+                            operands.push(Operand::Capture {
+                                is_parent_local,
+                                index: cursor.read_arg(),
+                            });
+                        }
+                    }
+                }
+                _ => (),
+            }
+
+            instructions.push(DisassembledInstruction {
+                offset,
+                opcode: instruction.into(),
+                name: instruction.name(),
+                is_wide: cursor.is_wide(),
+                operands,
+                line: self.line(offset),
+            });
+        }
+
+        let mut label_offsets = instructions
+            .iter()
+            .flat_map(|instruction| &instruction.operands)
+            .filter_map(|operand| match operand {
+                Operand::Target { offset, .. } => Some(*offset),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        label_offsets.sort_unstable();
+        label_offsets.dedup();
+
+        let labels = label_offsets
+            .into_iter()
+            .enumerate()
+            .map(|(index, offset)| Label {
+                name: format!("L{}", index),
+                offset,
+            })
+            .collect::<Vec<_>>();
+
+        for operand in instructions
+            .iter_mut()
+            .flat_map(|instruction| &mut instruction.operands)
+        {
+            if let Operand::Target { label, offset } = operand {
+                if let Some(target) = labels.iter().find(|target| target.offset == *offset) {
+                    label.clone_from(&target.name);
+                }
+            }
+        }
+
+        Disassembly {
+            name: self.bytecode.name().to_owned(),
+            slot_count: self.bytecode.slot_count(),
+            upvalue_count: self.bytecode.upvalue_count(),
+            source_lines: instructions
+                .iter()
+                .filter_map(|instruction| instruction.line)
+                .map(|line| (line, self.source[line - 1].trim().to_owned()))
+                .collect(),
+            instructions,
+            labels,
+            constants: self.bytecode.constants().iter().map(ToString::to_string).collect(),
+            functions: self
+                .bytecode
+                .constants()
+                .iter()
+                .filter_map(|constant| match constant {
+                    ConstantValue::Function(function) => Some(function.bytecode.disassemble()),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+
+    fn constant(&self, index: usize) -> Operand {
+        let value = self
+            .bytecode
+            .constants()
+            .get(index)
+            .map_or_else(|| String::from("<invalid>"), ToString::to_string);
+
+        Operand::Constant { index, value }
+    }
+
+    fn line(&self, offset: u64) -> Option<usize> {
+        let span = self.bytecode.source_map().get(&offset)?;
+        let line = self.bytecode.source().line_index().position_of(span.start).line;
+
+        self.source.get(line).map(|_| line + 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FunctionBytecode;
+    use dice_core::{
+        source::{Source, SourceKind},
+        span::Span,
+    };
+    use std::collections::HashMap;
+
+    fn op(instruction: Instruction) -> u8 {
+        instruction.into()
+    }
+
+    #[test]
+    fn test_jumps_resolve_to_labels_and_lines() {
+        // if true { 1 } else { 0 }
+        let data = vec![
+            op(Instruction::PushTrue),
+            op(Instruction::JumpIfFalse),
+            0,
+            4,
+            op(Instruction::PushI1),
+            op(Instruction::Jump),
+            0,
+            1,
+            op(Instruction::PushI0),
+            op(Instruction::Return),
+        ];
+        let source = Source::new("if true\n{ 1 } else { 0 }", SourceKind::Script);
+        let source_map = vec![(0, Span::new(3..7)), (4, Span::new(10..11))]
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+        let bytecode = Bytecode::new(
+            data.into_boxed_slice(),
+            0,
+            0,
+            0,
+            Box::new([]),
+            "<script>",
+            source,
+            source_map,
+        );
+        let disassembly = bytecode.disassemble();
+
+        assert_eq!(2, disassembly.labels.len());
+        assert_eq!(8, disassembly.labels[0].offset);
+        assert_eq!(9, disassembly.labels[1].offset);
+        assert_eq!(Some(1), disassembly.instructions[0].line);
+        assert_eq!(Some(2), disassembly.instructions[2].line);
+        assert_eq!(
+            Some("{ 1 } else { 0 }"),
+            disassembly.source_lines.get(&2).map(String::as_str)
+        );
+
+        let text = disassembly.to_string();
+        assert!(text.contains("JUMP_IF_FALSE            | L0"));
+        assert!(text.contains("L1:\n     9 | RETURN"));
+
+        let json = disassembly.to_json();
+        assert!(json.contains(r#""kind": "target""#));
+        assert!(json.contains(r#""label": "L1""#));
+    }
+
+    #[test]
+    fn test_nested_functions_are_disassembled() {
+        let source = Source::new("", SourceKind::Script);
+        let function = Bytecode::new(
+            vec![op(Instruction::PushUnit), op(Instruction::Return)].into_boxed_slice(),
+            0,
+            0,
+            0,
+            Box::new([]),
+            "inner",
+            source.clone(),
+            HashMap::new(),
+        );
+        let constants = vec![ConstantValue::Function(FunctionBytecode::new(
+            function,
+            "inner",
+            uuid::Uuid::new_v4(),
+        ))];
+        let data = vec![op(Instruction::CreateClosure), 0, op(Instruction::Return)];
+        let bytecode = Bytecode::new(
+            data.into_boxed_slice(),
+            0,
+            0,
+            0,
+            constants.into_boxed_slice(),
+            "<script>",
+            source,
+            HashMap::new(),
+        );
+        let disassembly = bytecode.disassemble();
+
+        assert_eq!(1, disassembly.functions.len());
+        assert_eq!("inner", disassembly.functions[0].name);
+        assert!(disassembly.to_string().contains("Function: \"inner\""));
+    }
+}
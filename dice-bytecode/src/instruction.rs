@@ -20,6 +20,7 @@ pub enum Instruction {
     // Compound stack manipulation instructions.
     CreateArray,
     CreateObject,
+    CreateMap,
     CreateClosure,
     InheritClass,
     // NOTE: There's no concept of "storing" a module.
@@ -36,6 +37,8 @@ pub enum Instruction {
     LoadIndex,
     StoreIndex,
     AssignIndex,
+    // NOTE: Loads a slice of the target, with null start or end values marking an open bound.
+    LoadSlice,
     LoadUpvalue,
     StoreUpvalue,
     AssignUpvalue,
@@ -69,6 +72,9 @@ pub enum Instruction {
     JumpIfFalse,
     JumpIfTrue,
     Call,
+    // NOTE: Calls a function in tail position.  Script functions reuse the caller's stack frame, anything else is
+    // called normally and returns through the instructions that follow.
+    TailCall,
     // NOTE: This instruction is used to call the super class constructor of a class.
     CallSuper,
     Return,
@@ -78,6 +84,14 @@ pub enum Instruction {
     AssertTypeOrNullForLocal,
     AssertTypeAndReturn,
     AssertTypeOrNullAndReturn,
+    // Superinstructions, fused from common sequences by the peephole optimizer.
+    LoadLocalLoadLocal,
+    AddLocalConst,
+    // NOTE: Compares a local against a constant, jumping when the local is not less than the constant.  This is the
+    // exit test of a counting loop.
+    JumpUnlessLessThanLocal,
+    // NOTE: Prefix that widens the index and count operands of the following instruction from 8 to 32 bits.
+    Wide,
 }
 
 impl From<u8> for Instruction {
@@ -92,13 +106,10 @@ impl Into<u8> for Instruction {
     }
 }
 
-impl Display for Instruction {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let value: u8 = (*self).into();
-
-        write!(f, "{:02X} | ", value)?;
-
-        let name = match self {
+impl Instruction {
+    /// The mnemonic used for the instruction in disassembly.
+    pub fn name(self) -> &'static str {
+        match self {
             Instruction::PushNull => "PUSH_NULL",
             Instruction::PushUnit => "PUSH_UNIT",
             Instruction::PushFalse => "PUSH_FALSE",
@@ -113,6 +124,7 @@ impl Display for Instruction {
             Instruction::Swap => "SWAP",
             Instruction::CreateArray => "CREATE_ARRAY",
             Instruction::CreateObject => "CREATE_OBJECT",
+            Instruction::CreateMap => "CREATE_MAP",
             Instruction::CreateClosure => "CREATE_CLOSURE",
             Instruction::InheritClass => "INHERIT_CLASS",
             Instruction::LoadModule => "LOAD_MODULE",
@@ -127,6 +139,7 @@ impl Display for Instruction {
             Instruction::LoadIndex => "LOAD_INDEX",
             Instruction::StoreIndex => "STORE_INDEX",
             Instruction::AssignIndex => "ASSIGN_INDEX",
+            Instruction::LoadSlice => "LOAD_SLICE",
             Instruction::LoadUpvalue => "LOAD_UPVALUE",
             Instruction::StoreUpvalue => "STORE_UPVALUE",
             Instruction::AssignUpvalue => "ASSIGN_UPVALUE",
@@ -154,6 +167,7 @@ impl Display for Instruction {
             Instruction::JumpIfFalse => "JUMP_IF_FALSE",
             Instruction::JumpIfTrue => "JUMP_IF_TRUE",
             Instruction::Call => "CALL",
+            Instruction::TailCall => "TAIL_CALL",
             Instruction::CallSuper => "CALL_SUPER",
             Instruction::Return => "RETURN",
             Instruction::AssertBool => "ASSERT_BOOL",
@@ -161,8 +175,18 @@ impl Display for Instruction {
             Instruction::AssertTypeOrNullForLocal => "ASSERT_TYPE_OR_NULL_FOR_LOCAL",
             Instruction::AssertTypeAndReturn => "ASSERT_TYPE_AND_RETURN",
             Instruction::AssertTypeOrNullAndReturn => "ASSERT_TYPE_OR_NULL_AND_RETURN",
-        };
+            Instruction::LoadLocalLoadLocal => "LOAD_LOCAL_LOAD_LOCAL",
+            Instruction::AddLocalConst => "ADD_LOCAL_CONST",
+            Instruction::JumpUnlessLessThanLocal => "JUMP_UNLESS_LT_LOCAL",
+            Instruction::Wide => "WIDE",
+        }
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value: u8 = (*self).into();
 
-        write!(f, "{}", name)
+        write!(f, "{:02X} | {}", value, self.name())
     }
 }
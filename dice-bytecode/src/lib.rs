@@ -1,21 +1,34 @@
 extern crate core;
 
-use std::{fmt::Display, rc::Rc};
+use std::{collections::HashMap, fmt::Display, rc::Rc};
+
+use dice_core::{error::trace::ErrorTrace, source::Source, span::Span};
 
 pub use cursor::BytecodeCursor;
+pub use disassembler::{DisassembledInstruction, Disassembly, Label, Operand};
 pub use instruction::Instruction;
+pub use register::{Register, RegisterBytecode, RegisterInstruction};
+pub use serialization::{BYTECODE_FORMAT_VERSION, BYTECODE_MAGIC};
+pub use type_shape::{TypeShape, TypeShapeKind};
 
 mod cursor;
+mod disassembler;
 mod instruction;
+mod register;
+mod serialization;
+mod type_shape;
+mod verifier;
 
 #[derive(Debug)]
 struct BytecodeInner {
     slot_count: usize,
     upvalue_count: usize,
+    cache_count: usize,
     constants: Box<[ConstantValue]>,
     data: Box<[u8]>,
-    // source: Source,
-    // source_map: HashMap<u64, Span>,
+    name: String,
+    source: Source,
+    source_map: HashMap<u64, Span>,
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +44,7 @@ pub enum ConstantValue {
     String(String),
     Symbol(String),
     Function(FunctionBytecode),
+    TypeShape(TypeShape),
 }
 
 #[derive(Debug, Clone)]
@@ -58,35 +72,64 @@ impl PartialEq for FunctionBytecode {
     }
 }
 
+impl Display for ConstantValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConstantValue::Int(value) => write!(f, "{}", value),
+            ConstantValue::Float(value) => write!(f, "{}", value),
+            ConstantValue::String(value) => write!(f, "{:?}", value),
+            ConstantValue::Symbol(value) => write!(f, "#{}", value),
+            ConstantValue::Function(function) => write!(f, "fn {}", function.name),
+            ConstantValue::TypeShape(shape) => write!(f, "{}", shape),
+        }
+    }
+}
+
 impl Bytecode {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         data: Box<[u8]>,
         slot_count: usize,
         upvalue_count: usize,
+        cache_count: usize,
         constants: Box<[ConstantValue]>,
-        // source: Source,
-        // source_map: HashMap<u64, Span>,
+        name: impl Into<String>,
+        source: Source,
+        source_map: HashMap<u64, Span>,
     ) -> Self {
         Self {
             inner: Rc::new(BytecodeInner {
                 constants,
                 slot_count,
                 upvalue_count,
-                // source,
-                // source_map,
+                cache_count,
+                name: name.into(),
+                source,
+                source_map,
                 data,
             }),
         }
     }
 
-    // pub fn source(&self) -> &Source {
-    //     &self.inner.source
-    // }
+    /// The name of the function the bytecode was compiled from.
+    pub fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    pub fn source(&self) -> &Source {
+        &self.inner.source
+    }
+
+    pub fn source_map(&self) -> &HashMap<u64, Span> {
+        &self.inner.source_map
+    }
+
+    /// Build a trace entry pointing at the source of the instruction at the given offset.
+    pub fn trace(&self, offset: u64) -> ErrorTrace {
+        let span = self.source_map().get(&offset).copied().unwrap_or_else(Span::empty);
 
-    // #[allow(dead_code)]
-    // pub fn source_map(&self) -> &HashMap<u64, Span> {
-    //     &self.inner.source_map
-    // }
+        ErrorTrace::new(self.name(), self.source().clone(), span)
+    }
 
     pub fn constants(&self) -> &[ConstantValue] {
         &self.inner.constants
@@ -103,86 +146,15 @@ impl Bytecode {
     pub fn upvalue_count(&self) -> usize {
         self.inner.upvalue_count
     }
+
+    /// The number of inline caches the field and method instructions of the function refer to by index.
+    pub fn cache_count(&self) -> usize {
+        self.inner.cache_count
+    }
 }
 
 impl Display for Bytecode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "Code")?;
-        writeln!(f, "--------")?;
-
-        let mut cursor = self.cursor();
-        let mut position = 0;
-
-        while let Some(instruction) = cursor.read_instruction() {
-            write!(f, "{:6} | {:<24} | ", position, format!("{}", instruction))?;
-
-            match instruction {
-                Instruction::Jump | Instruction::JumpIfFalse | Instruction::JumpIfTrue => {
-                    write!(f, "offset={}", cursor.read_offset())?
-                }
-                Instruction::PushConst
-                | Instruction::Dup
-                | Instruction::LoadModule
-                | Instruction::LoadGlobal
-                | Instruction::LoadLocal
-                | Instruction::LoadField
-                | Instruction::LoadMethod
-                | Instruction::LoadUpvalue
-                | Instruction::StoreGlobal
-                | Instruction::StoreLocal
-                | Instruction::StoreField
-                | Instruction::StoreMethod
-                | Instruction::StoreUpvalue
-                | Instruction::AssignLocal
-                | Instruction::AssignField
-                | Instruction::AssignUpvalue
-                | Instruction::CloseUpvalue
-                | Instruction::Call
-                | Instruction::CallSuper
-                | Instruction::CreateArray
-                | Instruction::InheritClass
-                | Instruction::AssertTypeForLocal
-                | Instruction::AssertTypeOrNullForLocal => write!(f, "const={}", cursor.read_u8())?,
-                Instruction::LoadFieldToLocal => write!(f, "const={} slot={}", cursor.read_u8(), cursor.read_u8())?,
-                Instruction::CreateClosure => {
-                    let const_index = cursor.read_u8() as usize;
-                    let function = &self.constants()[const_index];
-
-                    match function {
-                        ConstantValue::Function(function) => {
-                            write!(f, "const={:<8} |", const_index)?;
-
-                            for _ in 0..function.bytecode.upvalue_count() {
-                                let kind = match cursor.read_u8() {
-                                    1 => "parent_local",
-                                    _ => "upvalue",
-                                };
-                                let index = cursor.read_u8() as usize;
-
-                                write!(f, " ({}={})", kind, index)?;
-                            }
-                        }
-                        _ => write!(f, "NOT A FUNCTION!")?,
-                    }
-                }
-                _ => (),
-            }
-
-            position = cursor.position();
-
-            writeln!(f)?;
-        }
-
-        writeln!(f)?;
-
-        for const_value in self.constants() {
-            if let ConstantValue::Function(function) = const_value {
-                writeln!(f, "Function: {:?}", function.name)?;
-                writeln!(f, "--------")?;
-                function.bytecode.fmt(f)?;
-            }
-        }
-
-        Ok(())
+        self.disassemble().fmt(f)
     }
 }
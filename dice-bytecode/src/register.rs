@@ -0,0 +1,233 @@
+use std::{collections::HashMap, fmt::Display, rc::Rc};
+
+use dice_core::{error::trace::ErrorTrace, source::Source, span::Span};
+
+use crate::ConstantValue;
+
+pub type Register = u16;
+
+/// An instruction for the register machine.  Operands name registers directly, so values move between locals and
+/// temporaries without passing through the stack.
+// NOTE: Jump targets are absolute instruction indices.
+#[derive(Clone, Copy, Debug)]
+pub enum RegisterInstruction {
+    LoadNull {
+        dst: Register,
+    },
+    LoadUnit {
+        dst: Register,
+    },
+    LoadBool {
+        dst: Register,
+        value: bool,
+    },
+    LoadConst {
+        dst: Register,
+        index: u32,
+    },
+    Move {
+        dst: Register,
+        src: Register,
+    },
+    Negate {
+        dst: Register,
+        src: Register,
+    },
+    Not {
+        dst: Register,
+        src: Register,
+    },
+    Multiply {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    Divide {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    Remainder {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    Add {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    Subtract {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    GreaterThan {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    GreaterThanOrEqual {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    LessThan {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    LessThanOrEqual {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    Equal {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    NotEqual {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    AssertBool {
+        src: Register,
+    },
+    Jump {
+        target: u32,
+    },
+    JumpIfFalse {
+        src: Register,
+        target: u32,
+    },
+    JumpIfTrue {
+        src: Register,
+        target: u32,
+    },
+    JumpIfNotNull {
+        src: Register,
+        target: u32,
+    },
+    Return {
+        src: Register,
+    },
+}
+
+impl Display for RegisterInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            RegisterInstruction::LoadNull { dst } => write!(f, "LOAD_NULL r{}", dst),
+            RegisterInstruction::LoadUnit { dst } => write!(f, "LOAD_UNIT r{}", dst),
+            RegisterInstruction::LoadBool { dst, value } => write!(f, "LOAD_BOOL r{}, {}", dst, value),
+            RegisterInstruction::LoadConst { dst, index } => write!(f, "LOAD_CONST r{}, const={}", dst, index),
+            RegisterInstruction::Move { dst, src } => write!(f, "MOVE r{}, r{}", dst, src),
+            RegisterInstruction::Negate { dst, src } => write!(f, "NEG r{}, r{}", dst, src),
+            RegisterInstruction::Not { dst, src } => write!(f, "NOT r{}, r{}", dst, src),
+            RegisterInstruction::Multiply { dst, lhs, rhs } => write!(f, "MUL r{}, r{}, r{}", dst, lhs, rhs),
+            RegisterInstruction::Divide { dst, lhs, rhs } => write!(f, "DIV r{}, r{}, r{}", dst, lhs, rhs),
+            RegisterInstruction::Remainder { dst, lhs, rhs } => write!(f, "REM r{}, r{}, r{}", dst, lhs, rhs),
+            RegisterInstruction::Add { dst, lhs, rhs } => write!(f, "ADD r{}, r{}, r{}", dst, lhs, rhs),
+            RegisterInstruction::Subtract { dst, lhs, rhs } => write!(f, "SUB r{}, r{}, r{}", dst, lhs, rhs),
+            RegisterInstruction::GreaterThan { dst, lhs, rhs } => write!(f, "GT r{}, r{}, r{}", dst, lhs, rhs),
+            RegisterInstruction::GreaterThanOrEqual { dst, lhs, rhs } => write!(f, "GTE r{}, r{}, r{}", dst, lhs, rhs),
+            RegisterInstruction::LessThan { dst, lhs, rhs } => write!(f, "LT r{}, r{}, r{}", dst, lhs, rhs),
+            RegisterInstruction::LessThanOrEqual { dst, lhs, rhs } => write!(f, "LTE r{}, r{}, r{}", dst, lhs, rhs),
+            RegisterInstruction::Equal { dst, lhs, rhs } => write!(f, "EQ r{}, r{}, r{}", dst, lhs, rhs),
+            RegisterInstruction::NotEqual { dst, lhs, rhs } => write!(f, "NEQ r{}, r{}, r{}", dst, lhs, rhs),
+            RegisterInstruction::AssertBool { src } => write!(f, "ASSERT_BOOL r{}", src),
+            RegisterInstruction::Jump { target } => write!(f, "JUMP {}", target),
+            RegisterInstruction::JumpIfFalse { src, target } => write!(f, "JUMP_IF_FALSE r{}, {}", src, target),
+            RegisterInstruction::JumpIfTrue { src, target } => write!(f, "JUMP_IF_TRUE r{}, {}", src, target),
+            RegisterInstruction::JumpIfNotNull { src, target } => write!(f, "JUMP_IF_NOT_NULL r{}, {}", src, target),
+            RegisterInstruction::Return { src } => write!(f, "RETURN r{}", src),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct RegisterBytecodeInner {
+    instructions: Box<[RegisterInstruction]>,
+    register_count: usize,
+    constants: Box<[ConstantValue]>,
+    name: String,
+    source: Source,
+    source_map: HashMap<usize, Span>,
+}
+
+/// Code for the register machine, an alternative to the stack based [`Bytecode`](crate::Bytecode).
+#[derive(Debug, Clone)]
+pub struct RegisterBytecode {
+    inner: Rc<RegisterBytecodeInner>,
+}
+
+impl RegisterBytecode {
+    pub fn new(
+        instructions: Box<[RegisterInstruction]>,
+        register_count: usize,
+        constants: Box<[ConstantValue]>,
+        name: impl Into<String>,
+        source: Source,
+        source_map: HashMap<usize, Span>,
+    ) -> Self {
+        Self {
+            inner: Rc::new(RegisterBytecodeInner {
+                instructions,
+                register_count,
+                constants,
+                name: name.into(),
+                source,
+                source_map,
+            }),
+        }
+    }
+
+    pub fn instructions(&self) -> &[RegisterInstruction] {
+        &self.inner.instructions
+    }
+
+    pub fn register_count(&self) -> usize {
+        self.inner.register_count
+    }
+
+    pub fn constants(&self) -> &[ConstantValue] {
+        &self.inner.constants
+    }
+
+    pub fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    pub fn source(&self) -> &Source {
+        &self.inner.source
+    }
+
+    /// Build a trace entry pointing at the source of the instruction at the given index.
+    pub fn trace(&self, index: usize) -> ErrorTrace {
+        let span = self.inner.source_map.get(&index).copied().unwrap_or_else(Span::empty);
+
+        ErrorTrace::new(self.name(), self.source().clone(), span)
+    }
+}
+
+impl Display for RegisterBytecode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Registers: {}", self.register_count())?;
+        writeln!(f, "--------")?;
+
+        for (index, instruction) in self.instructions().iter().enumerate() {
+            writeln!(f, "{:6} | {}", index, instruction)?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Constants")?;
+        writeln!(f, "--------")?;
+
+        for (index, constant) in self.constants().iter().enumerate() {
+            writeln!(f, "{:6} | {}", index, constant)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,528 @@
+use crate::{Bytecode, ConstantValue, FunctionBytecode, TypeShape, TypeShapeKind};
+use bytes::BufMut as _;
+use dice_core::{
+    error::{
+        codes::{BYTECODE_CHECKSUM_MISMATCH, BYTECODE_VERSION_MISMATCH, INVALID_BYTECODE_FORMAT},
+        Error,
+    },
+    source::{Source, SourceKind},
+    span::Span,
+    tags,
+};
+use std::collections::HashMap;
+
+/// The magic number every serialized bytecode file starts with.
+pub const BYTECODE_MAGIC: [u8; 4] = *b"DICE";
+/// The version of the serialized format.  Bump this any time the layout or the instruction set changes.
+pub const BYTECODE_FORMAT_VERSION: u16 = 5;
+
+const HEADER_LEN: usize = BYTECODE_MAGIC.len() + 2 + 4;
+
+const CONST_INT: u8 = 0;
+const CONST_FLOAT: u8 = 1;
+const CONST_STRING: u8 = 2;
+const CONST_SYMBOL: u8 = 3;
+const CONST_FUNCTION: u8 = 4;
+const CONST_TYPE_SHAPE: u8 = 5;
+
+const SHAPE_CLASS: u8 = 0;
+const SHAPE_ARRAY: u8 = 1;
+const SHAPE_FUNCTION: u8 = 2;
+const SHAPE_UNION: u8 = 3;
+
+const SOURCE_MODULE: u8 = 0;
+const SOURCE_SCRIPT: u8 = 1;
+
+// NOTE: Functions and type shapes are read recursively, so their nesting is capped to keep malformed input from
+// overflowing the native stack.
+const MAX_NESTING_DEPTH: usize = 128;
+
+impl Bytecode {
+    /// Serialize the bytecode, along with all nested functions and the source it was compiled from.
+    ///
+    /// The layout is a header of the magic number, format version and a CRC32 checksum of the payload, followed by
+    /// the payload itself.  All integers are stored big-endian.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut payload = Vec::new();
+
+        // NOTE: Every function in a compilation unit shares the same source, so it's only written once.
+        write_source(&mut payload, self.source());
+        write_function(&mut payload, self);
+
+        let mut buffer = Vec::with_capacity(HEADER_LEN + payload.len());
+        buffer.put_slice(&BYTECODE_MAGIC);
+        buffer.put_u16(BYTECODE_FORMAT_VERSION);
+        buffer.put_u32(crc32fast::hash(&payload));
+        buffer.put_slice(&payload);
+
+        buffer
+    }
+
+    /// Deserialize bytecode previously produced by [`Bytecode::to_bytes`].
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        let mut reader = Reader::new(data);
+
+        if reader.read_slice(BYTECODE_MAGIC.len())? != BYTECODE_MAGIC {
+            return Err(Error::new(INVALID_BYTECODE_FORMAT));
+        }
+
+        let version = reader.read_u16()?;
+
+        if version != BYTECODE_FORMAT_VERSION {
+            return Err(Error::new(BYTECODE_VERSION_MISMATCH).with_tags(tags! {
+                expected => BYTECODE_FORMAT_VERSION.to_string(),
+                actual => version.to_string()
+            }));
+        }
+
+        let checksum = reader.read_u32()?;
+
+        if crc32fast::hash(reader.remaining()) != checksum {
+            return Err(Error::new(BYTECODE_CHECKSUM_MISMATCH));
+        }
+
+        let source = reader.read_source()?;
+        let bytecode = reader.read_function(&source)?;
+
+        if !reader.remaining().is_empty() {
+            return Err(Error::new(INVALID_BYTECODE_FORMAT));
+        }
+
+        Ok(bytecode)
+    }
+}
+
+fn write_len(buffer: &mut Vec<u8>, len: usize) {
+    buffer.put_u32(len as u32);
+}
+
+fn write_str(buffer: &mut Vec<u8>, value: &str) {
+    write_len(buffer, value.len());
+    buffer.put_slice(value.as_bytes());
+}
+
+fn write_source(buffer: &mut Vec<u8>, source: &Source) {
+    buffer.put_u8(match source.kind() {
+        SourceKind::Module => SOURCE_MODULE,
+        SourceKind::Script => SOURCE_SCRIPT,
+    });
+
+    match source.path() {
+        Some(path) => {
+            buffer.put_u8(1);
+            write_str(buffer, path);
+        }
+        None => buffer.put_u8(0),
+    }
+
+    write_str(buffer, source.source());
+}
+
+fn write_function(buffer: &mut Vec<u8>, bytecode: &Bytecode) {
+    write_str(buffer, bytecode.name());
+    write_len(buffer, bytecode.slot_count());
+    write_len(buffer, bytecode.upvalue_count());
+    write_len(buffer, bytecode.cache_count());
+    write_len(buffer, bytecode.inner.data.len());
+    buffer.put_slice(&bytecode.inner.data);
+
+    // NOTE: The source map is sorted so that the same bytecode always serializes to the same bytes.
+    let mut source_map = bytecode.source_map().iter().collect::<Vec<_>>();
+    source_map.sort_unstable_by_key(|(offset, _)| **offset);
+
+    write_len(buffer, source_map.len());
+
+    for (offset, span) in source_map {
+        buffer.put_u64(*offset);
+        buffer.put_u64(span.start as u64);
+        buffer.put_u64(span.end as u64);
+    }
+
+    write_len(buffer, bytecode.constants().len());
+
+    for constant in bytecode.constants() {
+        write_constant(buffer, constant);
+    }
+}
+
+fn write_constant(buffer: &mut Vec<u8>, constant: &ConstantValue) {
+    match constant {
+        ConstantValue::Int(value) => {
+            buffer.put_u8(CONST_INT);
+            buffer.put_i64(*value);
+        }
+        ConstantValue::Float(value) => {
+            buffer.put_u8(CONST_FLOAT);
+            buffer.put_f64(*value);
+        }
+        ConstantValue::String(value) => {
+            buffer.put_u8(CONST_STRING);
+            write_str(buffer, value);
+        }
+        ConstantValue::Symbol(value) => {
+            buffer.put_u8(CONST_SYMBOL);
+            write_str(buffer, value);
+        }
+        ConstantValue::Function(function) => {
+            buffer.put_u8(CONST_FUNCTION);
+            write_str(buffer, &function.name);
+            buffer.put_slice(function.id.as_bytes());
+            write_function(buffer, &function.bytecode);
+        }
+        ConstantValue::TypeShape(shape) => {
+            buffer.put_u8(CONST_TYPE_SHAPE);
+            write_type_shape(buffer, shape);
+        }
+    }
+}
+
+fn write_type_shape(buffer: &mut Vec<u8>, shape: &TypeShape) {
+    buffer.put_u8(shape.is_nullable as u8);
+
+    match &shape.kind {
+        TypeShapeKind::Class => buffer.put_u8(SHAPE_CLASS),
+        TypeShapeKind::Array(element) => {
+            buffer.put_u8(SHAPE_ARRAY);
+            write_type_shape(buffer, element);
+        }
+        TypeShapeKind::Function => buffer.put_u8(SHAPE_FUNCTION),
+        TypeShapeKind::Union(members) => {
+            buffer.put_u8(SHAPE_UNION);
+            write_len(buffer, members.len());
+
+            for member in members {
+                write_type_shape(buffer, member);
+            }
+        }
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, depth: 0 }
+    }
+
+    fn remaining(&self) -> &'a [u8] {
+        self.data
+    }
+
+    fn read_slice(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.data.len() < len {
+            return Err(Error::new(INVALID_BYTECODE_FORMAT));
+        }
+
+        let (value, rest) = self.data.split_at(len);
+        self.data = rest;
+
+        Ok(value)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let mut value = [0; N];
+        value.copy_from_slice(self.read_slice(N)?);
+
+        Ok(value)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Error> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    fn read_bool(&mut self) -> Result<bool, Error> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(Error::new(INVALID_BYTECODE_FORMAT)),
+        }
+    }
+
+    fn read_u16(&mut self) -> Result<u16, Error> {
+        Ok(u16::from_be_bytes(self.read_array()?))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_be_bytes(self.read_array()?))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, Error> {
+        Ok(u64::from_be_bytes(self.read_array()?))
+    }
+
+    fn read_len(&mut self) -> Result<usize, Error> {
+        Ok(self.read_u32()? as usize)
+    }
+
+    fn read_string(&mut self) -> Result<String, Error> {
+        let len = self.read_len()?;
+        let value = self.read_slice(len)?;
+
+        String::from_utf8(value.to_vec()).map_err(|_| Error::new(INVALID_BYTECODE_FORMAT))
+    }
+
+    fn read_source(&mut self) -> Result<Source, Error> {
+        let kind = match self.read_u8()? {
+            SOURCE_MODULE => SourceKind::Module,
+            SOURCE_SCRIPT => SourceKind::Script,
+            _ => return Err(Error::new(INVALID_BYTECODE_FORMAT)),
+        };
+
+        let path = if self.read_bool()? {
+            Some(self.read_string()?)
+        } else {
+            None
+        };
+        let source = self.read_string()?;
+
+        Ok(match path {
+            Some(path) => Source::with_path(source, path, kind),
+            None => Source::new(source, kind),
+        })
+    }
+
+    fn read_function(&mut self, source: &Source) -> Result<Bytecode, Error> {
+        let name = self.read_string()?;
+        let slot_count = self.read_len()?;
+        let upvalue_count = self.read_len()?;
+        let cache_count = self.read_len()?;
+        let data_len = self.read_len()?;
+        let data = self.read_slice(data_len)?.into();
+
+        let source_map_len = self.read_len()?;
+        let mut source_map = HashMap::new();
+
+        for _ in 0..source_map_len {
+            let offset = self.read_u64()?;
+            let start = self.read_u64()? as usize;
+            let end = self.read_u64()? as usize;
+            let text = source.source();
+
+            // NOTE: Spans slice the source when errors are rendered, so they have to fall within it.
+            if start > end || !text.is_char_boundary(start) || !text.is_char_boundary(end) {
+                return Err(Error::new(INVALID_BYTECODE_FORMAT));
+            }
+
+            source_map.insert(offset, Span::new(start..end));
+        }
+
+        let constant_len = self.read_len()?;
+        let mut constants = Vec::new();
+
+        for _ in 0..constant_len {
+            constants.push(self.read_constant(source)?);
+        }
+
+        Ok(Bytecode::new(
+            data,
+            slot_count,
+            upvalue_count,
+            cache_count,
+            constants.into_boxed_slice(),
+            name,
+            source.clone(),
+            source_map,
+        ))
+    }
+
+    fn read_constant(&mut self, source: &Source) -> Result<ConstantValue, Error> {
+        let constant = match self.read_u8()? {
+            CONST_INT => ConstantValue::Int(i64::from_be_bytes(self.read_array()?)),
+            CONST_FLOAT => ConstantValue::Float(f64::from_be_bytes(self.read_array()?)),
+            CONST_STRING => ConstantValue::String(self.read_string()?),
+            CONST_SYMBOL => ConstantValue::Symbol(self.read_string()?),
+            CONST_FUNCTION => {
+                let name = self.read_string()?;
+                let id = uuid::Uuid::from_bytes(self.read_array()?);
+                let bytecode = self.read_nested(|reader| reader.read_function(source))?;
+
+                ConstantValue::Function(FunctionBytecode::new(bytecode, name, id))
+            }
+            CONST_TYPE_SHAPE => ConstantValue::TypeShape(self.read_type_shape()?),
+            _ => return Err(Error::new(INVALID_BYTECODE_FORMAT)),
+        };
+
+        Ok(constant)
+    }
+
+    fn read_type_shape(&mut self) -> Result<TypeShape, Error> {
+        let is_nullable = self.read_bool()?;
+        let kind = match self.read_u8()? {
+            SHAPE_CLASS => TypeShapeKind::Class,
+            SHAPE_ARRAY => TypeShapeKind::Array(Box::new(self.read_nested(Self::read_type_shape)?)),
+            SHAPE_FUNCTION => TypeShapeKind::Function,
+            SHAPE_UNION => {
+                let len = self.read_len()?;
+                let mut members = Vec::new();
+
+                for _ in 0..len {
+                    members.push(self.read_nested(Self::read_type_shape)?);
+                }
+
+                TypeShapeKind::Union(members)
+            }
+            _ => return Err(Error::new(INVALID_BYTECODE_FORMAT)),
+        };
+
+        Ok(TypeShape::new(kind, is_nullable))
+    }
+
+    fn read_nested<T>(&mut self, read: impl FnOnce(&mut Self) -> Result<T, Error>) -> Result<T, Error> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(Error::new(INVALID_BYTECODE_FORMAT));
+        }
+
+        self.depth += 1;
+        let value = read(self);
+        self.depth -= 1;
+
+        value
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bytecode() -> Bytecode {
+        let source = Source::with_path("let x = 1", "test.dm", SourceKind::Module);
+        let function = Bytecode::new(
+            vec![0, 1, 2].into_boxed_slice(),
+            1,
+            0,
+            0,
+            vec![ConstantValue::TypeShape(TypeShape::new(
+                TypeShapeKind::Union(vec![
+                    TypeShape::new(TypeShapeKind::Class, false),
+                    TypeShape::new(
+                        TypeShapeKind::Array(Box::new(TypeShape::new(TypeShapeKind::Class, true))),
+                        false,
+                    ),
+                ]),
+                true,
+            ))]
+            .into_boxed_slice(),
+            "inner",
+            source.clone(),
+            HashMap::new(),
+        );
+
+        Bytecode::new(
+            vec![8, 0, 8, 1].into_boxed_slice(),
+            2,
+            0,
+            0,
+            vec![
+                ConstantValue::Int(-42),
+                ConstantValue::Float(1.5),
+                ConstantValue::String("hello".to_owned()),
+                ConstantValue::Symbol("world".to_owned()),
+                ConstantValue::Function(FunctionBytecode::new(function, "inner", uuid::Uuid::new_v4())),
+            ]
+            .into_boxed_slice(),
+            "<module>",
+            source,
+            vec![(0, Span::new(0..3)), (2, Span::new(4..9))].into_iter().collect(),
+        )
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let bytecode = bytecode();
+        let bytes = bytecode.to_bytes();
+        let result = Bytecode::from_bytes(&bytes).unwrap();
+
+        assert_eq!(bytecode.name(), result.name());
+        assert_eq!(bytecode.constants(), result.constants());
+        assert_eq!(bytecode.source_map(), result.source_map());
+        assert_eq!(bytecode.source().path(), result.source().path());
+        assert_eq!(bytecode.source().source(), result.source().source());
+        assert_eq!(bytes, result.to_bytes());
+    }
+
+    #[test]
+    fn test_rejects_mismatched_version() {
+        let mut bytes = bytecode().to_bytes();
+        bytes[BYTECODE_MAGIC.len()..BYTECODE_MAGIC.len() + 2].copy_from_slice(&u16::MAX.to_be_bytes());
+
+        let error = Bytecode::from_bytes(&bytes).unwrap_err();
+        assert_eq!(error.error_code(), BYTECODE_VERSION_MISMATCH);
+    }
+
+    #[test]
+    fn test_rejects_corrupted_payload() {
+        let mut bytes = bytecode().to_bytes();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+
+        let error = Bytecode::from_bytes(&bytes).unwrap_err();
+        assert_eq!(error.error_code(), BYTECODE_CHECKSUM_MISMATCH);
+
+        let error = Bytecode::from_bytes(&bytes[..HEADER_LEN - 1]).unwrap_err();
+        assert_eq!(error.error_code(), INVALID_BYTECODE_FORMAT);
+    }
+
+    #[test]
+    fn test_rejects_spans_outside_the_source() {
+        let source = Source::new("let é = 1", SourceKind::Script);
+
+        // NOTE: The second span ends in the middle of the two byte character.
+        for span in [Span::new(4..11), Span::new(0..5)] {
+            let bytecode = Bytecode::new(
+                Box::new([]),
+                0,
+                0,
+                0,
+                Box::new([]),
+                "f",
+                source.clone(),
+                vec![(0, span)].into_iter().collect(),
+            );
+
+            let error = Bytecode::from_bytes(&bytecode.to_bytes()).unwrap_err();
+            assert_eq!(error.error_code(), INVALID_BYTECODE_FORMAT);
+        }
+    }
+
+    #[test]
+    fn test_rejects_deeply_nested_input() {
+        let source = Source::new("", SourceKind::Script);
+        let function = |constants: Vec<ConstantValue>| {
+            Bytecode::new(
+                Box::new([]),
+                0,
+                0,
+                0,
+                constants.into_boxed_slice(),
+                "f",
+                source.clone(),
+                HashMap::new(),
+            )
+        };
+        let array_of = |depth| {
+            (0..depth).fold(TypeShape::new(TypeShapeKind::Class, false), |shape, _| {
+                TypeShape::new(TypeShapeKind::Array(Box::new(shape)), false)
+            })
+        };
+
+        let bytes = function(vec![ConstantValue::TypeShape(array_of(MAX_NESTING_DEPTH))]).to_bytes();
+        Bytecode::from_bytes(&bytes).unwrap();
+
+        let bytes = function(vec![ConstantValue::TypeShape(array_of(MAX_NESTING_DEPTH + 1))]).to_bytes();
+        let error = Bytecode::from_bytes(&bytes).unwrap_err();
+        assert_eq!(error.error_code(), INVALID_BYTECODE_FORMAT);
+
+        let nested = (0..=MAX_NESTING_DEPTH).fold(function(Vec::new()), |inner, _| {
+            function(vec![ConstantValue::Function(FunctionBytecode::new(
+                inner,
+                "f",
+                uuid::Uuid::nil(),
+            ))])
+        });
+        let error = Bytecode::from_bytes(&nested.to_bytes()).unwrap_err();
+        assert_eq!(error.error_code(), INVALID_BYTECODE_FORMAT);
+    }
+}
@@ -0,0 +1,61 @@
+use std::fmt::{Display, Formatter};
+
+/// Describes the structure of a type annotation checked by the type assertion instructions.
+// NOTE: Classes are runtime values, so they can't be stored in the shape itself.  Instead the classes referenced by
+// a shape are pushed onto the stack in pre-order before the assertion executes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeShape {
+    pub kind: TypeShapeKind,
+    pub is_nullable: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeShapeKind {
+    Class,
+    Array(Box<TypeShape>),
+    Function,
+    Union(Vec<TypeShape>),
+}
+
+impl TypeShape {
+    pub fn new(kind: TypeShapeKind, is_nullable: bool) -> Self {
+        Self { kind, is_nullable }
+    }
+
+    /// The number of classes that must be on the stack to check a value against this shape.
+    pub fn class_count(&self) -> usize {
+        match &self.kind {
+            TypeShapeKind::Class => 1,
+            TypeShapeKind::Array(element) => element.class_count(),
+            TypeShapeKind::Function => 0,
+            TypeShapeKind::Union(members) => members.iter().map(TypeShape::class_count).sum(),
+        }
+    }
+
+    pub fn accepts_null(&self) -> bool {
+        match &self.kind {
+            TypeShapeKind::Union(members) => self.is_nullable || members.iter().any(TypeShape::accepts_null),
+            _ => self.is_nullable,
+        }
+    }
+}
+
+impl Display for TypeShape {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            TypeShapeKind::Class => write!(f, "Class")?,
+            TypeShapeKind::Array(element) => write!(f, "Array<{}>", element)?,
+            TypeShapeKind::Function => write!(f, "Function")?,
+            TypeShapeKind::Union(members) => {
+                let members = members.iter().map(ToString::to_string).collect::<Vec<_>>().join(" | ");
+                write!(f, "({})", members)?
+            }
+        }
+
+        if self.is_nullable {
+            write!(f, "?")?;
+        }
+
+        Ok(())
+    }
+}
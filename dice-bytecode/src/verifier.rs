@@ -0,0 +1,755 @@
+use crate::{Bytecode, ConstantValue, Instruction};
+use dice_core::{
+    error::{
+        codes::{
+            INCOMPLETE_INSTRUCTION, INCONSISTENT_STACK_DEPTH, INVALID_CACHE_SLOT, INVALID_CONSTANT_INDEX,
+            INVALID_CONSTANT_TYPE, INVALID_FLAG, INVALID_JUMP_TARGET, INVALID_LOCAL_SLOT, INVALID_OPCODE,
+            INVALID_UPVALUE_INDEX, STACK_UNDERFLOW,
+        },
+        Error,
+    },
+    tags,
+};
+use num_traits::FromPrimitive as _;
+use std::collections::HashMap;
+
+impl Bytecode {
+    /// Verify that the bytecode, along with every nested function, is well-formed and safe to execute.
+    ///
+    /// The interpreter trusts the bytecode it executes, so anything that didn't come straight from the compiler, such
+    /// as deserialized bytecode, should be verified first.
+    pub fn verify(&self) -> Result<(), Error> {
+        Verifier::new(self).verify()
+    }
+}
+
+struct DecodedInstruction {
+    offset: usize,
+    next: usize,
+    instruction: Instruction,
+    // NOTE: Index and count operands, along with any flags, in the order they're encoded.
+    args: Vec<usize>,
+    jump_offset: Option<i16>,
+}
+
+impl DecodedInstruction {
+    fn jump_target(&self) -> Option<i64> {
+        self.jump_offset.map(|offset| self.next as i64 + offset as i64)
+    }
+
+    fn arg(&self) -> usize {
+        self.args.first().copied().unwrap_or_default()
+    }
+}
+
+struct OperandReader<'a, 'b> {
+    verifier: &'b Verifier<'a>,
+    offset: usize,
+    position: usize,
+    is_wide: bool,
+}
+
+impl OperandReader<'_, '_> {
+    fn read(&mut self, len: usize) -> Result<&[u8], Error> {
+        let bytes = self
+            .verifier
+            .data
+            .get(self.position..self.position + len)
+            .ok_or_else(|| self.verifier.incomplete(self.offset))?;
+        self.position += len;
+
+        Ok(bytes)
+    }
+
+    fn read_flag(&mut self) -> Result<usize, Error> {
+        Ok(self.read(1)?[0] as usize)
+    }
+
+    fn read_arg(&mut self) -> Result<usize, Error> {
+        if self.is_wide {
+            let bytes = self.read(4)?;
+
+            Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+        } else {
+            self.read_flag()
+        }
+    }
+
+    fn read_offset(&mut self) -> Result<i16, Error> {
+        let bytes = self.read(2)?;
+
+        Ok(i16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+}
+
+struct Verifier<'a> {
+    bytecode: &'a Bytecode,
+    data: &'a [u8],
+}
+
+impl<'a> Verifier<'a> {
+    fn new(bytecode: &'a Bytecode) -> Self {
+        Self {
+            bytecode,
+            data: &bytecode.inner.data,
+        }
+    }
+
+    fn verify(&self) -> Result<(), Error> {
+        for constant in self.bytecode.constants() {
+            if let ConstantValue::Function(function) = constant {
+                function.bytecode.verify()?;
+            }
+        }
+
+        let instructions = self.decode()?;
+        let positions = instructions
+            .iter()
+            .enumerate()
+            .map(|(index, instruction)| (instruction.offset, index))
+            .collect::<HashMap<_, _>>();
+
+        for instruction in &instructions {
+            if let Some(target) = instruction.jump_target() {
+                let is_valid_target = target == self.data.len() as i64 || positions.contains_key(&(target as usize));
+
+                if target < 0 || !is_valid_target {
+                    return Err(self.error(INVALID_JUMP_TARGET, instruction.offset).with_tags(tags! {
+                        offset => instruction.offset.to_string(),
+                        target => target.to_string()
+                    }));
+                }
+            }
+        }
+
+        self.verify_stack_depth(&instructions, &positions)
+    }
+
+    /// Decode every instruction in a linear sweep, checking opcodes and the bounds of their operands.
+    fn decode(&self) -> Result<Vec<DecodedInstruction>, Error> {
+        let mut instructions = Vec::new();
+        let mut offset = 0;
+
+        while offset < self.data.len() {
+            let mut position = offset;
+            let mut instruction = self.instruction(offset, position)?;
+            let is_wide = matches!(instruction, Instruction::Wide);
+
+            if is_wide {
+                position += 1;
+                instruction = self.instruction(offset, position)?;
+
+                // NOTE: Only a single wide prefix is allowed per instruction.
+                if matches!(instruction, Instruction::Wide) {
+                    return Err(self.invalid_opcode(position));
+                }
+            }
+
+            let mut reader = OperandReader {
+                verifier: self,
+                offset,
+                position: position + 1,
+                is_wide,
+            };
+            let (args, jump_offset) = self.operands(offset, instruction, &mut reader)?;
+            let next = reader.position;
+
+            self.verify_operands(offset, instruction, &args)?;
+
+            instructions.push(DecodedInstruction {
+                offset,
+                next,
+                instruction,
+                args,
+                jump_offset,
+            });
+            offset = next;
+        }
+
+        Ok(instructions)
+    }
+
+    fn instruction(&self, offset: usize, position: usize) -> Result<Instruction, Error> {
+        // NOTE: Only a wide prefix at the end of the bytecode can leave its instruction missing.
+        let opcode = *self.data.get(position).ok_or_else(|| self.incomplete(offset))?;
+
+        Instruction::from_u8(opcode).ok_or_else(|| self.invalid_opcode(position))
+    }
+
+    fn operands(
+        &self,
+        offset: usize,
+        instruction: Instruction,
+        reader: &mut OperandReader,
+    ) -> Result<(Vec<usize>, Option<i16>), Error> {
+        let operands = match instruction {
+            Instruction::Jump | Instruction::JumpIfFalse | Instruction::JumpIfTrue => {
+                return Ok((Vec::new(), Some(reader.read_offset()?)))
+            }
+            Instruction::JumpUnlessLessThanLocal => {
+                let args = vec![reader.read_arg()?, reader.read_arg()?];
+
+                return Ok((args, Some(reader.read_offset()?)));
+            }
+            Instruction::LoadFieldToLocal => vec![reader.read_arg()?, reader.read_arg()?, reader.read_arg()?],
+            Instruction::LoadField | Instruction::StoreField | Instruction::AssignField | Instruction::LoadMethod => {
+                vec![reader.read_arg()?, reader.read_arg()?]
+            }
+            Instruction::AssertTypeForLocal
+            | Instruction::AssertTypeOrNullForLocal
+            | Instruction::LoadLocalLoadLocal
+            | Instruction::AddLocalConst => vec![reader.read_arg()?, reader.read_arg()?],
+            Instruction::LoadSlice => vec![reader.read_flag()?],
+            Instruction::PushConst
+            | Instruction::Dup
+            | Instruction::CreateArray
+            | Instruction::InheritClass
+            | Instruction::LoadModule
+            | Instruction::LoadGlobal
+            | Instruction::StoreGlobal
+            | Instruction::LoadLocal
+            | Instruction::StoreLocal
+            | Instruction::AssignLocal
+            | Instruction::LoadUpvalue
+            | Instruction::StoreUpvalue
+            | Instruction::AssignUpvalue
+            | Instruction::CloseUpvalue
+            | Instruction::StoreMethod
+            | Instruction::Call
+            | Instruction::TailCall
+            | Instruction::CallSuper
+            | Instruction::AssertTypeAndReturn
+            | Instruction::AssertTypeOrNullAndReturn => vec![reader.read_arg()?],
+            // NOTE: Closures are followed by a pair of operands for each upvalue of the function they create.
+            Instruction::CreateClosure => {
+                let const_index = reader.read_arg()?;
+                let upvalue_count = match self.constant(offset, const_index)? {
+                    ConstantValue::Function(function) => function.bytecode.upvalue_count(),
+                    _ => return Err(self.invalid_constant_type(offset, const_index, "Function")),
+                };
+                let mut operands = vec![const_index];
+
+                for _ in 0..upvalue_count {
+                    operands.push(reader.read_flag()?);
+                    operands.push(reader.read_arg()?);
+                }
+
+                operands
+            }
+            _ => Vec::new(),
+        };
+
+        Ok((operands, None))
+    }
+
+    fn verify_operands(&self, offset: usize, instruction: Instruction, operands: &[usize]) -> Result<(), Error> {
+        match instruction {
+            Instruction::PushConst => self.loadable_constant(offset, operands[0])?,
+            Instruction::InheritClass
+            | Instruction::LoadModule
+            | Instruction::LoadGlobal
+            | Instruction::StoreGlobal
+            | Instruction::StoreMethod => self.symbol(offset, operands[0])?,
+            Instruction::LoadField | Instruction::StoreField | Instruction::AssignField | Instruction::LoadMethod => {
+                self.symbol(offset, operands[0])?;
+                self.cache_slot(offset, operands[1])?;
+            }
+            Instruction::LoadLocal | Instruction::StoreLocal | Instruction::AssignLocal | Instruction::CloseUpvalue => {
+                self.local_slot(offset, operands[0])?
+            }
+            Instruction::LoadUpvalue | Instruction::StoreUpvalue | Instruction::AssignUpvalue => {
+                self.upvalue_index(offset, operands[0])?
+            }
+            Instruction::LoadFieldToLocal => {
+                self.symbol(offset, operands[0])?;
+                self.local_slot(offset, operands[1])?;
+                self.cache_slot(offset, operands[2])?;
+            }
+            Instruction::AssertTypeForLocal | Instruction::AssertTypeOrNullForLocal => {
+                self.type_shape_class_count(offset, operands[0])?;
+                self.local_slot(offset, operands[1])?;
+            }
+            Instruction::LoadLocalLoadLocal => {
+                self.local_slot(offset, operands[0])?;
+                self.local_slot(offset, operands[1])?;
+            }
+            Instruction::AddLocalConst | Instruction::JumpUnlessLessThanLocal => {
+                self.local_slot(offset, operands[0])?;
+                self.loadable_constant(offset, operands[1])?;
+            }
+            Instruction::AssertTypeAndReturn | Instruction::AssertTypeOrNullAndReturn => {
+                self.type_shape_class_count(offset, operands[0])?;
+            }
+            Instruction::CreateClosure => {
+                // NOTE: The flag marks whether the upvalue captures a local slot, or an upvalue of the enclosing function.
+                for upvalue in operands[1..].chunks(2) {
+                    match upvalue[0] {
+                        1 => self.local_slot(offset, upvalue[1])?,
+                        0 => self.upvalue_index(offset, upvalue[1])?,
+                        flag => {
+                            return Err(self.error(INVALID_FLAG, offset).with_tags(tags! {
+                                offset => offset.to_string(),
+                                flag => flag.to_string()
+                            }))
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// Walk every reachable path through the bytecode, checking that the stack never underflows and that every
+    /// path into an instruction agrees on the depth of the stack.
+    fn verify_stack_depth(
+        &self,
+        instructions: &[DecodedInstruction],
+        positions: &HashMap<usize, usize>,
+    ) -> Result<(), Error> {
+        if instructions.is_empty() {
+            return Ok(());
+        }
+
+        let mut depths: Vec<Option<usize>> = vec![None; instructions.len()];
+        let mut pending = vec![0];
+        depths[0] = Some(0);
+
+        while let Some(index) = pending.pop() {
+            let instruction = &instructions[index];
+            let depth = depths[index].expect("Pending instructions should always have a depth.");
+            let (pops, pushes) = self.stack_effect(instruction)?;
+
+            if depth < pops {
+                return Err(self.underflow(instruction.offset, depth, pops));
+            }
+
+            let depth = depth - pops + pushes;
+
+            for successor in Self::successors(instruction) {
+                // NOTE: Running off the end of the bytecode is an implicit return, which requires a value to return.
+                if successor == self.data.len() {
+                    if depth < 1 {
+                        return Err(self.underflow(instruction.offset, depth, 1));
+                    }
+
+                    continue;
+                }
+
+                let successor_index = positions[&successor];
+
+                match depths[successor_index] {
+                    None => {
+                        depths[successor_index] = Some(depth);
+                        pending.push(successor_index);
+                    }
+                    Some(expected) if expected != depth => {
+                        return Err(self.error(INCONSISTENT_STACK_DEPTH, successor).with_tags(tags! {
+                            offset => successor.to_string(),
+                            expected => expected.to_string(),
+                            actual => depth.to_string()
+                        }))
+                    }
+                    Some(_) => (),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn successors(instruction: &DecodedInstruction) -> Vec<usize> {
+        match instruction.instruction {
+            Instruction::Return | Instruction::AssertTypeAndReturn | Instruction::AssertTypeOrNullAndReturn => vec![],
+            Instruction::Jump => vec![instruction.jump_target().expect("Jumps should have a target.") as usize],
+            Instruction::JumpIfFalse | Instruction::JumpIfTrue | Instruction::JumpUnlessLessThanLocal => vec![
+                instruction.next,
+                instruction.jump_target().expect("Jumps should have a target.") as usize,
+            ],
+            _ => vec![instruction.next],
+        }
+    }
+
+    /// The number of values an instruction requires on the stack, and the number it leaves in their place.
+    fn stack_effect(&self, instruction: &DecodedInstruction) -> Result<(usize, usize), Error> {
+        let operand = instruction.arg();
+        let effect = match instruction.instruction {
+            Instruction::PushNull
+            | Instruction::PushUnit
+            | Instruction::PushFalse
+            | Instruction::PushTrue
+            | Instruction::PushI0
+            | Instruction::PushI1
+            | Instruction::PushF0
+            | Instruction::PushF1
+            | Instruction::PushConst
+            | Instruction::CreateObject
+            | Instruction::CreateMap
+            | Instruction::CreateClosure
+            | Instruction::LoadModule
+            | Instruction::LoadGlobal
+            | Instruction::LoadLocal
+            | Instruction::LoadUpvalue
+            | Instruction::AddLocalConst => (0, 1),
+            Instruction::LoadLocalLoadLocal => (0, 2),
+            Instruction::Pop | Instruction::StoreGlobal | Instruction::JumpIfFalse | Instruction::JumpIfTrue => (1, 0),
+            Instruction::Dup => (operand + 1, operand + 2),
+            Instruction::Swap => (2, 2),
+            Instruction::CreateArray => (operand, 1),
+            Instruction::InheritClass
+            | Instruction::StoreLocal
+            | Instruction::AssignLocal
+            | Instruction::StoreUpvalue
+            | Instruction::AssignUpvalue
+            | Instruction::LoadField
+            | Instruction::LoadFieldToLocal
+            | Instruction::Negate
+            | Instruction::Not
+            | Instruction::AssertBool
+            | Instruction::Return => (1, 1),
+            Instruction::StoreField
+            | Instruction::AssignField
+            | Instruction::LoadIndex
+            | Instruction::LoadMethod
+            | Instruction::Multiply
+            | Instruction::Divide
+            | Instruction::Remainder
+            | Instruction::Add
+            | Instruction::Subtract
+            | Instruction::GreaterThan
+            | Instruction::GreaterThanOrEqual
+            | Instruction::LessThan
+            | Instruction::LessThanOrEqual
+            | Instruction::Equal
+            | Instruction::NotEqual
+            | Instruction::Is
+            | Instruction::RangeInclusive
+            | Instruction::RangeExclusive => (2, 1),
+            Instruction::StoreIndex | Instruction::AssignIndex | Instruction::LoadSlice => (3, 1),
+            Instruction::StoreMethod => (2, 0),
+            Instruction::CloseUpvalue | Instruction::Jump | Instruction::JumpUnlessLessThanLocal => (0, 0),
+            // NOTE: Calls consume the function (or receiver) slot and their arguments, while super calls also consume
+            // the super class.
+            Instruction::Call | Instruction::TailCall => (operand + 1, 1),
+            Instruction::CallSuper => (operand + 2, 1),
+            Instruction::AssertTypeForLocal | Instruction::AssertTypeOrNullForLocal => {
+                (self.type_shape_class_count(instruction.offset, operand)?, 0)
+            }
+            Instruction::AssertTypeAndReturn | Instruction::AssertTypeOrNullAndReturn => {
+                (self.type_shape_class_count(instruction.offset, operand)? + 1, 1)
+            }
+            Instruction::Wide => unreachable!("Wide prefixes are consumed while decoding."),
+        };
+
+        Ok(effect)
+    }
+
+    fn constant(&self, offset: usize, index: usize) -> Result<&'a ConstantValue, Error> {
+        self.bytecode.inner.constants.get(index).ok_or_else(|| {
+            self.error(INVALID_CONSTANT_INDEX, offset).with_tags(tags! {
+                offset => offset.to_string(),
+                index => index.to_string(),
+                length => self.bytecode.constants().len().to_string()
+            })
+        })
+    }
+
+    // NOTE: Type shapes are only read by the type assertions, so they can't be pushed onto the stack as values.
+    fn loadable_constant(&self, offset: usize, index: usize) -> Result<(), Error> {
+        match self.constant(offset, index)? {
+            ConstantValue::TypeShape(_) => Err(self.invalid_constant_type(offset, index, "value")),
+            _ => Ok(()),
+        }
+    }
+
+    fn symbol(&self, offset: usize, index: usize) -> Result<(), Error> {
+        match self.constant(offset, index)? {
+            ConstantValue::Symbol(_) => Ok(()),
+            _ => Err(self.invalid_constant_type(offset, index, "Symbol")),
+        }
+    }
+
+    fn type_shape_class_count(&self, offset: usize, index: usize) -> Result<usize, Error> {
+        match self.constant(offset, index)? {
+            ConstantValue::TypeShape(shape) => Ok(shape.class_count()),
+            _ => Err(self.invalid_constant_type(offset, index, "TypeShape")),
+        }
+    }
+
+    fn local_slot(&self, offset: usize, slot: usize) -> Result<(), Error> {
+        if slot >= self.bytecode.slot_count() {
+            return Err(self.error(INVALID_LOCAL_SLOT, offset).with_tags(tags! {
+                offset => offset.to_string(),
+                slot => slot.to_string(),
+                length => self.bytecode.slot_count().to_string()
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn upvalue_index(&self, offset: usize, index: usize) -> Result<(), Error> {
+        if index >= self.bytecode.upvalue_count() {
+            return Err(self.error(INVALID_UPVALUE_INDEX, offset).with_tags(tags! {
+                offset => offset.to_string(),
+                index => index.to_string(),
+                length => self.bytecode.upvalue_count().to_string()
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn cache_slot(&self, offset: usize, index: usize) -> Result<(), Error> {
+        if index >= self.bytecode.cache_count() {
+            return Err(self.error(INVALID_CACHE_SLOT, offset).with_tags(tags! {
+                offset => offset.to_string(),
+                index => index.to_string(),
+                length => self.bytecode.cache_count().to_string()
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn invalid_constant_type(&self, offset: usize, index: usize, expected: &str) -> Error {
+        self.error(INVALID_CONSTANT_TYPE, offset).with_tags(tags! {
+            offset => offset.to_string(),
+            index => index.to_string(),
+            expected => expected
+        })
+    }
+
+    fn invalid_opcode(&self, position: usize) -> Error {
+        self.error(INVALID_OPCODE, position).with_tags(tags! {
+            opcode => format!("{:#04X}", self.data[position]),
+            offset => position.to_string()
+        })
+    }
+
+    fn incomplete(&self, offset: usize) -> Error {
+        self.error(INCOMPLETE_INSTRUCTION, offset).with_tags(tags! {
+            offset => offset.to_string()
+        })
+    }
+
+    fn underflow(&self, offset: usize, depth: usize, required: usize) -> Error {
+        self.error(STACK_UNDERFLOW, offset).with_tags(tags! {
+            offset => offset.to_string(),
+            depth => depth.to_string(),
+            required => required.to_string()
+        })
+    }
+
+    fn error(&self, code: &'static str, offset: usize) -> Error {
+        Error::new(code).push_trace(self.bytecode.trace(offset as u64))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{TypeShape, TypeShapeKind};
+    use dice_core::source::{Source, SourceKind};
+
+    fn bytecode(data: Vec<u8>, slot_count: usize, constants: Vec<ConstantValue>) -> Bytecode {
+        Bytecode::new(
+            data.into_boxed_slice(),
+            slot_count,
+            0,
+            0,
+            constants.into_boxed_slice(),
+            "<script>",
+            Source::new("", SourceKind::Script),
+            HashMap::new(),
+        )
+    }
+
+    fn op(instruction: Instruction) -> u8 {
+        instruction.into()
+    }
+
+    #[test]
+    fn test_accepts_balanced_branches() {
+        // if true { 1 } else { 0 }
+        let data = vec![
+            op(Instruction::PushTrue),
+            op(Instruction::JumpIfFalse),
+            0,
+            4,
+            op(Instruction::PushI1),
+            op(Instruction::Jump),
+            0,
+            1,
+            op(Instruction::PushI0),
+            op(Instruction::Return),
+        ];
+
+        bytecode(data, 0, vec![]).verify().unwrap();
+    }
+
+    #[test]
+    fn test_rejects_invalid_opcode() {
+        let error = bytecode(vec![0xFF], 0, vec![]).verify().unwrap_err();
+        assert_eq!(error.error_code(), INVALID_OPCODE);
+    }
+
+    #[test]
+    fn test_rejects_out_of_bounds_operands() {
+        let error = bytecode(vec![op(Instruction::PushConst), 0], 0, vec![])
+            .verify()
+            .unwrap_err();
+        assert_eq!(error.error_code(), INVALID_CONSTANT_INDEX);
+
+        let error = bytecode(vec![op(Instruction::LoadLocal), 1], 1, vec![])
+            .verify()
+            .unwrap_err();
+        assert_eq!(error.error_code(), INVALID_LOCAL_SLOT);
+
+        let error = bytecode(vec![op(Instruction::LoadUpvalue), 0], 0, vec![])
+            .verify()
+            .unwrap_err();
+        assert_eq!(error.error_code(), INVALID_UPVALUE_INDEX);
+
+        let error = bytecode(vec![op(Instruction::LoadLocal)], 1, vec![])
+            .verify()
+            .unwrap_err();
+        assert_eq!(error.error_code(), INCOMPLETE_INSTRUCTION);
+
+        let error = bytecode(vec![op(Instruction::LoadGlobal), 0], 0, vec![ConstantValue::Int(1)])
+            .verify()
+            .unwrap_err();
+        assert_eq!(error.error_code(), INVALID_CONSTANT_TYPE);
+    }
+
+    #[test]
+    fn test_rejects_type_shapes_loaded_as_values() {
+        let shape = ConstantValue::TypeShape(TypeShape::new(TypeShapeKind::Function, false));
+        let error = bytecode(
+            vec![op(Instruction::PushConst), 0, op(Instruction::Return)],
+            0,
+            vec![shape],
+        )
+        .verify()
+        .unwrap_err();
+        assert_eq!(error.error_code(), INVALID_CONSTANT_TYPE);
+    }
+
+    #[test]
+    fn test_accepts_wide_operands() {
+        let data = vec![
+            op(Instruction::Wide),
+            op(Instruction::LoadLocal),
+            0,
+            0,
+            1,
+            0,
+            op(Instruction::Return),
+        ];
+        bytecode(data, 257, vec![]).verify().unwrap();
+
+        let data = vec![
+            op(Instruction::Wide),
+            op(Instruction::LoadLocal),
+            0,
+            0,
+            1,
+            0,
+            op(Instruction::Return),
+        ];
+        let error = bytecode(data, 256, vec![]).verify().unwrap_err();
+        assert_eq!(error.error_code(), INVALID_LOCAL_SLOT);
+
+        let data = vec![op(Instruction::Wide), op(Instruction::Wide), op(Instruction::PushNull)];
+        let error = bytecode(data, 0, vec![]).verify().unwrap_err();
+        assert_eq!(error.error_code(), INVALID_OPCODE);
+    }
+
+    #[test]
+    fn test_rejects_trailing_wide_prefix() {
+        let data = vec![op(Instruction::PushNull), op(Instruction::Wide)];
+        let error = bytecode(data, 0, vec![]).verify().unwrap_err();
+
+        assert_eq!(error.error_code(), INCOMPLETE_INSTRUCTION);
+    }
+
+    #[test]
+    fn test_rejects_invalid_closure_flag() {
+        let function = |upvalue_count| {
+            ConstantValue::Function(crate::FunctionBytecode::new(
+                Bytecode::new(
+                    vec![op(Instruction::PushNull), op(Instruction::Return)].into_boxed_slice(),
+                    0,
+                    upvalue_count,
+                    0,
+                    Box::new([]),
+                    "f",
+                    Source::new("", SourceKind::Script),
+                    HashMap::new(),
+                ),
+                "f",
+                uuid::Uuid::nil(),
+            ))
+        };
+
+        let data = vec![op(Instruction::CreateClosure), 0, 1, 0, op(Instruction::Return)];
+        bytecode(data, 1, vec![function(1)]).verify().unwrap();
+
+        let data = vec![op(Instruction::CreateClosure), 0, 2, 0, op(Instruction::Return)];
+        let error = bytecode(data, 1, vec![function(1)]).verify().unwrap_err();
+        assert_eq!(error.error_code(), INVALID_FLAG);
+    }
+
+    #[test]
+    fn test_rejects_invalid_cache_slot() {
+        let data = vec![
+            op(Instruction::PushUnit),
+            op(Instruction::LoadField),
+            0,
+            0,
+            op(Instruction::Return),
+        ];
+        let error = bytecode(data, 0, vec![ConstantValue::Symbol("x".to_owned())])
+            .verify()
+            .unwrap_err();
+
+        assert_eq!(error.error_code(), INVALID_CACHE_SLOT);
+    }
+
+    #[test]
+    fn test_rejects_invalid_jump_target() {
+        let data = vec![
+            op(Instruction::PushTrue),
+            op(Instruction::Jump),
+            0xFF,
+            0xFE,
+            op(Instruction::Return),
+        ];
+        let error = bytecode(data, 0, vec![]).verify().unwrap_err();
+
+        assert_eq!(error.error_code(), INVALID_JUMP_TARGET);
+    }
+
+    #[test]
+    fn test_rejects_unbalanced_stack() {
+        let error = bytecode(vec![op(Instruction::Add)], 0, vec![]).verify().unwrap_err();
+        assert_eq!(error.error_code(), STACK_UNDERFLOW);
+
+        let data = vec![
+            op(Instruction::PushTrue),
+            op(Instruction::JumpIfFalse),
+            0,
+            5,
+            op(Instruction::PushI1),
+            op(Instruction::PushI1),
+            op(Instruction::Jump),
+            0,
+            0,
+            op(Instruction::PushI0),
+            op(Instruction::Return),
+        ];
+        let error = bytecode(data, 0, vec![]).verify().unwrap_err();
+        assert_eq!(error.error_code(), INCONSISTENT_STACK_DEPTH);
+    }
+}
@@ -6,7 +6,7 @@ use dice::{
         Error,
     },
     tags,
-    value::{NativeFn, Value},
+    value::{NativeFn, OwnedValue, Value},
     Dice, Runtime,
 };
 use std::io::Write;
@@ -17,10 +17,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut dice = Dice::default();
 
     dice.runtime().load_prelude("prelude.dm")?;
-    dice.runtime()
-        .add_global("print", Value::with_native_fn(Box::new(print_value) as NativeFn))?;
-    dice.runtime()
-        .add_global("panic", Value::with_native_fn(Box::new(panic_err) as NativeFn))?;
+    dice.runtime().mutate(|runtime| {
+        runtime.add_global("print", Value::with_native_fn(Box::new(print_value) as NativeFn))?;
+        runtime.add_global("panic", Value::with_native_fn(Box::new(panic_err) as NativeFn))
+    })?;
 
     loop {
         print!("Input: ");
@@ -58,15 +58,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 }
 
-fn print_value(_: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn print_value<'gc>(runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
     if let [_, arg, ..] = args {
-        println!("{}", arg);
+        println!("{}", OwnedValue::from_value(&runtime.context(), arg));
     }
 
     Ok(Value::Unit)
 }
 
-fn panic_err(_: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn panic_err<'gc>(_: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
     if let [_, Value::String(message), ..] = args {
         Err(Error::new(PANIC).with_tags(tags! {
             message => message.to_string()
@@ -0,0 +1,50 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+// NOTE: Everything that can change the bytecode generated for a given source.
+const CODEGEN_SOURCES: &[&str] = &["src", "../dice-syntax/src", "../dice-bytecode/src", "../dice-core/src"];
+
+/// Hash the sources of the compiler and the crates it generates code with, so that cached bytecode is invalidated by
+/// any change to code generation, even when the crate version stays the same.
+fn main() {
+    let mut files = Vec::new();
+
+    for directory in CODEGEN_SOURCES {
+        println!("cargo:rerun-if-changed={}", directory);
+        collect_files(Path::new(directory), &mut files);
+    }
+
+    files.sort();
+
+    let mut hasher = DefaultHasher::new();
+
+    for file in files {
+        file.hash(&mut hasher);
+        std::fs::read(&file)
+            .expect("Compiler sources should be readable.")
+            .hash(&mut hasher);
+    }
+
+    println!("cargo:rustc-env=DICE_COMPILER_BUILD_ID={:016x}", hasher.finish());
+}
+
+fn collect_files(directory: &Path, files: &mut Vec<PathBuf>) {
+    // NOTE: The sibling crates are missing when the compiler is built on its own, which leaves only its own sources.
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
@@ -1,24 +1,28 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, convert::TryFrom};
 
 use bytes::BufMut as _;
 
 use dice_bytecode::Instruction;
-use dice_bytecode::{Bytecode, ConstantValue};
+use dice_bytecode::{Bytecode, ConstantValue, TypeShape};
 use dice_core::{
     error::{
-        codes::{TOO_MANY_CONSTANTS, TOO_MANY_UPVALUES},
+        codes::{JUMP_TOO_FAR, TOO_MANY_CONSTANTS, TOO_MANY_UPVALUES},
         Error,
     },
     source::Source,
     span::Span,
 };
 
-use super::upvalue::UpvalueDescriptor;
+use super::{peephole::Peephole, upvalue::UpvalueDescriptor};
+
+// NOTE: The largest operand that can be encoded, when widened by a WIDE prefix.
+const MAX_ARG: usize = u32::MAX as usize;
 
 pub struct Assembler {
     constants: Vec<ConstantValue>,
     source_map: HashMap<u64, Span>,
     data: Vec<u8>,
+    cache_count: usize,
 }
 
 impl Assembler {
@@ -27,17 +31,35 @@ impl Assembler {
             constants: Default::default(),
             source_map: Default::default(),
             data: Default::default(),
+            cache_count: 0,
         }
     }
 
-    pub fn generate(self, slot_count: usize, upvalue_count: usize, _source: Source) -> Bytecode {
+    pub fn generate(
+        mut self,
+        slot_count: usize,
+        upvalue_count: usize,
+        name: &str,
+        source: Source,
+        is_optimized: bool,
+    ) -> Bytecode {
+        // NOTE: The unoptimized code is kept when the optimized code can't be encoded.
+        if is_optimized {
+            if let Some((data, source_map)) = Peephole::optimize(&self.data, &self.source_map, &mut self.constants) {
+                self.data = data;
+                self.source_map = source_map;
+            }
+        }
+
         Bytecode::new(
             self.data.into(),
             slot_count,
             upvalue_count,
+            self.cache_count,
             self.constants.into_boxed_slice(),
-            // source,
-            // self.source_map,
+            name,
+            source,
+            self.source_map,
         )
     }
 
@@ -83,31 +105,37 @@ impl Assembler {
     }
 
     pub fn push_const(&mut self, value: ConstantValue, span: Span) -> Result<(), Error> {
-        self.source_map.insert(self.data.len() as u64, span);
-        self.data.put_u8(Instruction::PushConst.into());
-
         self.source_map.insert(self.data.len() as u64, span);
         let const_pos = self.make_constant(value, span)?;
-        self.data.put_u8(const_pos);
+        self.put_instruction(Instruction::PushConst, &[const_pos]);
 
         Ok(())
     }
 
     pub fn closure(&mut self, value: ConstantValue, upvalues: &[UpvalueDescriptor], span: Span) -> Result<(), Error> {
+        if upvalues.len() > MAX_ARG {
+            return Err(Error::new(TOO_MANY_UPVALUES).with_span(span));
+        }
+
         self.source_map.insert(self.data.len() as u64, span);
-        self.data.put_u8(Instruction::CreateClosure.into());
         let fn_pos = self.make_constant(value, span)?;
-        self.data.put_u8(fn_pos);
+        let is_wide = fn_pos > u8::MAX as usize
+            || upvalues
+                .iter()
+                .any(|upvalue| upvalue.description().1 > u8::MAX as usize);
 
-        if upvalues.len() > 255 {
-            return Err(Error::new(TOO_MANY_UPVALUES).with_span(span));
+        if is_wide {
+            self.data.put_u8(Instruction::Wide.into());
         }
 
+        self.data.put_u8(Instruction::CreateClosure.into());
+        self.put_arg(fn_pos, is_wide);
+
         for upvalue in upvalues {
             let (is_parent_local, index) = upvalue.description();
 
             self.data.put_u8(is_parent_local as u8);
-            self.data.put_u8(index as u8);
+            self.put_arg(index, is_wide);
         }
 
         Ok(())
@@ -118,10 +146,9 @@ impl Assembler {
         self.data.put_u8(Instruction::Pop.into());
     }
 
-    pub fn dup(&mut self, offset: u8, span: Span) {
+    pub fn dup(&mut self, offset: usize, span: Span) {
         self.source_map.insert(self.data.len() as u64, span);
-        self.data.put_u8(Instruction::Dup.into());
-        self.data.put_u8(offset);
+        self.put_instruction(Instruction::Dup, &[offset]);
     }
 
     pub fn swap(&mut self, span: Span) {
@@ -129,10 +156,9 @@ impl Assembler {
         self.data.put_u8(Instruction::Swap.into());
     }
 
-    pub fn create_list(&mut self, length: u8, span: Span) {
+    pub fn create_list(&mut self, length: usize, span: Span) {
         self.source_map.insert(self.data.len() as u64, span);
-        self.data.put_u8(Instruction::CreateArray.into());
-        self.data.put_u8(length);
+        self.put_instruction(Instruction::CreateArray, &[length]);
     }
 
     pub fn create_object(&mut self, span: Span) {
@@ -140,11 +166,15 @@ impl Assembler {
         self.data.put_u8(Instruction::CreateObject.into());
     }
 
+    pub fn create_map(&mut self, span: Span) {
+        self.source_map.insert(self.data.len() as u64, span);
+        self.data.put_u8(Instruction::CreateMap.into());
+    }
+
     pub fn inherit_class(&mut self, name: &str, span: Span) -> Result<(), Error> {
         self.source_map.insert(self.data.len() as u64, span);
-        self.data.put_u8(Instruction::InheritClass.into());
-        let name_slot = self.make_constant(ConstantValue::Symbol(name.into()), span)? as u8;
-        self.data.put_u8(name_slot);
+        let name_slot = self.make_constant(ConstantValue::Symbol(name.into()), span)?;
+        self.put_instruction(Instruction::InheritClass, &[name_slot]);
 
         Ok(())
     }
@@ -259,70 +289,79 @@ impl Assembler {
         patch_pos
     }
 
-    pub fn patch_jump(&mut self, jump_position: u64) {
-        let offset = (self.current_position() - jump_position - 2) as i16;
-        (&mut self.data[jump_position as usize..]).put_i16(offset)
+    pub fn patch_jump(&mut self, jump_position: u64) -> Result<(), Error> {
+        let offset = self.current_position() as i64 - jump_position as i64 - 2;
+        let offset = i16::try_from(offset).map_err(|_| {
+            let error = Error::new(JUMP_TOO_FAR);
+
+            match self.source_map.get(&(jump_position - 1)) {
+                Some(span) => error.with_span(*span),
+                None => error,
+            }
+        })?;
+
+        (&mut self.data[jump_position as usize..]).put_i16(offset);
+
+        Ok(())
     }
 
-    pub fn jump_back(&mut self, position: u64, span: Span) {
+    pub fn jump_back(&mut self, position: u64, span: Span) -> Result<(), Error> {
+        // NOTE: The offset is relative to the end of the jump, after its opcode and 16 bit offset.
+        let offset = position as i64 - (self.current_position() as i64 + 3);
+        let offset = i16::try_from(offset).map_err(|_| Error::new(JUMP_TOO_FAR).with_span(span))?;
+
         self.source_map.insert(self.data.len() as u64, span);
         self.data.put_u8(Instruction::Jump.into());
-        let offset = -((self.current_position() - position + 2) as i16);
         self.data.put_i16(offset);
+
+        Ok(())
     }
 
     pub fn current_position(&self) -> u64 {
         (self.data.len()) as u64
     }
 
-    pub fn store_local(&mut self, slot: u8, span: Span) {
+    pub fn store_local(&mut self, slot: usize, span: Span) {
         self.source_map.insert(self.data.len() as u64, span);
-        self.data.put_u8(Instruction::StoreLocal.into());
-        self.data.put_u8(slot);
+        self.put_instruction(Instruction::StoreLocal, &[slot]);
     }
 
-    pub fn assign_local(&mut self, slot: u8, span: Span) {
+    pub fn assign_local(&mut self, slot: usize, span: Span) {
         self.source_map.insert(self.data.len() as u64, span);
-        self.data.put_u8(Instruction::AssignLocal.into());
-        self.data.put_u8(slot);
+        self.put_instruction(Instruction::AssignLocal, &[slot]);
     }
 
-    pub fn load_local(&mut self, slot: u8, span: Span) {
+    pub fn load_local(&mut self, slot: usize, span: Span) {
         self.source_map.insert(self.data.len() as u64, span);
-        self.data.put_u8(Instruction::LoadLocal.into());
-        self.data.put_u8(slot);
+        self.put_instruction(Instruction::LoadLocal, &[slot]);
     }
 
     #[allow(dead_code)]
-    pub fn store_upvalue(&mut self, index: u8, span: Span) {
+    pub fn store_upvalue(&mut self, index: usize, span: Span) {
         self.source_map.insert(self.data.len() as u64, span);
-        self.data.put_u8(Instruction::StoreUpvalue.into());
-        self.data.put_u8(index);
+        self.put_instruction(Instruction::StoreUpvalue, &[index]);
     }
 
-    pub fn assign_upvalue(&mut self, index: u8, span: Span) {
+    pub fn assign_upvalue(&mut self, index: usize, span: Span) {
         self.source_map.insert(self.data.len() as u64, span);
-        self.data.put_u8(Instruction::AssignUpvalue.into());
-        self.data.put_u8(index);
+        self.put_instruction(Instruction::AssignUpvalue, &[index]);
     }
 
-    pub fn load_upvalue(&mut self, index: u8, span: Span) {
+    pub fn load_upvalue(&mut self, index: usize, span: Span) {
         self.source_map.insert(self.data.len() as u64, span);
-        self.data.put_u8(Instruction::LoadUpvalue.into());
-        self.data.put_u8(index);
+        self.put_instruction(Instruction::LoadUpvalue, &[index]);
     }
 
-    pub fn close_upvalue(&mut self, index: u8, span: Span) {
+    pub fn close_upvalue(&mut self, index: usize, span: Span) {
         self.source_map.insert(self.data.len() as u64, span);
-        self.data.put_u8(Instruction::CloseUpvalue.into());
-        self.data.put_u8(index);
+        self.put_instruction(Instruction::CloseUpvalue, &[index]);
     }
 
     pub fn store_field(&mut self, field: impl Into<String>, span: Span) -> Result<(), Error> {
         self.source_map.insert(self.data.len() as u64, span);
         let const_slot = self.make_constant(ConstantValue::Symbol(field.into()), span)?;
-        self.data.put_u8(Instruction::StoreField.into());
-        self.data.put_u8(const_slot);
+        let cache_slot = self.make_cache_slot();
+        self.put_instruction(Instruction::StoreField, &[const_slot, cache_slot]);
 
         Ok(())
     }
@@ -330,8 +369,8 @@ impl Assembler {
     pub fn assign_field(&mut self, field: impl Into<String>, span: Span) -> Result<(), Error> {
         self.source_map.insert(self.data.len() as u64, span);
         let const_slot = self.make_constant(ConstantValue::Symbol(field.into()), span)?;
-        self.data.put_u8(Instruction::AssignField.into());
-        self.data.put_u8(const_slot);
+        let cache_slot = self.make_cache_slot();
+        self.put_instruction(Instruction::AssignField, &[const_slot, cache_slot]);
 
         Ok(())
     }
@@ -339,8 +378,8 @@ impl Assembler {
     pub fn load_method(&mut self, method: impl Into<String>, span: Span) -> Result<(), Error> {
         self.source_map.insert(self.data.len() as u64, span);
         let const_slot = self.make_constant(ConstantValue::Symbol(method.into()), span)?;
-        self.data.put_u8(Instruction::LoadMethod.into());
-        self.data.put_u8(const_slot);
+        let cache_slot = self.make_cache_slot();
+        self.put_instruction(Instruction::LoadMethod, &[const_slot, cache_slot]);
 
         Ok(())
     }
@@ -348,8 +387,7 @@ impl Assembler {
     pub fn store_method(&mut self, method: impl Into<String>, span: Span) -> Result<(), Error> {
         self.source_map.insert(self.data.len() as u64, span);
         let const_slot = self.make_constant(ConstantValue::Symbol(method.into()), span)?;
-        self.data.put_u8(Instruction::StoreMethod.into());
-        self.data.put_u8(const_slot);
+        self.put_instruction(Instruction::StoreMethod, &[const_slot]);
 
         Ok(())
     }
@@ -357,8 +395,8 @@ impl Assembler {
     pub fn load_field(&mut self, field: impl Into<String>, span: Span) -> Result<(), Error> {
         self.source_map.insert(self.data.len() as u64, span);
         let const_slot = self.make_constant(ConstantValue::Symbol(field.into()), span)?;
-        self.data.put_u8(Instruction::LoadField.into());
-        self.data.put_u8(const_slot);
+        let cache_slot = self.make_cache_slot();
+        self.put_instruction(Instruction::LoadField, &[const_slot, cache_slot]);
 
         Ok(())
     }
@@ -368,8 +406,7 @@ impl Assembler {
             let const_slot = assembler.make_constant(ConstantValue::Symbol(global), span)?;
 
             assembler.source_map.insert(assembler.data.len() as u64, span);
-            assembler.data.put_u8(Instruction::StoreGlobal.into());
-            assembler.data.put_u8(const_slot);
+            assembler.put_instruction(Instruction::StoreGlobal, &[const_slot]);
 
             Ok(())
         }
@@ -382,8 +419,7 @@ impl Assembler {
             let const_slot = assembler.make_constant(ConstantValue::Symbol(global), span)?;
 
             assembler.source_map.insert(assembler.data.len() as u64, span);
-            assembler.data.put_u8(Instruction::LoadGlobal.into());
-            assembler.data.put_u8(const_slot);
+            assembler.put_instruction(Instruction::LoadGlobal, &[const_slot]);
 
             Ok(())
         }
@@ -396,8 +432,7 @@ impl Assembler {
             let const_slot = assembler.make_constant(ConstantValue::Symbol(path), span)?;
 
             assembler.source_map.insert(assembler.data.len() as u64, span);
-            assembler.data.put_u8(Instruction::LoadModule.into());
-            assembler.data.put_u8(const_slot);
+            assembler.put_instruction(Instruction::LoadModule, &[const_slot]);
 
             Ok(())
         }
@@ -410,7 +445,12 @@ impl Assembler {
         self.data.put_u8(Instruction::LoadIndex.into());
     }
 
-    #[allow(dead_code)]
+    pub fn load_slice(&mut self, is_inclusive: bool, span: Span) {
+        self.source_map.insert(self.data.len() as u64, span);
+        self.data.put_u8(Instruction::LoadSlice.into());
+        self.data.put_u8(is_inclusive as u8);
+    }
+
     pub fn store_index(&mut self, span: Span) {
         self.source_map.insert(self.data.len() as u64, span);
         self.data.put_u8(Instruction::StoreIndex.into());
@@ -421,26 +461,33 @@ impl Assembler {
         self.data.put_u8(Instruction::AssignIndex.into());
     }
 
-    pub fn load_field_to_local(&mut self, field: impl Into<String>, local_slot: u8, span: Span) -> Result<(), Error> {
+    pub fn load_field_to_local(
+        &mut self,
+        field: impl Into<String>,
+        local_slot: usize,
+        span: Span,
+    ) -> Result<(), Error> {
         self.source_map.insert(self.data.len() as u64, span);
         let const_slot = self.make_constant(ConstantValue::Symbol(field.into()), span)?;
-        self.data.put_u8(Instruction::LoadFieldToLocal.into());
-        self.data.put_u8(const_slot);
-        self.data.put_u8(local_slot);
+        let cache_slot = self.make_cache_slot();
+        self.put_instruction(Instruction::LoadFieldToLocal, &[const_slot, local_slot, cache_slot]);
 
         Ok(())
     }
 
-    pub fn call(&mut self, arg_count: u8, span: Span) {
+    pub fn call(&mut self, arg_count: usize, span: Span) {
         self.source_map.insert(self.data.len() as u64, span);
-        self.data.put_u8(Instruction::Call.into());
-        self.data.put_u8(arg_count);
+        self.put_instruction(Instruction::Call, &[arg_count]);
     }
 
-    pub fn call_super(&mut self, arg_count: u8, span: Span) {
+    pub fn tail_call(&mut self, arg_count: usize, span: Span) {
         self.source_map.insert(self.data.len() as u64, span);
-        self.data.put_u8(Instruction::CallSuper.into());
-        self.data.put_u8(arg_count);
+        self.put_instruction(Instruction::TailCall, &[arg_count]);
+    }
+
+    pub fn call_super(&mut self, arg_count: usize, span: Span) {
+        self.source_map.insert(self.data.len() as u64, span);
+        self.put_instruction(Instruction::CallSuper, &[arg_count]);
     }
 
     pub fn ret(&mut self, span: Span) {
@@ -453,29 +500,63 @@ impl Assembler {
         self.data.put_u8(Instruction::AssertBool.into());
     }
 
-    pub fn assert_type_for_local(&mut self, slot: u8, span: Span) {
+    pub fn assert_type_for_local(&mut self, shape: TypeShape, slot: usize, span: Span) -> Result<(), Error> {
         self.source_map.insert(self.data.len() as u64, span);
-        self.data.put_u8(Instruction::AssertTypeForLocal.into());
-        self.data.put_u8(slot);
+        let shape_slot = self.make_constant(ConstantValue::TypeShape(shape), span)?;
+        self.put_instruction(Instruction::AssertTypeForLocal, &[shape_slot, slot]);
+
+        Ok(())
     }
 
-    pub fn assert_type_or_null_for_local(&mut self, slot: u8, span: Span) {
+    pub fn assert_type_or_null_for_local(&mut self, shape: TypeShape, slot: usize, span: Span) -> Result<(), Error> {
         self.source_map.insert(self.data.len() as u64, span);
-        self.data.put_u8(Instruction::AssertTypeOrNullForLocal.into());
-        self.data.put_u8(slot);
+        let shape_slot = self.make_constant(ConstantValue::TypeShape(shape), span)?;
+        self.put_instruction(Instruction::AssertTypeOrNullForLocal, &[shape_slot, slot]);
+
+        Ok(())
     }
 
-    pub fn assert_type_and_return(&mut self, span: Span) {
+    pub fn assert_type_and_return(&mut self, shape: TypeShape, span: Span) -> Result<(), Error> {
         self.source_map.insert(self.data.len() as u64, span);
-        self.data.put_u8(Instruction::AssertTypeAndReturn.into());
+        let shape_slot = self.make_constant(ConstantValue::TypeShape(shape), span)?;
+        self.put_instruction(Instruction::AssertTypeAndReturn, &[shape_slot]);
+
+        Ok(())
     }
 
-    pub fn assert_type_or_null_and_return(&mut self, span: Span) {
+    pub fn assert_type_or_null_and_return(&mut self, shape: TypeShape, span: Span) -> Result<(), Error> {
         self.source_map.insert(self.data.len() as u64, span);
-        self.data.put_u8(Instruction::AssertTypeOrNullAndReturn.into());
+        let shape_slot = self.make_constant(ConstantValue::TypeShape(shape), span)?;
+        self.put_instruction(Instruction::AssertTypeOrNullAndReturn, &[shape_slot]);
+
+        Ok(())
+    }
+
+    /// Emit an instruction along with its index and count operands, prefixing it with a WIDE instruction when any of
+    /// the operands don't fit into a single byte.
+    fn put_instruction(&mut self, instruction: Instruction, args: &[usize]) {
+        let is_wide = args.iter().any(|arg| *arg > u8::MAX as usize);
+
+        if is_wide {
+            self.data.put_u8(Instruction::Wide.into());
+        }
+
+        self.data.put_u8(instruction.into());
+
+        for arg in args {
+            self.put_arg(*arg, is_wide);
+        }
+    }
+
+    fn put_arg(&mut self, arg: usize, is_wide: bool) {
+        if is_wide {
+            self.data.put_u32(arg as u32);
+        } else {
+            self.data.put_u8(arg as u8);
+        }
     }
 
-    fn make_constant(&mut self, into: ConstantValue, span: Span) -> Result<u8, Error> {
+    fn make_constant(&mut self, into: ConstantValue, span: Span) -> Result<usize, Error> {
         let position = if let Some(position) = self.constants.iter().position(|current| *current == into) {
             position
         } else {
@@ -483,12 +564,17 @@ impl Assembler {
             self.constants.len() - 1
         };
 
-        // NOTE: This could be alleviated by offering a long-form PushConst.
-        if position > 255 {
+        if position > MAX_ARG {
             return Err(Error::new(TOO_MANY_CONSTANTS).with_span(span));
         }
 
-        Ok(position as u8)
+        Ok(position)
+    }
+
+    /// Give a field or method instruction an inline cache of its own, so the runtime can index its caches directly.
+    fn make_cache_slot(&mut self) -> usize {
+        self.cache_count += 1;
+        self.cache_count - 1
     }
 }
 
@@ -636,7 +722,7 @@ macro_rules! emit_bytecode {
 
 
     ($assembler:expr, $span:expr => [JUMP_BACK $offset:expr; $($rest:tt)*] ) => {
-        $assembler.jump_back($offset, $span);
+        $assembler.jump_back($offset, $span)?;
         emit_bytecode! { $assembler, $span => [$($rest)*] }
     };
 
@@ -690,6 +776,11 @@ macro_rules! emit_bytecode {
         emit_bytecode! { $assembler, $span => [$($rest)*] }
     };
 
+    ($assembler:expr, $span:expr => [LOAD_SLICE $is_inclusive:expr; $($rest:tt)*] ) => {
+        $assembler.load_slice($is_inclusive, $span);
+        emit_bytecode! { $assembler, $span => [$($rest)*] }
+    };
+
     ($assembler:expr, $span:expr => [STORE_INDEX; $($rest:tt)*] ) => {
         $assembler.store_index($span);
         emit_bytecode! { $assembler, $span => [$($rest)*] }
@@ -728,7 +819,7 @@ macro_rules! emit_bytecode {
     ($assembler:expr, $span:expr => [CLOSE_UPVALUES $variables:expr; $($rest:tt)*]) => {
         for variable in $variables {
             if variable.is_captured {
-                $assembler.close_upvalue(variable.slot as u8, $span);
+                $assembler.close_upvalue(variable.slot, $span);
             }
         }
 
@@ -745,23 +836,23 @@ macro_rules! emit_bytecode {
         emit_bytecode! { $assembler, $span => [$($rest)*] }
     };
 
-    ($assembler:expr, $span:expr => [ASSERT_TYPE_FOR_LOCAL $slot:expr; $($rest:tt)*] ) => {
-        $assembler.assert_type_for_local($slot, $span);
+    ($assembler:expr, $span:expr => [ASSERT_TYPE_FOR_LOCAL $shape:expr, $slot:expr; $($rest:tt)*] ) => {
+        $assembler.assert_type_for_local($shape, $slot, $span)?;
         emit_bytecode! { $assembler, $span => [$($rest)*] }
     };
 
-    ($assembler:expr, $span:expr => [ASSERT_TYPE_OR_NULL_FOR_LOCAL $slot:expr; $($rest:tt)*] ) => {
-        $assembler.assert_type_or_null_for_local($slot, $span);
+    ($assembler:expr, $span:expr => [ASSERT_TYPE_OR_NULL_FOR_LOCAL $shape:expr, $slot:expr; $($rest:tt)*] ) => {
+        $assembler.assert_type_or_null_for_local($shape, $slot, $span)?;
         emit_bytecode! { $assembler, $span => [$($rest)*] }
     };
 
-    ($assembler:expr, $span:expr => [ASSERT_TYPE_AND_RETURN; $($rest:tt)*] ) => {
-        $assembler.assert_type_and_return($span);
+    ($assembler:expr, $span:expr => [ASSERT_TYPE_AND_RETURN $shape:expr; $($rest:tt)*] ) => {
+        $assembler.assert_type_and_return($shape, $span)?;
         emit_bytecode! { $assembler, $span => [$($rest)*] }
     };
 
-    ($assembler:expr, $span:expr => [ASSERT_TYPE_OR_NULL_AND_RETURN; $($rest:tt)*] ) => {
-        $assembler.assert_type_or_null_and_return($span);
+    ($assembler:expr, $span:expr => [ASSERT_TYPE_OR_NULL_AND_RETURN $shape:expr; $($rest:tt)*] ) => {
+        $assembler.assert_type_or_null_and_return($shape, $span)?;
         emit_bytecode! { $assembler, $span => [$($rest)*] }
     };
 
@@ -771,7 +862,7 @@ macro_rules! emit_bytecode {
     };
 
     ($assembler:expr, $span:expr => [PATCH_JUMP <- $into:expr; $($rest:tt)*] ) => {
-        $assembler.patch_jump($into);
+        $assembler.patch_jump($into)?;
         emit_bytecode! { $assembler, $span => [$($rest)*] }
     };
 
@@ -10,19 +10,60 @@ use dice_syntax::{Parser, SyntaxTree};
 use crate::{
     assembler::Assembler,
     compiler_stack::{CompilerContext, CompilerKind, CompilerStack},
+    optimizer::Optimizer,
     scope_stack::State,
+    type_checker::TypeChecker,
     visitor::NodeVisitor,
 };
 
+static SCRIPT_NAME: &str = "<script>";
+static MODULE_NAME: &str = "<module>";
+
+/// Identifies the build of the compiler, used to invalidate any cached bytecode produced by other builds.
+// NOTE: The crate version isn't enough, since it rarely changes when code generation does.
+pub static COMPILER_BUILD_ID: &str = env!("DICE_COMPILER_BUILD_ID");
+
+#[derive(Debug, Clone)]
+pub struct CompilerOptions {
+    /// Check type annotations at compile time, in addition to the assertions performed at runtime.
+    pub type_check: bool,
+    /// Fold constant expressions and remove unreachable code before generating bytecode, then run a peephole pass over
+    /// the generated bytecode.
+    pub optimize: bool,
+}
+
+impl Default for CompilerOptions {
+    fn default() -> Self {
+        Self {
+            type_check: false,
+            optimize: true,
+        }
+    }
+}
+
 pub struct Compiler {
     pub(crate) syntax_tree: SyntaxTree,
     pub(crate) compiler_stack: CompilerStack,
     pub(crate) source: Source,
+    pub(crate) options: CompilerOptions,
 }
 
 impl Compiler {
     pub fn compile_source(source: Source) -> Result<Bytecode, Error> {
-        let syntax_tree = Parser::new(&source).parse()?;
+        Self::compile_source_with_options(source, CompilerOptions::default())
+    }
+
+    pub fn compile_source_with_options(source: Source, options: CompilerOptions) -> Result<Bytecode, Error> {
+        let mut syntax_tree = Parser::new(&source).parse()?;
+
+        if options.type_check {
+            TypeChecker::check(&syntax_tree).with_source(|| source.clone())?;
+        }
+
+        if options.optimize {
+            Optimizer::optimize(&mut syntax_tree);
+        }
+
         let kind = match source.kind() {
             SourceKind::Module => CompilerKind::Module,
             SourceKind::Script => CompilerKind::Script,
@@ -32,6 +73,7 @@ impl Compiler {
             syntax_tree,
             compiler_stack,
             source,
+            options,
         };
 
         compiler.compile(kind).with_source(|| compiler.source.clone())
@@ -52,7 +94,7 @@ impl Compiler {
                 .scope_stack()
                 .local(EXPORT)
                 .expect("#export should always be defined for modules.")
-                .slot as u8;
+                .slot;
 
             emit_bytecode! {
                 self.assembler()?, Span::new(0..0) => [
@@ -63,8 +105,12 @@ impl Compiler {
         }
 
         let compiler_context = self.compiler_stack.pop()?;
+        let name = match kind {
+            CompilerKind::Module => MODULE_NAME,
+            _ => SCRIPT_NAME,
+        };
 
-        Ok(compiler_context.finish(self.source.clone()))
+        Ok(compiler_context.finish(name, self.source.clone(), self.options.optimize))
     }
 
     pub(super) fn context(&mut self) -> Result<&mut CompilerContext, Error> {
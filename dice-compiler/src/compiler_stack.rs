@@ -85,10 +85,11 @@ impl CompilerContext {
         &mut self.temporary_count
     }
 
-    pub fn finish(mut self, source: Source) -> Bytecode {
+    pub fn finish(mut self, name: &str, source: Source, is_optimized: bool) -> Bytecode {
         let slot_count = self.scope_stack.slot_count;
         let upvalue_count = self.upvalues().len();
-        self.assembler.generate(slot_count, upvalue_count, source)
+        self.assembler
+            .generate(slot_count, upvalue_count, name, source, is_optimized)
     }
 }
 
@@ -124,6 +125,13 @@ impl CompilerStack {
         self.stack.get_mut(index)
     }
 
+    pub fn resolve_type_alias(&mut self, name: &str) -> Option<TypeAnnotation> {
+        self.stack
+            .iter_mut()
+            .rev()
+            .find_map(|context| context.scope_stack().type_alias(name).cloned())
+    }
+
     pub fn resolve_upvalue(&mut self, name: String, depth: usize) -> Option<usize> {
         let parent_local = self.offset(depth + 1)?.scope_stack().local(name.clone());
         let descriptor = match parent_local {
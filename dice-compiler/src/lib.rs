@@ -4,6 +4,10 @@ mod compile_fn;
 pub mod compiler;
 mod compiler_stack;
 mod decl_scan;
+mod optimizer;
+mod peephole;
+pub mod register_compiler;
 mod scope_stack;
+mod type_checker;
 mod upvalue;
 mod visitor;
@@ -0,0 +1,386 @@
+use dice_core::span::Span;
+use dice_syntax::{
+    Binary, BinaryOperator, Block, IfExpression, LitBool, LitFloat, LitInt, LitNull, LitUnit, Prefix, SyntaxNode,
+    SyntaxNodeId, SyntaxTree, UnaryOperator, WhileLoop,
+};
+
+#[derive(Debug, Clone, Copy)]
+enum Constant {
+    Null,
+    Unit,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+}
+
+impl Constant {
+    fn into_node(self, span: Span) -> SyntaxNode {
+        match self {
+            Constant::Null => SyntaxNode::LitNull(LitNull { span }),
+            Constant::Unit => SyntaxNode::LitUnit(LitUnit { span }),
+            Constant::Bool(value) => SyntaxNode::LitBool(LitBool { value, span }),
+            Constant::Int(value) => SyntaxNode::LitInt(LitInt { value, span }),
+            Constant::Float(value) => SyntaxNode::LitFloat(LitFloat { value, span }),
+        }
+    }
+}
+
+/// A pass over the syntax tree that folds operators applied to literals and removes code that can never run.
+// NOTE: Folding only applies where the interpreter's result is known without calling into any classes, and never
+// folds an operation that would fail at runtime, such as an overflow or division by zero, so that the error is still
+// raised when the code executes.  Folded literals take the span of the expression they replace.
+pub(crate) struct Optimizer<'a> {
+    syntax_tree: &'a mut SyntaxTree,
+}
+
+impl<'a> Optimizer<'a> {
+    pub fn optimize(syntax_tree: &'a mut SyntaxTree) {
+        let root = syntax_tree.root();
+        let mut optimizer = Self { syntax_tree };
+
+        optimizer.optimize_node(root);
+    }
+
+    fn optimize_node(&mut self, id: SyntaxNodeId) {
+        let node = self.syntax_tree.get(id).clone();
+
+        for child in children(&node) {
+            self.optimize_node(child);
+        }
+
+        let replacement = match &node {
+            SyntaxNode::Prefix(prefix) => self.fold_prefix(prefix),
+            SyntaxNode::Binary(binary) => self.fold_binary(binary),
+            SyntaxNode::IfExpression(if_expression) => self.eliminate_branch(if_expression),
+            SyntaxNode::WhileLoop(while_loop) => self.eliminate_loop(while_loop),
+            SyntaxNode::Block(block) => self.eliminate_unreachable(block),
+            _ => None,
+        };
+
+        if let Some(replacement) = replacement {
+            *self.syntax_tree.get_mut(id) = replacement;
+        }
+    }
+
+    fn constant(&self, id: SyntaxNodeId) -> Option<Constant> {
+        match self.syntax_tree.get(id) {
+            SyntaxNode::LitNull(_) => Some(Constant::Null),
+            SyntaxNode::LitUnit(_) => Some(Constant::Unit),
+            SyntaxNode::LitBool(LitBool { value, .. }) => Some(Constant::Bool(*value)),
+            SyntaxNode::LitInt(LitInt { value, .. }) => Some(Constant::Int(*value)),
+            SyntaxNode::LitFloat(LitFloat { value, .. }) => Some(Constant::Float(*value)),
+            _ => None,
+        }
+    }
+
+    fn fold_prefix(
+        &self,
+        Prefix {
+            operator,
+            expression,
+            span,
+        }: &Prefix,
+    ) -> Option<SyntaxNode> {
+        let result = match (operator, self.constant(*expression)?) {
+            (UnaryOperator::Negate, Constant::Int(value)) => Constant::Int(value.checked_neg()?),
+            (UnaryOperator::Negate, Constant::Float(value)) => Constant::Float(-value),
+            (UnaryOperator::Not, Constant::Bool(value)) => Constant::Bool(!value),
+            _ => return None,
+        };
+
+        Some(result.into_node(*span))
+    }
+
+    fn fold_binary(
+        &self,
+        Binary {
+            operator,
+            lhs_expression,
+            rhs_expression,
+            span,
+        }: &Binary,
+    ) -> Option<SyntaxNode> {
+        let lhs = self.constant(*lhs_expression)?;
+
+        // NOTE: The short circuiting operators can be decided by the left hand side alone.
+        match (operator, lhs) {
+            (BinaryOperator::LogicalAnd, Constant::Bool(false)) | (BinaryOperator::LogicalOr, Constant::Bool(true)) => {
+                return Some(lhs.into_node(*span))
+            }
+            (BinaryOperator::Coalesce, Constant::Null) => return Some(self.syntax_tree.get(*rhs_expression).clone()),
+            (BinaryOperator::Coalesce, _) => return Some(lhs.into_node(*span)),
+            _ => {}
+        }
+
+        let rhs = self.constant(*rhs_expression)?;
+        let result = match (operator, lhs, rhs) {
+            (BinaryOperator::Add, Constant::Int(lhs), Constant::Int(rhs)) => Constant::Int(lhs.checked_add(rhs)?),
+            (BinaryOperator::Subtract, Constant::Int(lhs), Constant::Int(rhs)) => Constant::Int(lhs.checked_sub(rhs)?),
+            (BinaryOperator::Multiply, Constant::Int(lhs), Constant::Int(rhs)) => Constant::Int(lhs.checked_mul(rhs)?),
+            (BinaryOperator::Divide, Constant::Int(lhs), Constant::Int(rhs)) => Constant::Int(lhs.checked_div(rhs)?),
+            (BinaryOperator::Remainder, Constant::Int(lhs), Constant::Int(rhs)) => Constant::Int(lhs.checked_rem(rhs)?),
+            (BinaryOperator::Add, Constant::Float(lhs), Constant::Float(rhs)) => Constant::Float(lhs + rhs),
+            (BinaryOperator::Subtract, Constant::Float(lhs), Constant::Float(rhs)) => Constant::Float(lhs - rhs),
+            (BinaryOperator::Multiply, Constant::Float(lhs), Constant::Float(rhs)) => Constant::Float(lhs * rhs),
+            (BinaryOperator::Divide, Constant::Float(lhs), Constant::Float(rhs)) => Constant::Float(lhs / rhs),
+            (BinaryOperator::Remainder, Constant::Float(lhs), Constant::Float(rhs)) => Constant::Float(lhs % rhs),
+            (BinaryOperator::GreaterThan, lhs, rhs) => Constant::Bool(compare(lhs, rhs)?.is_gt()),
+            (BinaryOperator::GreaterThanEquals, lhs, rhs) => Constant::Bool(compare(lhs, rhs)?.is_ge()),
+            (BinaryOperator::LessThan, lhs, rhs) => Constant::Bool(compare(lhs, rhs)?.is_lt()),
+            (BinaryOperator::LessThanEquals, lhs, rhs) => Constant::Bool(compare(lhs, rhs)?.is_le()),
+            (BinaryOperator::Equals, lhs, rhs) => Constant::Bool(equals(lhs, rhs)?),
+            (BinaryOperator::NotEquals, lhs, rhs) => Constant::Bool(!equals(lhs, rhs)?),
+            (BinaryOperator::LogicalAnd, Constant::Bool(true), Constant::Bool(rhs))
+            | (BinaryOperator::LogicalOr, Constant::Bool(false), Constant::Bool(rhs)) => Constant::Bool(rhs),
+            _ => return None,
+        };
+
+        Some(result.into_node(*span))
+    }
+
+    fn eliminate_branch(
+        &self,
+        IfExpression {
+            condition,
+            primary,
+            secondary,
+            span,
+        }: &IfExpression,
+    ) -> Option<SyntaxNode> {
+        match (self.constant(*condition)?, secondary) {
+            (Constant::Bool(true), _) => Some(self.syntax_tree.get(*primary).clone()),
+            (Constant::Bool(false), Some(secondary)) => Some(self.syntax_tree.get(*secondary).clone()),
+            (Constant::Bool(false), None) => Some(Constant::Unit.into_node(*span)),
+            _ => None,
+        }
+    }
+
+    fn eliminate_loop(&self, WhileLoop { condition, span, .. }: &WhileLoop) -> Option<SyntaxNode> {
+        match self.constant(*condition)? {
+            Constant::Bool(false) => Some(Constant::Unit.into_node(*span)),
+            _ => None,
+        }
+    }
+
+    // NOTE: Declarations following an exit are kept, since functions and classes are visible throughout their block
+    // and may refer to the other declarations around them.
+    fn eliminate_unreachable(&self, block: &Block) -> Option<SyntaxNode> {
+        let items = block
+            .expressions
+            .iter()
+            .chain(block.trailing_expression.iter())
+            .copied()
+            .collect::<Vec<_>>();
+        let exit = items.iter().position(|item| {
+            matches!(
+                self.syntax_tree.get(*item),
+                SyntaxNode::Return(_) | SyntaxNode::Break(_) | SyntaxNode::Continue(_)
+            )
+        })?;
+
+        let (reachable, unreachable) = items.split_at(exit + 1);
+        let declarations = unreachable
+            .iter()
+            .copied()
+            .filter(|item| self.is_declaration(*item))
+            .collect::<Vec<_>>();
+
+        if declarations.len() == unreachable.len() {
+            return None;
+        }
+
+        let trailing_expression = block
+            .trailing_expression
+            .filter(|trailing_expression| declarations.last() == Some(trailing_expression));
+        let expressions = reachable
+            .iter()
+            .chain(declarations.iter())
+            .copied()
+            .filter(|item| Some(*item) != trailing_expression)
+            .collect();
+
+        Some(SyntaxNode::Block(Block {
+            expressions,
+            trailing_expression,
+            span: block.span,
+        }))
+    }
+
+    fn is_declaration(&self, id: SyntaxNodeId) -> bool {
+        matches!(
+            self.syntax_tree.get(id),
+            SyntaxNode::VarDecl(_)
+                | SyntaxNode::FnDecl(_)
+                | SyntaxNode::OpDecl(_)
+                | SyntaxNode::ClassDecl(_)
+                | SyntaxNode::ImportDecl(_)
+                | SyntaxNode::ExportDecl(_)
+                | SyntaxNode::TypeAlias(_)
+        )
+    }
+}
+
+fn compare(lhs: Constant, rhs: Constant) -> Option<std::cmp::Ordering> {
+    match (lhs, rhs) {
+        (Constant::Bool(lhs), Constant::Bool(rhs)) => Some(lhs.cmp(&rhs)),
+        (Constant::Int(lhs), Constant::Int(rhs)) => Some(lhs.cmp(&rhs)),
+        // NOTE: Comparisons involving NaN are left to the runtime.
+        (Constant::Float(lhs), Constant::Float(rhs)) => lhs.partial_cmp(&rhs),
+        _ => None,
+    }
+}
+
+fn equals(lhs: Constant, rhs: Constant) -> Option<bool> {
+    match (lhs, rhs) {
+        (Constant::Null, Constant::Null) | (Constant::Unit, Constant::Unit) => Some(true),
+        (Constant::Null, _) | (_, Constant::Null) | (Constant::Unit, _) | (_, Constant::Unit) => Some(false),
+        (Constant::Bool(lhs), Constant::Bool(rhs)) => Some(lhs == rhs),
+        (Constant::Int(lhs), Constant::Int(rhs)) => Some(lhs == rhs),
+        (Constant::Float(lhs), Constant::Float(rhs)) => Some(lhs == rhs),
+        _ => None,
+    }
+}
+
+pub(crate) fn children(node: &SyntaxNode) -> Vec<SyntaxNodeId> {
+    match node {
+        SyntaxNode::LitIdent(_)
+        | SyntaxNode::LitNull(_)
+        | SyntaxNode::LitUnit(_)
+        | SyntaxNode::LitInt(_)
+        | SyntaxNode::LitFloat(_)
+        | SyntaxNode::LitString(_)
+        | SyntaxNode::LitBool(_)
+        | SyntaxNode::SuperAccess(_)
+        | SyntaxNode::ImportDecl(_)
+        | SyntaxNode::TypeAlias(_)
+        | SyntaxNode::Break(_)
+        | SyntaxNode::Continue(_) => Vec::new(),
+        SyntaxNode::LitList(list) => list.items.clone(),
+        SyntaxNode::LitObject(object) => object.items.iter().map(|(_, item)| *item).collect(),
+        SyntaxNode::LitMap(map) => map.entries.iter().flat_map(|(key, value)| [*key, *value]).collect(),
+        SyntaxNode::LitAnonymousFn(anonymous_fn) => vec![anonymous_fn.body],
+        SyntaxNode::FieldAccess(field_access) => vec![field_access.expression],
+        SyntaxNode::Index(index) => vec![index.expression, index.index_expression],
+        SyntaxNode::Slice(slice) => std::iter::once(slice.expression)
+            .chain(slice.start)
+            .chain(slice.end)
+            .collect(),
+        SyntaxNode::Prefix(prefix) => vec![prefix.expression],
+        SyntaxNode::Binary(binary) => vec![binary.lhs_expression, binary.rhs_expression],
+        SyntaxNode::Is(is) => vec![is.value],
+        SyntaxNode::NullPropagate(null_propagate) => vec![null_propagate.expression],
+        SyntaxNode::ErrorPropagate(error_propagate) => vec![error_propagate.expression],
+        SyntaxNode::Assignment(assignment) => vec![assignment.lhs_expression, assignment.rhs_expression],
+        SyntaxNode::VarDecl(var_decl) => vec![var_decl.expr],
+        SyntaxNode::FnDecl(fn_decl) => vec![fn_decl.body],
+        SyntaxNode::OpDecl(op_decl) => vec![op_decl.body],
+        SyntaxNode::ClassDecl(class_decl) => class_decl
+            .base
+            .iter()
+            .chain(class_decl.associated_items.iter())
+            .copied()
+            .collect(),
+        SyntaxNode::ExportDecl(export) => vec![export.export],
+        SyntaxNode::IfExpression(if_expression) => std::iter::once(if_expression.condition)
+            .chain(std::iter::once(if_expression.primary))
+            .chain(if_expression.secondary)
+            .collect(),
+        SyntaxNode::Loop(loop_) => vec![loop_.body],
+        SyntaxNode::WhileLoop(while_loop) => vec![while_loop.condition, while_loop.body],
+        SyntaxNode::ForLoop(for_loop) => vec![for_loop.source, for_loop.body],
+        SyntaxNode::Block(block) => block
+            .expressions
+            .iter()
+            .chain(block.trailing_expression.iter())
+            .copied()
+            .collect(),
+        SyntaxNode::Return(return_) => return_.result.into_iter().collect(),
+        SyntaxNode::FnCall(fn_call) => std::iter::once(fn_call.target)
+            .chain(fn_call.args.iter().copied())
+            .collect(),
+        SyntaxNode::SuperCall(super_call) => super_call.args.clone(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use dice_core::source::{Source, SourceKind};
+    use dice_syntax::Parser;
+
+    fn optimize(source: &str) -> (SyntaxTree, Block) {
+        let source = Source::new(source.to_owned(), SourceKind::Script);
+        let mut syntax_tree = Parser::new(&source).parse().expect("Source should parse.");
+        Optimizer::optimize(&mut syntax_tree);
+
+        match syntax_tree.get(syntax_tree.root()).clone() {
+            SyntaxNode::Block(block) => (syntax_tree, block),
+            node => panic!("Expected a block, found {:?}.", node),
+        }
+    }
+
+    #[test]
+    fn test_folds_arithmetic_and_preserves_span() {
+        let source = Source::new("2 * 3 + 1".to_owned(), SourceKind::Script);
+        let mut syntax_tree = Parser::new(&source).parse().expect("Source should parse.");
+        let trailing_expression = match syntax_tree.get(syntax_tree.root()) {
+            SyntaxNode::Block(block) => block
+                .trailing_expression
+                .expect("Block should have a trailing expression."),
+            node => panic!("Expected a block, found {:?}.", node),
+        };
+        let expected_span = syntax_tree.get(trailing_expression).span();
+
+        Optimizer::optimize(&mut syntax_tree);
+
+        match syntax_tree.get(trailing_expression) {
+            SyntaxNode::LitInt(LitInt { value, span }) => {
+                assert_eq!(7, *value);
+                assert_eq!(expected_span, *span);
+            }
+            node => panic!("Expected an int, found {:?}.", node),
+        }
+    }
+
+    #[test]
+    fn test_does_not_fold_runtime_errors() {
+        for source in &["1 / 0", "9223372036854775807 + 1", "!1"] {
+            let (syntax_tree, block) = optimize(source);
+            let trailing_expression = block
+                .trailing_expression
+                .expect("Block should have a trailing expression.");
+
+            assert!(!matches!(
+                syntax_tree.get(trailing_expression),
+                SyntaxNode::LitInt(_) | SyntaxNode::LitBool(_)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_eliminates_unreachable_code() {
+        let (syntax_tree, block) = optimize("if false { 1 } else { 2 }");
+        let trailing_expression = block
+            .trailing_expression
+            .expect("Block should have a trailing expression.");
+        assert!(matches!(syntax_tree.get(trailing_expression), SyntaxNode::Block(_)));
+
+        let (syntax_tree, block) = optimize("fn f() { return 1\n g()\n fn g() { 2 } }");
+        let body = match syntax_tree.get(block.trailing_expression.expect("Block should have a function.")) {
+            SyntaxNode::FnDecl(fn_decl) => syntax_tree.get(fn_decl.body),
+            node => panic!("Expected a function, found {:?}.", node),
+        };
+
+        match body {
+            SyntaxNode::Block(Block {
+                expressions,
+                trailing_expression: Some(trailing_expression),
+                ..
+            }) => {
+                assert_eq!(1, expressions.len());
+                assert!(matches!(syntax_tree.get(*trailing_expression), SyntaxNode::FnDecl(_)));
+            }
+            node => panic!("Expected a block, found {:?}.", node),
+        }
+    }
+}
@@ -0,0 +1,465 @@
+use std::{collections::HashMap, convert::TryFrom};
+
+use bytes::BufMut as _;
+
+use dice_bytecode::{BytecodeCursor, ConstantValue, Instruction};
+use dice_core::span::Span;
+
+struct Op {
+    instruction: Instruction,
+    // NOTE: Index and count operands, along with any flags, in the order they're encoded.
+    args: Vec<usize>,
+    // NOTE: Jump targets are stored as the index of the target op, where the number of ops marks the end of the code.
+    target: Option<usize>,
+    span: Option<Span>,
+}
+
+impl Op {
+    fn new(instruction: Instruction, args: Vec<usize>, target: Option<usize>, span: Option<Span>) -> Self {
+        Self {
+            instruction,
+            args,
+            target,
+            span,
+        }
+    }
+
+    fn is_wide(&self) -> bool {
+        self.args
+            .iter()
+            .enumerate()
+            .any(|(index, arg)| !self.is_flag(index) && *arg > u8::MAX as usize)
+    }
+
+    // NOTE: Flags are always a single byte, even when the instruction is widened.
+    fn is_flag(&self, index: usize) -> bool {
+        match self.instruction {
+            Instruction::LoadSlice => true,
+            Instruction::CreateClosure => index % 2 == 1,
+            _ => false,
+        }
+    }
+
+    fn len(&self) -> usize {
+        let is_wide = self.is_wide();
+        let args = (0..self.args.len())
+            .map(|index| match (self.is_flag(index), is_wide) {
+                (true, _) | (false, false) => 1,
+                (false, true) => 4,
+            })
+            .sum::<usize>();
+        let target = if self.target.is_some() { 2 } else { 0 };
+
+        is_wide as usize + 1 + args + target
+    }
+
+    // NOTE: Instructions that only push a value, which can be removed along with a POP that immediately follows them.
+    fn is_pure_push(&self) -> bool {
+        matches!(
+            self.instruction,
+            Instruction::PushNull
+                | Instruction::PushUnit
+                | Instruction::PushFalse
+                | Instruction::PushTrue
+                | Instruction::PushI0
+                | Instruction::PushI1
+                | Instruction::PushF0
+                | Instruction::PushF1
+                | Instruction::PushConst
+                | Instruction::Dup
+                | Instruction::LoadLocal
+                | Instruction::LoadUpvalue
+        )
+    }
+
+    fn is_constant_push(&self) -> bool {
+        matches!(
+            self.instruction,
+            Instruction::PushI0
+                | Instruction::PushI1
+                | Instruction::PushF0
+                | Instruction::PushF1
+                | Instruction::PushConst
+        )
+    }
+
+    fn is(&self, instruction: Instruction) -> bool {
+        self.instruction as u8 == instruction as u8
+    }
+}
+
+/// A pass over assembled bytecode that removes redundant instructions, threads jumps through unconditional jumps and
+/// fuses common sequences into superinstructions.
+// NOTE: Sequences are only rewritten when no jump lands inside of them, so every jump target survives the pass.
+pub(crate) struct Peephole<'a> {
+    constants: &'a mut Vec<ConstantValue>,
+}
+
+impl<'a> Peephole<'a> {
+    pub fn optimize(
+        data: &[u8],
+        source_map: &HashMap<u64, Span>,
+        constants: &'a mut Vec<ConstantValue>,
+    ) -> Option<(Vec<u8>, HashMap<u64, Span>)> {
+        let mut peephole = Self { constants };
+        let mut ops = peephole.decode(data, source_map);
+
+        loop {
+            Self::thread_jumps(&mut ops);
+
+            let (optimized, is_changed) = peephole.rewrite(ops);
+            ops = optimized;
+
+            if !is_changed {
+                break;
+            }
+        }
+
+        Self::encode(&ops)
+    }
+
+    fn decode(&self, data: &[u8], source_map: &HashMap<u64, Span>) -> Vec<Op> {
+        let mut cursor = BytecodeCursor::new(data);
+        let mut ops = Vec::new();
+        let mut positions = HashMap::new();
+
+        while let Some(instruction) = cursor.read_instruction() {
+            let offset = cursor.last_instruction_offset();
+            let mut target = None;
+            let args = match instruction {
+                Instruction::Jump | Instruction::JumpIfFalse | Instruction::JumpIfTrue => {
+                    target = Some(Self::read_target(&mut cursor));
+                    Vec::new()
+                }
+                Instruction::JumpUnlessLessThanLocal => {
+                    let args = vec![cursor.read_arg(), cursor.read_arg()];
+                    target = Some(Self::read_target(&mut cursor));
+                    args
+                }
+                Instruction::LoadFieldToLocal => vec![cursor.read_arg(), cursor.read_arg(), cursor.read_arg()],
+                Instruction::LoadField
+                | Instruction::StoreField
+                | Instruction::AssignField
+                | Instruction::LoadMethod => {
+                    vec![cursor.read_arg(), cursor.read_arg()]
+                }
+                Instruction::AssertTypeForLocal
+                | Instruction::AssertTypeOrNullForLocal
+                | Instruction::LoadLocalLoadLocal
+                | Instruction::AddLocalConst => vec![cursor.read_arg(), cursor.read_arg()],
+                Instruction::LoadSlice => vec![cursor.read_u8() as usize],
+                Instruction::PushConst
+                | Instruction::Dup
+                | Instruction::CreateArray
+                | Instruction::InheritClass
+                | Instruction::LoadModule
+                | Instruction::LoadGlobal
+                | Instruction::StoreGlobal
+                | Instruction::LoadLocal
+                | Instruction::StoreLocal
+                | Instruction::AssignLocal
+                | Instruction::LoadUpvalue
+                | Instruction::StoreUpvalue
+                | Instruction::AssignUpvalue
+                | Instruction::CloseUpvalue
+                | Instruction::StoreMethod
+                | Instruction::Call
+                | Instruction::TailCall
+                | Instruction::CallSuper
+                | Instruction::AssertTypeAndReturn
+                | Instruction::AssertTypeOrNullAndReturn => vec![cursor.read_arg()],
+                Instruction::CreateClosure => {
+                    let const_index = cursor.read_arg();
+                    let upvalue_count = match &self.constants[const_index] {
+                        ConstantValue::Function(function) => function.bytecode.upvalue_count(),
+                        _ => unreachable!("Closures should always refer to a function."),
+                    };
+                    let mut args = vec![const_index];
+
+                    for _ in 0..upvalue_count {
+                        args.push(cursor.read_u8() as usize);
+                        args.push(cursor.read_arg());
+                    }
+
+                    args
+                }
+                _ => Vec::new(),
+            };
+
+            positions.insert(offset as usize, ops.len());
+            ops.push(Op::new(instruction, args, target, source_map.get(&offset).copied()));
+        }
+
+        let op_count = ops.len();
+
+        for op in ops.iter_mut() {
+            op.target = op
+                .target
+                .map(|target| positions.get(&target).copied().unwrap_or(op_count));
+        }
+
+        ops
+    }
+
+    fn read_target(cursor: &mut BytecodeCursor) -> usize {
+        let offset = cursor.read_offset();
+
+        (cursor.position() as i64 + offset as i64) as usize
+    }
+
+    /// Encode the ops, unless a jump no longer fits in its 16 bit offset.
+    // NOTE: Threading jumps can move their targets further away than the assembler placed them.
+    fn encode(ops: &[Op]) -> Option<(Vec<u8>, HashMap<u64, Span>)> {
+        let mut offsets = Vec::with_capacity(ops.len() + 1);
+        let mut offset = 0;
+
+        for op in ops {
+            offsets.push(offset);
+            offset += op.len();
+        }
+
+        offsets.push(offset);
+
+        let mut data = Vec::with_capacity(offset);
+        let mut source_map = HashMap::new();
+
+        for op in ops {
+            let is_wide = op.is_wide();
+
+            if let Some(span) = op.span {
+                source_map.insert(data.len() as u64, span);
+            }
+
+            if is_wide {
+                data.put_u8(Instruction::Wide.into());
+            }
+
+            data.put_u8(op.instruction.into());
+
+            for (index, arg) in op.args.iter().enumerate() {
+                if op.is_flag(index) || !is_wide {
+                    data.put_u8(*arg as u8);
+                } else {
+                    data.put_u32(*arg as u32);
+                }
+            }
+
+            if let Some(target) = op.target {
+                let offset = offsets[target] as i64 - (data.len() as i64 + 2);
+                data.put_i16(i16::try_from(offset).ok()?);
+            }
+        }
+
+        Some((data, source_map))
+    }
+
+    /// Point any jump that lands on an unconditional jump directly at that jump's target.
+    fn thread_jumps(ops: &mut [Op]) {
+        for index in 0..ops.len() {
+            let mut target = match ops[index].target {
+                Some(target) => target,
+                None => continue,
+            };
+
+            // NOTE: The number of hops is bounded, so a cycle of jumps can't loop forever.
+            for _ in 0..ops.len() {
+                match ops.get(target) {
+                    Some(op) if op.is(Instruction::Jump) && op.target != Some(target) => {
+                        target = op.target.expect("Jumps should always have a target.")
+                    }
+                    _ => break,
+                }
+            }
+
+            ops[index].target = Some(target);
+        }
+    }
+
+    fn rewrite(&mut self, ops: Vec<Op>) -> (Vec<Op>, bool) {
+        let mut is_target = vec![false; ops.len() + 1];
+
+        for target in ops.iter().filter_map(|op| op.target) {
+            is_target[target] = true;
+        }
+
+        let mut optimized = Vec::with_capacity(ops.len());
+        let mut indices = vec![0; ops.len() + 1];
+        let mut ops = ops.into_iter().map(Some).collect::<Vec<_>>();
+        let mut is_changed = false;
+        let mut index = 0;
+
+        while index < ops.len() {
+            let window = ops[index..]
+                .iter()
+                .take(4)
+                .map(|op| op.as_ref().expect("Ops should only be taken once."))
+                .collect::<Vec<_>>();
+            let fusable = (1..window.len())
+                .take_while(|offset| !is_target[index + offset])
+                .count()
+                + 1;
+
+            match self.fuse(&window[..fusable], index) {
+                Some((consumed, replacement)) => {
+                    for offset in 0..consumed {
+                        indices[index + offset] = optimized.len();
+                    }
+
+                    optimized.extend(replacement);
+                    is_changed = true;
+                    index += consumed;
+                }
+                None => {
+                    indices[index] = optimized.len();
+                    optimized.extend(ops[index].take());
+                    index += 1;
+                }
+            }
+        }
+
+        indices[ops.len()] = optimized.len();
+
+        for op in optimized.iter_mut() {
+            op.target = op.target.map(|target| indices[target]);
+        }
+
+        (optimized, is_changed)
+    }
+
+    /// Match a sequence of ops starting at the given index, returning the number of ops consumed along with the op to
+    /// replace them with, if any.
+    fn fuse(&mut self, window: &[&Op], index: usize) -> Option<(usize, Option<Op>)> {
+        match window {
+            [push, pop, ..] if push.is_pure_push() && pop.is(Instruction::Pop) => Some((2, None)),
+            [jump, ..] if jump.is(Instruction::Jump) && jump.target == Some(index + 1) => Some((1, None)),
+            [load, push, less_than, jump, ..]
+                if load.is(Instruction::LoadLocal)
+                    && push.is_constant_push()
+                    && less_than.is(Instruction::LessThan)
+                    && jump.is(Instruction::JumpIfFalse) =>
+            {
+                let args = vec![load.args[0], self.constant_index(push)];
+                let op = Op::new(Instruction::JumpUnlessLessThanLocal, args, jump.target, less_than.span);
+
+                Some((4, Some(op)))
+            }
+            [load, push, add, ..]
+                if load.is(Instruction::LoadLocal) && push.is_constant_push() && add.is(Instruction::Add) =>
+            {
+                let args = vec![load.args[0], self.constant_index(push)];
+                let op = Op::new(Instruction::AddLocalConst, args, None, add.span);
+
+                Some((3, Some(op)))
+            }
+            [load, other_load, ..] if load.is(Instruction::LoadLocal) && other_load.is(Instruction::LoadLocal) => {
+                let args = vec![load.args[0], other_load.args[0]];
+                let op = Op::new(Instruction::LoadLocalLoadLocal, args, None, load.span);
+
+                Some((2, Some(op)))
+            }
+            _ => None,
+        }
+    }
+
+    fn constant_index(&mut self, push: &Op) -> usize {
+        let constant = match push.instruction {
+            Instruction::PushConst => return push.args[0],
+            Instruction::PushI0 => ConstantValue::Int(0),
+            Instruction::PushI1 => ConstantValue::Int(1),
+            Instruction::PushF0 => ConstantValue::Float(0.0),
+            Instruction::PushF1 => ConstantValue::Float(1.0),
+            _ => unreachable!("Only constant pushes have a constant index."),
+        };
+
+        match self.constants.iter().position(|current| *current == constant) {
+            Some(position) => position,
+            None => {
+                self.constants.push(constant);
+                self.constants.len() - 1
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::compiler::{Compiler, CompilerOptions};
+    use dice_bytecode::{Bytecode, Instruction};
+    use dice_core::source::{Source, SourceKind};
+
+    fn compile(source: &str, optimize: bool) -> Bytecode {
+        let source = Source::new(source.to_owned(), SourceKind::Script);
+        let options = CompilerOptions {
+            optimize,
+            ..Default::default()
+        };
+        let bytecode = Compiler::compile_source_with_options(source, options).expect("Source should compile.");
+        bytecode.verify().expect("Bytecode should verify.");
+
+        bytecode
+    }
+
+    fn instructions(bytecode: &Bytecode) -> Vec<String> {
+        let mut cursor = bytecode.cursor();
+        let mut instructions = Vec::new();
+
+        while let Some(instruction) = cursor.read_instruction() {
+            let name = instruction.to_string();
+            instructions.push(name[5..].to_owned());
+
+            match instruction {
+                Instruction::Jump | Instruction::JumpIfFalse | Instruction::JumpIfTrue => {
+                    cursor.read_offset();
+                }
+                Instruction::JumpUnlessLessThanLocal => {
+                    cursor.read_arg();
+                    cursor.read_arg();
+                    cursor.read_offset();
+                }
+                Instruction::LoadLocalLoadLocal | Instruction::AddLocalConst => {
+                    cursor.read_arg();
+                    cursor.read_arg();
+                }
+                Instruction::PushConst
+                | Instruction::LoadLocal
+                | Instruction::StoreLocal
+                | Instruction::AssignLocal => {
+                    cursor.read_arg();
+                }
+                _ => (),
+            }
+        }
+
+        instructions
+    }
+
+    #[test]
+    fn test_fuses_counting_loop() {
+        let bytecode = compile("let mut x = 0 while x < 100000 { x = x + 1 }", true);
+
+        assert_eq!(
+            vec![
+                "PUSH_I0",
+                "STORE_LOCAL",
+                "POP",
+                "JUMP_UNLESS_LT_LOCAL",
+                "ADD_LOCAL_CONST",
+                "ASSIGN_LOCAL",
+                "POP",
+                "JUMP",
+                "PUSH_UNIT"
+            ],
+            instructions(&bytecode)
+        );
+    }
+
+    #[test]
+    fn test_fuses_branch_condition() {
+        let source = "let a = 1 let b = 2 let c = if a < b { a } else { b } c";
+        let unoptimized = compile(source, false);
+        let optimized = compile(source, true);
+
+        assert!(instructions(&optimized).len() < instructions(&unoptimized).len());
+        assert!(instructions(&optimized).contains(&"LOAD_LOCAL_LOAD_LOCAL".to_owned()));
+    }
+}
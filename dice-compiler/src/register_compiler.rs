@@ -0,0 +1,766 @@
+use std::{collections::HashMap, convert::TryFrom};
+
+use dice_bytecode::{ConstantValue, Register, RegisterBytecode, RegisterInstruction};
+use dice_core::{
+    error::{
+        codes::{
+            CANNOT_REASSIGN_IMMUTABLE_VARIABLE, INVALID_BREAK_USAGE, INVALID_CONTINUE_USAGE,
+            UNSUPPORTED_BY_REGISTER_BACKEND,
+        },
+        Error, ResultExt as _,
+    },
+    source::{Source, SourceKind},
+    span::Span,
+    tags,
+};
+use dice_syntax::{
+    Assignment, AssignmentOperator, Binary, BinaryOperator, Block, ForLoop, IfExpression, Parser, SyntaxNode,
+    SyntaxNodeId, SyntaxTree, UnaryOperator, VarDecl, VarDeclKind,
+};
+
+use crate::{
+    compiler::CompilerOptions,
+    optimizer::{children, Optimizer},
+    type_checker::TypeChecker,
+};
+
+static SCRIPT_NAME: &str = "<script>";
+
+#[derive(Clone, Copy)]
+struct Local {
+    register: Register,
+    is_mutable: bool,
+}
+
+struct Scope {
+    variables: Vec<(String, Local)>,
+    /// The first register available for temporaries, past any locals declared in this scope.
+    top: usize,
+    /// The first register available when the scope was entered, restored when it's exited.
+    start: usize,
+}
+
+struct LoopContext {
+    continue_target: Option<u32>,
+    exit_points: Vec<usize>,
+}
+
+/// Generates code for the register machine, mapping each local directly to a register.
+///
+/// Only a subset of the language is supported: scripts made up of literals, locals, operators and control flow.
+/// Anything else fails with `UNSUPPORTED_BY_REGISTER_BACKEND`, so that callers can fall back to the stack based
+/// [`Compiler`](crate::compiler::Compiler).
+pub struct RegisterCompiler {
+    syntax_tree: SyntaxTree,
+    source: Source,
+    instructions: Vec<RegisterInstruction>,
+    constants: Vec<ConstantValue>,
+    source_map: HashMap<usize, Span>,
+    scopes: Vec<Scope>,
+    loops: Vec<LoopContext>,
+    constant_registers: HashMap<u32, Register>,
+    next_register: usize,
+    register_count: usize,
+}
+
+impl RegisterCompiler {
+    pub fn compile_source(source: Source) -> Result<RegisterBytecode, Error> {
+        Self::compile_source_with_options(source, CompilerOptions::default())
+    }
+
+    pub fn compile_source_with_options(source: Source, options: CompilerOptions) -> Result<RegisterBytecode, Error> {
+        let mut syntax_tree = Parser::new(&source).parse()?;
+
+        if options.type_check {
+            TypeChecker::check(&syntax_tree).with_source(|| source.clone())?;
+        }
+
+        if options.optimize {
+            Optimizer::optimize(&mut syntax_tree);
+        }
+
+        let mut compiler = Self {
+            syntax_tree,
+            source,
+            instructions: Vec::new(),
+            constants: Vec::new(),
+            source_map: HashMap::new(),
+            scopes: Vec::new(),
+            loops: Vec::new(),
+            constant_registers: HashMap::new(),
+            next_register: 0,
+            register_count: 0,
+        };
+
+        compiler.compile().with_source(|| compiler.source.clone())
+    }
+
+    fn compile(&mut self) -> Result<RegisterBytecode, Error> {
+        let root = self.syntax_tree.root();
+        let span = self.syntax_tree.get(root).span();
+
+        if let SourceKind::Module = self.source.kind() {
+            return Err(Error::new(UNSUPPORTED_BY_REGISTER_BACKEND).with_span(span));
+        }
+
+        self.hoist_constants(root)?;
+
+        let result = self.allocate(span)?;
+        self.expression(root, Some(result))?;
+        self.emit(RegisterInstruction::Return { src: result }, span);
+
+        Ok(RegisterBytecode::new(
+            std::mem::take(&mut self.instructions).into_boxed_slice(),
+            self.register_count,
+            std::mem::take(&mut self.constants).into_boxed_slice(),
+            SCRIPT_NAME,
+            self.source.clone(),
+            std::mem::take(&mut self.source_map),
+        ))
+    }
+
+    /// Load every constant into a register of its own up front, so that they can be used as operands directly instead
+    /// of being reloaded each time they're evaluated.
+    fn hoist_constants(&mut self, root: SyntaxNodeId) -> Result<(), Error> {
+        let mut pending = vec![root];
+
+        while let Some(id) = pending.pop() {
+            let node = self.syntax_tree.get(id);
+            let span = node.span();
+
+            match literal_constant(node) {
+                Some(value) => {
+                    let index = self.constant(value, span)?;
+
+                    if !self.constant_registers.contains_key(&index) {
+                        let register = self.allocate(span)?;
+                        self.emit(RegisterInstruction::LoadConst { dst: register, index }, span);
+                        self.constant_registers.insert(index, register);
+                    }
+                }
+                None => pending.extend(children(node)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Compile an expression, writing its result to `dst`.  If there's no destination, the result is discarded.
+    fn expression(&mut self, id: SyntaxNodeId, dst: Option<Register>) -> Result<(), Error> {
+        let node = self.syntax_tree.get(id).clone();
+        let span = node.span();
+
+        match node {
+            SyntaxNode::LitNull(_) => self.load(dst, |dst| RegisterInstruction::LoadNull { dst }, span),
+            SyntaxNode::LitUnit(_) => self.load(dst, |dst| RegisterInstruction::LoadUnit { dst }, span),
+            SyntaxNode::LitBool(lit) => {
+                self.load(dst, |dst| RegisterInstruction::LoadBool { dst, value: lit.value }, span)
+            }
+            SyntaxNode::LitInt(lit) => self.load_const(dst, ConstantValue::Int(lit.value), span)?,
+            SyntaxNode::LitFloat(lit) => self.load_const(dst, ConstantValue::Float(lit.value), span)?,
+            SyntaxNode::LitString(lit) => self.load_const(dst, ConstantValue::String(lit.value), span)?,
+            SyntaxNode::LitIdent(lit) => {
+                let local = self.local(&lit.identifier, span)?;
+                self.copy(dst, local.register, span);
+            }
+            SyntaxNode::Prefix(prefix) => {
+                let src = self.operand(prefix.expression)?;
+                let dst = self.target(dst, span)?;
+                let instruction = match prefix.operator {
+                    UnaryOperator::Negate => RegisterInstruction::Negate { dst, src },
+                    UnaryOperator::Not => RegisterInstruction::Not { dst, src },
+                };
+
+                self.emit(instruction, span);
+            }
+            SyntaxNode::Binary(binary) => self.binary(&binary, dst)?,
+            SyntaxNode::Assignment(assignment) => self.assignment(&assignment, dst)?,
+            SyntaxNode::VarDecl(var_decl) => self.var_decl(&var_decl, dst)?,
+            SyntaxNode::Block(block) => self.block(&block, dst)?,
+            SyntaxNode::IfExpression(if_expression) => self.if_expression(&if_expression, dst)?,
+            SyntaxNode::WhileLoop(while_loop) => {
+                let loop_start = self.position();
+                let condition = self.operand(while_loop.condition)?;
+                let loop_exit = self.emit(
+                    RegisterInstruction::JumpIfFalse {
+                        src: condition,
+                        target: 0,
+                    },
+                    span,
+                );
+
+                self.loop_body(while_loop.body, loop_start, span)?;
+                self.patch(loop_exit);
+                self.load(dst, |dst| RegisterInstruction::LoadUnit { dst }, span);
+            }
+            SyntaxNode::Loop(loop_) => {
+                let loop_start = self.position();
+
+                self.loop_body(loop_.body, loop_start, span)?;
+                self.load(dst, |dst| RegisterInstruction::LoadUnit { dst }, span);
+            }
+            SyntaxNode::ForLoop(for_loop) => self.range_loop(&for_loop, dst)?,
+            SyntaxNode::Break(_) => {
+                if self.loops.is_empty() {
+                    return Err(Error::new(INVALID_BREAK_USAGE).with_span(span));
+                }
+
+                let exit_point = self.emit(RegisterInstruction::Jump { target: 0 }, span);
+                self.loops
+                    .last_mut()
+                    .expect("Loop should exist.")
+                    .exit_points
+                    .push(exit_point);
+            }
+            SyntaxNode::Continue(_) => {
+                let target = match self.loops.last() {
+                    Some(LoopContext {
+                        continue_target: Some(target),
+                        ..
+                    }) => *target,
+                    Some(_) => return Err(Error::new(UNSUPPORTED_BY_REGISTER_BACKEND).with_span(span)),
+                    None => return Err(Error::new(INVALID_CONTINUE_USAGE).with_span(span)),
+                };
+
+                self.emit(RegisterInstruction::Jump { target }, span);
+            }
+            node => return Err(Error::new(UNSUPPORTED_BY_REGISTER_BACKEND).with_span(node.span())),
+        }
+
+        Ok(())
+    }
+
+    fn statement(&mut self, id: SyntaxNodeId) -> Result<(), Error> {
+        self.expression(id, None)?;
+
+        // NOTE: Temporaries don't outlive the statement that created them.
+        self.next_register = self.scopes.last().map_or(self.next_register, |scope| scope.top);
+
+        Ok(())
+    }
+
+    fn block(&mut self, block: &Block, dst: Option<Register>) -> Result<(), Error> {
+        self.push_scope();
+
+        for expression in block.expressions.iter() {
+            self.statement(*expression)?;
+        }
+
+        match block.trailing_expression {
+            Some(trailing_expression) => self.expression(trailing_expression, dst)?,
+            None => self.load(dst, |dst| RegisterInstruction::LoadUnit { dst }, block.span),
+        }
+
+        self.pop_scope();
+
+        Ok(())
+    }
+
+    fn var_decl(&mut self, var_decl: &VarDecl, dst: Option<Register>) -> Result<(), Error> {
+        let name = match &var_decl.kind {
+            VarDeclKind::Singular(name) if var_decl.type_.is_none() => name.clone(),
+            _ => return Err(Error::new(UNSUPPORTED_BY_REGISTER_BACKEND).with_span(var_decl.span)),
+        };
+
+        // NOTE: The variable isn't in scope until after its initializer, which is evaluated straight into its register.
+        let register = self.allocate(var_decl.span)?;
+        self.expression(var_decl.expr, Some(register))?;
+        self.next_register = register as usize + 1;
+
+        let scope = self
+            .scopes
+            .last_mut()
+            .expect("Variables should always be declared in a scope.");
+        scope.variables.push((
+            name,
+            Local {
+                register,
+                is_mutable: var_decl.is_mutable,
+            },
+        ));
+        scope.top = scope.top.max(register as usize + 1);
+
+        self.copy(dst, register, var_decl.span);
+
+        Ok(())
+    }
+
+    fn assignment(&mut self, assignment: &Assignment, dst: Option<Register>) -> Result<(), Error> {
+        let span = assignment.span;
+        let (name, local) = match self.syntax_tree.get(assignment.lhs_expression) {
+            SyntaxNode::LitIdent(lit_ident) => {
+                let name = lit_ident.identifier.clone();
+                let local = self.local(&name, span)?;
+
+                (name, local)
+            }
+            _ => return Err(Error::new(UNSUPPORTED_BY_REGISTER_BACKEND).with_span(span)),
+        };
+
+        if !local.is_mutable {
+            return Err(Error::new(CANNOT_REASSIGN_IMMUTABLE_VARIABLE)
+                .with_span(span)
+                .with_tags(tags! {
+                    name => name
+                }));
+        }
+
+        let target = local.register;
+
+        match assignment.operator {
+            AssignmentOperator::Assignment if self.writes_once(assignment.rhs_expression) => {
+                self.expression(assignment.rhs_expression, Some(target))?;
+            }
+            AssignmentOperator::Assignment => {
+                let src = self.operand(assignment.rhs_expression)?;
+                self.copy(Some(target), src, span);
+            }
+            operator => {
+                let lhs = self.stable_operand(target, assignment.rhs_expression, span)?;
+                let rhs = self.operand(assignment.rhs_expression)?;
+                let instruction = match operator {
+                    AssignmentOperator::MulAssignment => RegisterInstruction::Multiply { dst: target, lhs, rhs },
+                    AssignmentOperator::DivAssignment => RegisterInstruction::Divide { dst: target, lhs, rhs },
+                    AssignmentOperator::AddAssignment => RegisterInstruction::Add { dst: target, lhs, rhs },
+                    AssignmentOperator::SubAssignment => RegisterInstruction::Subtract { dst: target, lhs, rhs },
+                    AssignmentOperator::Assignment => unreachable!(),
+                };
+
+                self.emit(instruction, span);
+            }
+        }
+
+        self.copy(dst, target, span);
+
+        Ok(())
+    }
+
+    fn binary(&mut self, binary: &Binary, dst: Option<Register>) -> Result<(), Error> {
+        let span = binary.span;
+
+        match binary.operator {
+            BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr => {
+                let dst = self.target(dst, span)?;
+
+                self.expression(binary.lhs_expression, Some(dst))?;
+                self.emit(RegisterInstruction::AssertBool { src: dst }, span);
+
+                let short_circuit_jump = match binary.operator {
+                    BinaryOperator::LogicalAnd => RegisterInstruction::JumpIfFalse { src: dst, target: 0 },
+                    _ => RegisterInstruction::JumpIfTrue { src: dst, target: 0 },
+                };
+                let short_circuit_jump = self.emit(short_circuit_jump, span);
+
+                self.expression(binary.rhs_expression, Some(dst))?;
+                self.emit(RegisterInstruction::AssertBool { src: dst }, span);
+                self.patch(short_circuit_jump);
+            }
+            BinaryOperator::Coalesce => {
+                let dst = self.target(dst, span)?;
+
+                self.expression(binary.lhs_expression, Some(dst))?;
+
+                let not_null_jump = self.emit(RegisterInstruction::JumpIfNotNull { src: dst, target: 0 }, span);
+
+                self.expression(binary.rhs_expression, Some(dst))?;
+                self.patch(not_null_jump);
+            }
+            BinaryOperator::Pipeline | BinaryOperator::RangeInclusive | BinaryOperator::RangeExclusive => {
+                return Err(Error::new(UNSUPPORTED_BY_REGISTER_BACKEND).with_span(span));
+            }
+            operator => {
+                let lhs = match self.local_operand(binary.lhs_expression) {
+                    Some(register) => self.stable_operand(register, binary.rhs_expression, span)?,
+                    None => self.operand(binary.lhs_expression)?,
+                };
+                let rhs = self.operand(binary.rhs_expression)?;
+                let dst = self.target(dst, span)?;
+                let instruction = match operator {
+                    BinaryOperator::Multiply => RegisterInstruction::Multiply { dst, lhs, rhs },
+                    BinaryOperator::Divide => RegisterInstruction::Divide { dst, lhs, rhs },
+                    BinaryOperator::Remainder => RegisterInstruction::Remainder { dst, lhs, rhs },
+                    BinaryOperator::Add => RegisterInstruction::Add { dst, lhs, rhs },
+                    BinaryOperator::Subtract => RegisterInstruction::Subtract { dst, lhs, rhs },
+                    BinaryOperator::GreaterThan => RegisterInstruction::GreaterThan { dst, lhs, rhs },
+                    BinaryOperator::LessThan => RegisterInstruction::LessThan { dst, lhs, rhs },
+                    BinaryOperator::GreaterThanEquals => RegisterInstruction::GreaterThanOrEqual { dst, lhs, rhs },
+                    BinaryOperator::LessThanEquals => RegisterInstruction::LessThanOrEqual { dst, lhs, rhs },
+                    BinaryOperator::Equals => RegisterInstruction::Equal { dst, lhs, rhs },
+                    BinaryOperator::NotEquals => RegisterInstruction::NotEqual { dst, lhs, rhs },
+                    _ => unreachable!(),
+                };
+
+                self.emit(instruction, span);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn if_expression(&mut self, if_expression: &IfExpression, dst: Option<Register>) -> Result<(), Error> {
+        let span = if_expression.span;
+        let condition = self.operand(if_expression.condition)?;
+        let if_jump = self.emit(
+            RegisterInstruction::JumpIfFalse {
+                src: condition,
+                target: 0,
+            },
+            span,
+        );
+
+        self.expression(if_expression.primary, dst)?;
+
+        if if_expression.secondary.is_none() && dst.is_none() {
+            self.patch(if_jump);
+            return Ok(());
+        }
+
+        let else_jump = self.emit(RegisterInstruction::Jump { target: 0 }, span);
+        self.patch(if_jump);
+
+        match if_expression.secondary {
+            Some(secondary) => self.expression(secondary, dst)?,
+            None => self.load(dst, |dst| RegisterInstruction::LoadUnit { dst }, span),
+        }
+
+        self.patch(else_jump);
+
+        Ok(())
+    }
+
+    fn range_loop(&mut self, for_loop: &ForLoop, dst: Option<Register>) -> Result<(), Error> {
+        let span = for_loop.span;
+        let (start, end, is_inclusive) = match self.syntax_tree.get(for_loop.source) {
+            SyntaxNode::Binary(Binary {
+                operator: BinaryOperator::RangeExclusive,
+                lhs_expression,
+                rhs_expression,
+                ..
+            }) => (*lhs_expression, *rhs_expression, false),
+            SyntaxNode::Binary(Binary {
+                operator: BinaryOperator::RangeInclusive,
+                lhs_expression,
+                rhs_expression,
+                ..
+            }) => (*lhs_expression, *rhs_expression, true),
+            _ => return Err(Error::new(UNSUPPORTED_BY_REGISTER_BACKEND).with_span(span)),
+        };
+
+        // NOTE: Mirror the stack based range loop, evaluating the end first, then counting up from the start.
+        let end_register = self.allocate(span)?;
+        self.expression(end, Some(end_register))?;
+        let counter = self.allocate(span)?;
+        self.expression(start, Some(counter))?;
+        let one = self.allocate(span)?;
+        self.load_const(Some(one), ConstantValue::Int(1), span)?;
+        let condition = self.allocate(span)?;
+
+        let loop_start = self.position();
+        let compare = if is_inclusive {
+            RegisterInstruction::LessThanOrEqual {
+                dst: condition,
+                lhs: counter,
+                rhs: end_register,
+            }
+        } else {
+            RegisterInstruction::LessThan {
+                dst: condition,
+                lhs: counter,
+                rhs: end_register,
+            }
+        };
+        self.emit(compare, span);
+        let loop_exit = self.emit(
+            RegisterInstruction::JumpIfFalse {
+                src: condition,
+                target: 0,
+            },
+            span,
+        );
+
+        self.push_scope();
+        let variable = self.allocate(span)?;
+        let scope = self.scopes.last_mut().expect("Scope should exist.");
+        scope.variables.push((
+            for_loop.variable.clone(),
+            Local {
+                register: variable,
+                is_mutable: false,
+            },
+        ));
+        scope.top = variable as usize + 1;
+        self.emit(
+            RegisterInstruction::Move {
+                dst: variable,
+                src: counter,
+            },
+            span,
+        );
+
+        // NOTE: The stack based range loop has no continue target, so neither does this one.
+        self.loops.push(LoopContext {
+            continue_target: None,
+            exit_points: Vec::new(),
+        });
+        self.statement(for_loop.body)?;
+        let loop_context = self.loops.pop().expect("Loop should exist.");
+        self.pop_scope();
+
+        self.emit(
+            RegisterInstruction::Add {
+                dst: counter,
+                lhs: counter,
+                rhs: one,
+            },
+            span,
+        );
+        self.emit(RegisterInstruction::Jump { target: loop_start }, span);
+        self.patch(loop_exit);
+
+        for exit_point in loop_context.exit_points {
+            self.patch(exit_point);
+        }
+
+        self.load(dst, |dst| RegisterInstruction::LoadUnit { dst }, span);
+
+        Ok(())
+    }
+
+    fn loop_body(&mut self, body: SyntaxNodeId, loop_start: u32, span: Span) -> Result<(), Error> {
+        self.loops.push(LoopContext {
+            continue_target: Some(loop_start),
+            exit_points: Vec::new(),
+        });
+        self.expression(body, None)?;
+        self.emit(RegisterInstruction::Jump { target: loop_start }, span);
+
+        let loop_context = self.loops.pop().expect("Loop should exist.");
+
+        for exit_point in loop_context.exit_points {
+            self.patch(exit_point);
+        }
+
+        Ok(())
+    }
+
+    /// Get a register holding the value of the expression, using a local's register directly where possible.
+    fn operand(&mut self, id: SyntaxNodeId) -> Result<Register, Error> {
+        if let Some(register) = self.local_operand(id).or_else(|| self.constant_operand(id)) {
+            return Ok(register);
+        }
+
+        let span = self.syntax_tree.get(id).span();
+        let register = self.allocate(span)?;
+        self.expression(id, Some(register))?;
+
+        Ok(register)
+    }
+
+    fn local_operand(&self, id: SyntaxNodeId) -> Option<Register> {
+        match self.syntax_tree.get(id) {
+            SyntaxNode::LitIdent(lit_ident) => self.resolve(&lit_ident.identifier).map(|local| local.register),
+            _ => None,
+        }
+    }
+
+    fn constant_operand(&self, id: SyntaxNodeId) -> Option<Register> {
+        let value = literal_constant(self.syntax_tree.get(id))?;
+        let index = self.constants.iter().position(|constant| *constant == value)?;
+
+        self.constant_registers.get(&(index as u32)).copied()
+    }
+
+    /// Copy a local to a temporary if the expression evaluated after it could reassign it, so that the value read
+    /// matches the value the stack based backend would have loaded.
+    fn stable_operand(&mut self, register: Register, next: SyntaxNodeId, span: Span) -> Result<Register, Error> {
+        if !self.may_assign(next) {
+            return Ok(register);
+        }
+
+        let copy = self.allocate(span)?;
+        self.copy(Some(copy), register, span);
+
+        Ok(copy)
+    }
+
+    // NOTE: Conservative, anything other than literals and arithmetic is assumed to assign.
+    fn may_assign(&self, id: SyntaxNodeId) -> bool {
+        match self.syntax_tree.get(id) {
+            SyntaxNode::LitNull(_)
+            | SyntaxNode::LitUnit(_)
+            | SyntaxNode::LitBool(_)
+            | SyntaxNode::LitInt(_)
+            | SyntaxNode::LitFloat(_)
+            | SyntaxNode::LitString(_)
+            | SyntaxNode::LitIdent(_) => false,
+            SyntaxNode::Prefix(prefix) => self.may_assign(prefix.expression),
+            SyntaxNode::Binary(binary) => {
+                self.may_assign(binary.lhs_expression) || self.may_assign(binary.rhs_expression)
+            }
+            _ => true,
+        }
+    }
+
+    /// Returns true if the expression only writes its destination once all of its operands have been read, so that
+    /// it can safely be evaluated straight into the register of the local it's assigned to.
+    fn writes_once(&self, id: SyntaxNodeId) -> bool {
+        match self.syntax_tree.get(id) {
+            SyntaxNode::Binary(binary) => !matches!(
+                binary.operator,
+                BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr | BinaryOperator::Coalesce
+            ),
+            SyntaxNode::Prefix(_) => true,
+            _ => !self.may_assign(id),
+        }
+    }
+
+    fn resolve(&self, name: &str) -> Option<Local> {
+        self.scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.variables.iter().rev())
+            .find(|(variable, _)| variable == name)
+            .map(|(_, local)| *local)
+    }
+
+    // NOTE: Globals live outside of the register file, so any name that isn't a local is left to the stack backend.
+    fn local(&self, name: &str, span: Span) -> Result<Local, Error> {
+        self.resolve(name)
+            .ok_or_else(|| Error::new(UNSUPPORTED_BY_REGISTER_BACKEND).with_span(span))
+    }
+
+    fn push_scope(&mut self) {
+        self.scopes.push(Scope {
+            variables: Vec::new(),
+            top: self.next_register,
+            start: self.next_register,
+        });
+    }
+
+    fn pop_scope(&mut self) {
+        let scope = self.scopes.pop().expect("Scope should exist.");
+        self.next_register = scope.start;
+    }
+
+    fn allocate(&mut self, span: Span) -> Result<Register, Error> {
+        let register = Register::try_from(self.next_register)
+            .map_err(|_| Error::new(UNSUPPORTED_BY_REGISTER_BACKEND).with_span(span))?;
+
+        self.next_register += 1;
+        self.register_count = self.register_count.max(self.next_register);
+
+        Ok(register)
+    }
+
+    fn target(&mut self, dst: Option<Register>, span: Span) -> Result<Register, Error> {
+        match dst {
+            Some(dst) => Ok(dst),
+            None => self.allocate(span),
+        }
+    }
+
+    fn load(&mut self, dst: Option<Register>, instruction: impl FnOnce(Register) -> RegisterInstruction, span: Span) {
+        if let Some(dst) = dst {
+            self.emit(instruction(dst), span);
+        }
+    }
+
+    fn load_const(&mut self, dst: Option<Register>, value: ConstantValue, span: Span) -> Result<(), Error> {
+        if let Some(dst) = dst {
+            let index = self.constant(value, span)?;
+            self.emit(RegisterInstruction::LoadConst { dst, index }, span);
+        }
+
+        Ok(())
+    }
+
+    fn constant(&mut self, value: ConstantValue, span: Span) -> Result<u32, Error> {
+        let index = match self.constants.iter().position(|constant| *constant == value) {
+            Some(index) => index,
+            None => {
+                self.constants.push(value);
+                self.constants.len() - 1
+            }
+        };
+
+        u32::try_from(index).map_err(|_| Error::new(UNSUPPORTED_BY_REGISTER_BACKEND).with_span(span))
+    }
+
+    fn copy(&mut self, dst: Option<Register>, src: Register, span: Span) {
+        match dst {
+            Some(dst) if dst != src => {
+                self.emit(RegisterInstruction::Move { dst, src }, span);
+            }
+            _ => {}
+        }
+    }
+
+    fn emit(&mut self, instruction: RegisterInstruction, span: Span) -> usize {
+        let index = self.instructions.len();
+
+        self.instructions.push(instruction);
+        self.source_map.insert(index, span);
+
+        index
+    }
+
+    fn position(&self) -> u32 {
+        self.instructions.len() as u32
+    }
+
+    /// Point the jump at the given index to the next instruction to be emitted.
+    fn patch(&mut self, index: usize) {
+        let position = self.position();
+
+        match &mut self.instructions[index] {
+            RegisterInstruction::Jump { target }
+            | RegisterInstruction::JumpIfFalse { target, .. }
+            | RegisterInstruction::JumpIfTrue { target, .. }
+            | RegisterInstruction::JumpIfNotNull { target, .. } => *target = position,
+            instruction => unreachable!("Cannot patch non-jump instruction {}.", instruction),
+        }
+    }
+}
+
+fn literal_constant(node: &SyntaxNode) -> Option<ConstantValue> {
+    match node {
+        SyntaxNode::LitInt(lit) => Some(ConstantValue::Int(lit.value)),
+        SyntaxNode::LitFloat(lit) => Some(ConstantValue::Float(lit.value)),
+        SyntaxNode::LitString(lit) => Some(ConstantValue::String(lit.value.clone())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use dice_bytecode::RegisterInstruction;
+    use dice_core::{
+        error::codes::UNSUPPORTED_BY_REGISTER_BACKEND,
+        source::{Source, SourceKind},
+    };
+
+    use super::RegisterCompiler;
+
+    #[test]
+    fn test_locals_are_used_as_operands() {
+        let source = Source::new("let mut x = 0 while x < 10 { x += 1 }", SourceKind::Script);
+        let bytecode = RegisterCompiler::compile_source(source).unwrap();
+        let instructions = bytecode.instructions();
+
+        // NOTE: The loop body adds straight into the local's register, without any moves.
+        assert!(instructions
+            .iter()
+            .any(|instruction| matches!(instruction, RegisterInstruction::Add { dst, lhs, .. } if dst == lhs)));
+        assert!(!instructions
+            .iter()
+            .any(|instruction| matches!(instruction, RegisterInstruction::Move { .. })));
+    }
+
+    #[test]
+    fn test_unsupported_expressions_are_rejected() {
+        let source = Source::new("let x = [1, 2, 3]", SourceKind::Script);
+        let error = RegisterCompiler::compile_source(source).unwrap_err();
+
+        assert_eq!(UNSUPPORTED_BY_REGISTER_BACKEND, error.error_code());
+    }
+}
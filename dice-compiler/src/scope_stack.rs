@@ -1,4 +1,5 @@
 use dice_core::error::{codes::INTERNAL_COMPILER_ERROR, Error};
+use dice_syntax::TypeAnnotation;
 
 #[derive(Clone)]
 pub struct ScopeVariable {
@@ -57,6 +58,7 @@ pub struct ScopeContext {
     pub entry_point: Option<usize>,
     pub exit_points: Vec<usize>,
     pub variables: Vec<ScopeVariable>,
+    pub type_aliases: Vec<(String, TypeAnnotation)>,
     pub call_context: CallContext,
     slot_count: usize,
 }
@@ -69,6 +71,7 @@ impl Default for ScopeContext {
             entry_point: None,
             exit_points: Vec::new(),
             variables: Vec::new(),
+            type_aliases: Vec::new(),
             call_context: Default::default(),
             slot_count: 0,
         }
@@ -142,6 +145,22 @@ impl ScopeStack {
             .find(|var| var.name == name)
     }
 
+    // NOTE: Type aliases are resolved entirely at compile time, so they don't occupy a slot.
+    pub fn add_type_alias(&mut self, name: impl Into<String>, type_: TypeAnnotation) -> Result<(), Error> {
+        self.top_mut()?.type_aliases.push((name.into(), type_));
+
+        Ok(())
+    }
+
+    pub fn type_alias(&self, name: &str) -> Option<&TypeAnnotation> {
+        self.stack
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.type_aliases.iter().rev())
+            .find(|(alias, _)| alias == name)
+            .map(|(_, type_)| type_)
+    }
+
     pub fn top_mut(&mut self) -> Result<&mut ScopeContext, Error> {
         self.stack.last_mut().ok_or_else(|| Error::new(INTERNAL_COMPILER_ERROR))
     }
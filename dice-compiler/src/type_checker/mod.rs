@@ -0,0 +1,785 @@
+use std::collections::HashMap;
+
+use dice_core::{
+    error::{codes::TYPE_MISMATCH, Error},
+    protocol::{
+        class::{NEW, SELF},
+        object::ANY_CLASS,
+        types::{ARRAY, FUNCTION, MAP},
+    },
+    span::Span,
+    tags,
+};
+use dice_syntax::{
+    Assignment, AssignmentOperator, Binary, BinaryOperator, Block, ClassDecl, FnArg, FnCall, FnDecl, IfExpression,
+    LitAnonymousFn, OpDecl, Prefix, Return, SyntaxNode, SyntaxNodeId, SyntaxTree, TypeAnnotation, TypeAnnotationKind,
+    UnaryOperator, VarDecl, VarDeclKind,
+};
+
+use ty::{Type, BOOL, FLOAT, INT, STRING, UNIT};
+
+mod ty;
+
+#[derive(Clone)]
+enum Binding {
+    Value(Type),
+    Class(String),
+}
+
+#[derive(Default)]
+struct Scope {
+    locals: HashMap<String, Binding>,
+    type_aliases: HashMap<String, Type>,
+}
+
+struct ClassInfo {
+    base: Option<String>,
+    constructor: Option<Vec<Type>>,
+}
+
+/// An optional pass over the syntax tree that infers the types of locals from literals and annotations, reporting
+/// values that can never satisfy an annotation before any code is generated.
+// NOTE: The checker is deliberately permissive.  Anything it can't reason about is treated as Unknown, which is
+// compatible with every type, leaving the runtime type assertions to catch the rest.
+pub(crate) struct TypeChecker<'a> {
+    syntax_tree: &'a SyntaxTree,
+    scopes: Vec<Scope>,
+    classes: HashMap<String, ClassInfo>,
+    return_types: Vec<Type>,
+}
+
+impl<'a> TypeChecker<'a> {
+    pub fn check(syntax_tree: &'a SyntaxTree) -> Result<(), Error> {
+        let mut checker = Self {
+            syntax_tree,
+            scopes: vec![Scope::default()],
+            classes: HashMap::new(),
+            return_types: Vec::new(),
+        };
+
+        checker.check_node(syntax_tree.root())?;
+
+        Ok(())
+    }
+
+    fn check_node(&mut self, node: SyntaxNodeId) -> Result<Type, Error> {
+        let node = self.syntax_tree.get(node).clone();
+
+        let type_ = match &node {
+            SyntaxNode::LitInt(_) => Type::named(INT),
+            SyntaxNode::LitFloat(_) => Type::named(FLOAT),
+            SyntaxNode::LitString(_) => Type::named(STRING),
+            SyntaxNode::LitBool(_) => Type::named(BOOL),
+            SyntaxNode::LitUnit(_) => Type::named(UNIT),
+            SyntaxNode::LitNull(_) => Type::Null,
+            SyntaxNode::LitIdent(ident) => match self.binding(&ident.identifier) {
+                Some(Binding::Value(type_)) => type_,
+                _ => Type::Unknown,
+            },
+            SyntaxNode::LitList(list) => {
+                let items = list
+                    .items
+                    .iter()
+                    .map(|item| self.check_node(*item))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                if items.is_empty() {
+                    Type::Array(Box::new(Type::Unknown))
+                } else {
+                    Type::Array(Box::new(Type::union(items)))
+                }
+            }
+            SyntaxNode::LitObject(object) => {
+                for (_, item) in &object.items {
+                    self.check_node(*item)?;
+                }
+
+                Type::Unknown
+            }
+            SyntaxNode::LitMap(map) => {
+                for (key, value) in &map.entries {
+                    self.check_node(*key)?;
+                    self.check_node(*value)?;
+                }
+
+                Type::named(MAP)
+            }
+            SyntaxNode::LitAnonymousFn(anonymous_fn) => self.check_anonymous_fn(anonymous_fn)?,
+            SyntaxNode::FieldAccess(field_access) => {
+                self.check_node(field_access.expression)?;
+                Type::Unknown
+            }
+            SyntaxNode::SuperAccess(_) => Type::Unknown,
+            SyntaxNode::Index(index) => {
+                let target = self.check_node(index.expression)?;
+                self.check_node(index.index_expression)?;
+
+                match target {
+                    Type::Array(element) => *element,
+                    Type::Named(name) if name == STRING => Type::named(STRING),
+                    _ => Type::Unknown,
+                }
+            }
+            SyntaxNode::Slice(slice) => {
+                let target = self.check_node(slice.expression)?;
+
+                for bound in slice.start.iter().chain(slice.end.iter()) {
+                    self.check_node(*bound)?;
+                }
+
+                match target {
+                    Type::Array(_) => target,
+                    Type::Named(ref name) if name == STRING => target,
+                    _ => Type::Unknown,
+                }
+            }
+            SyntaxNode::Prefix(prefix) => self.check_prefix(prefix)?,
+            SyntaxNode::Binary(binary) => self.check_binary(binary)?,
+            SyntaxNode::Is(is) => {
+                self.check_node(is.value)?;
+                Type::named(BOOL)
+            }
+            SyntaxNode::NullPropagate(null_propagate) => {
+                self.check_node(null_propagate.expression)?;
+                Type::Unknown
+            }
+            SyntaxNode::ErrorPropagate(error_propagate) => {
+                self.check_node(error_propagate.expression)?;
+                Type::Unknown
+            }
+            SyntaxNode::Assignment(assignment) => self.check_assignment(assignment)?,
+            SyntaxNode::VarDecl(var_decl) => self.check_var_decl(var_decl)?,
+            SyntaxNode::FnDecl(fn_decl) => {
+                self.check_fn_decl(fn_decl, None)?;
+                Type::Unknown
+            }
+            SyntaxNode::OpDecl(op_decl) => {
+                self.check_op_decl(op_decl, None)?;
+                Type::Unknown
+            }
+            SyntaxNode::ClassDecl(class_decl) => {
+                self.check_class_decl(class_decl)?;
+                Type::Unknown
+            }
+            SyntaxNode::ImportDecl(import) => {
+                for item in import.item_imports.iter().chain(import.module_import.iter()) {
+                    self.bind(item, Binding::Value(Type::Unknown));
+                }
+
+                Type::Unknown
+            }
+            SyntaxNode::ExportDecl(export) => {
+                self.check_node(export.export)?;
+                Type::Unknown
+            }
+            SyntaxNode::TypeAlias(type_alias) => {
+                let type_ = self.resolve_annotation(&type_alias.type_);
+                self.top_scope()
+                    .type_aliases
+                    .insert(type_alias.name.identifier.clone(), type_);
+
+                Type::Unknown
+            }
+            SyntaxNode::IfExpression(if_expression) => self.check_if(if_expression)?,
+            SyntaxNode::Loop(loop_) => {
+                self.check_node(loop_.body)?;
+                Type::Unknown
+            }
+            SyntaxNode::WhileLoop(while_loop) => {
+                self.check_node(while_loop.condition)?;
+                self.check_node(while_loop.body)?;
+                Type::Unknown
+            }
+            SyntaxNode::ForLoop(for_loop) => {
+                self.check_node(for_loop.source)?;
+                self.scopes.push(Scope::default());
+                self.bind(&for_loop.variable, Binding::Value(Type::Unknown));
+                self.check_node(for_loop.body)?;
+                self.scopes.pop();
+
+                Type::Unknown
+            }
+            SyntaxNode::Block(block) => self.check_block(block)?,
+            SyntaxNode::Break(_) | SyntaxNode::Continue(_) => Type::Unknown,
+            SyntaxNode::Return(return_) => {
+                self.check_return(return_)?;
+                Type::Unknown
+            }
+            SyntaxNode::FnCall(fn_call) => self.check_fn_call(fn_call)?,
+            SyntaxNode::SuperCall(super_call) => {
+                for arg in &super_call.args {
+                    self.check_node(*arg)?;
+                }
+
+                Type::Unknown
+            }
+        };
+
+        Ok(type_)
+    }
+
+    fn check_block(&mut self, block: &Block) -> Result<Type, Error> {
+        self.scopes.push(Scope::default());
+        self.scan_item_decls(block);
+
+        for expression in &block.expressions {
+            self.check_node(*expression)?;
+        }
+
+        let type_ = match block.trailing_expression {
+            Some(trailing_expression) => self.check_node(trailing_expression)?,
+            None => Type::named(UNIT),
+        };
+
+        self.scopes.pop();
+
+        Ok(type_)
+    }
+
+    // NOTE: Functions and classes can be referred to before their declaration, so their signatures are gathered
+    // ahead of time, mirroring how the compiler reserves their slots.  Their signatures can use any type alias in the
+    // block, so the aliases are gathered first.
+    fn scan_item_decls(&mut self, block: &Block) {
+        let expressions = block.expressions.iter().chain(block.trailing_expression.iter());
+
+        for expression in expressions.clone() {
+            if let SyntaxNode::TypeAlias(type_alias) = self.syntax_tree.get(*expression) {
+                let type_ = self.resolve_annotation(&type_alias.type_);
+                self.top_scope()
+                    .type_aliases
+                    .insert(type_alias.name.identifier.clone(), type_);
+            }
+        }
+
+        for expression in expressions {
+            let mut node = self.syntax_tree.get(*expression);
+
+            if let SyntaxNode::ExportDecl(export) = node {
+                node = self.syntax_tree.get(export.export);
+            }
+
+            match node {
+                SyntaxNode::FnDecl(fn_decl) => {
+                    let signature = self.fn_signature(&fn_decl.args, fn_decl.return_.as_ref());
+                    self.bind(&fn_decl.name.identifier, Binding::Value(signature));
+                }
+                SyntaxNode::ClassDecl(class_decl) => self.scan_class_decl(class_decl),
+                _ => {}
+            }
+        }
+    }
+
+    fn scan_class_decl(&mut self, class_decl: &ClassDecl) {
+        let base = class_decl.base.and_then(|base| match self.syntax_tree.get(base) {
+            SyntaxNode::LitIdent(ident) => Some(ident.identifier.clone()),
+            _ => None,
+        });
+        let constructor = class_decl
+            .associated_items
+            .iter()
+            .find_map(|item| match self.syntax_tree.get(*item) {
+                SyntaxNode::FnDecl(fn_decl) if fn_decl.name.identifier == NEW => Some(fn_decl),
+                _ => None,
+            })
+            .map(|constructor| {
+                constructor
+                    .args
+                    .iter()
+                    .skip(1)
+                    .map(|arg| self.arg_type(arg))
+                    .collect::<Vec<_>>()
+            });
+
+        self.classes
+            .insert(class_decl.name.identifier.clone(), ClassInfo { base, constructor });
+        self.bind(
+            &class_decl.name.identifier,
+            Binding::Class(class_decl.name.identifier.clone()),
+        );
+    }
+
+    fn check_class_decl(&mut self, class_decl: &ClassDecl) -> Result<(), Error> {
+        if let Some(base) = class_decl.base {
+            self.check_node(base)?;
+        }
+
+        let receiver = Type::Named(class_decl.name.identifier.clone());
+
+        for item in &class_decl.associated_items {
+            match self.syntax_tree.get(*item).clone() {
+                SyntaxNode::FnDecl(fn_decl) => self.check_fn_decl(&fn_decl, Some(&receiver))?,
+                SyntaxNode::OpDecl(op_decl) => self.check_op_decl(&op_decl, Some(&receiver))?,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_fn_decl(&mut self, fn_decl: &FnDecl, receiver: Option<&Type>) -> Result<(), Error> {
+        self.check_fn_body(&fn_decl.args, fn_decl.return_.as_ref(), fn_decl.body, receiver)
+    }
+
+    fn check_op_decl(&mut self, op_decl: &OpDecl, receiver: Option<&Type>) -> Result<(), Error> {
+        self.check_fn_body(&op_decl.args, op_decl.return_.as_ref(), op_decl.body, receiver)
+    }
+
+    fn check_anonymous_fn(&mut self, anonymous_fn: &LitAnonymousFn) -> Result<Type, Error> {
+        self.check_fn_body(
+            &anonymous_fn.args,
+            anonymous_fn.return_.as_ref(),
+            anonymous_fn.body,
+            None,
+        )?;
+
+        Ok(self.fn_signature(&anonymous_fn.args, anonymous_fn.return_.as_ref()))
+    }
+
+    fn check_fn_body(
+        &mut self,
+        args: &[FnArg],
+        return_: Option<&TypeAnnotation>,
+        body: SyntaxNodeId,
+        receiver: Option<&Type>,
+    ) -> Result<(), Error> {
+        let return_type = return_.map_or(Type::Unknown, |return_| self.resolve_annotation(return_));
+
+        self.scopes.push(Scope::default());
+
+        for arg in args {
+            let type_ = match receiver {
+                Some(receiver) if arg.name == SELF => receiver.clone(),
+                _ => self.arg_type(arg),
+            };
+
+            self.bind(&arg.name, Binding::Value(type_));
+        }
+
+        self.return_types.push(return_type.clone());
+        let body_type = self.check_node(body);
+        self.return_types.pop();
+        self.scopes.pop();
+
+        let body_span = self.trailing_span(body);
+        self.expect_assignable(&body_type?, &return_type, body_span)
+    }
+
+    fn check_return(&mut self, return_: &Return) -> Result<(), Error> {
+        if let Some(result) = return_.result {
+            let type_ = self.check_node(result)?;
+            let expected = self.return_types.last().cloned().unwrap_or(Type::Unknown);
+
+            self.expect_assignable(&type_, &expected, self.syntax_tree.get(result).span())?;
+        }
+
+        Ok(())
+    }
+
+    fn check_var_decl(&mut self, var_decl: &VarDecl) -> Result<Type, Error> {
+        let type_ = self.check_node(var_decl.expr)?;
+
+        match &var_decl.kind {
+            VarDeclKind::Singular(name) => {
+                let local_type = match &var_decl.type_ {
+                    Some(annotation) => {
+                        let expected = self.resolve_annotation(annotation);
+                        self.expect_assignable(&type_, &expected, self.syntax_tree.get(var_decl.expr).span())?;
+
+                        expected
+                    }
+                    // NOTE: Unannotated mutable locals can be reassigned to any value, so nothing is inferred.
+                    None if var_decl.is_mutable => Type::Unknown,
+                    None => type_,
+                };
+
+                self.bind(name, Binding::Value(local_type));
+            }
+            VarDeclKind::Destructured(names) => {
+                for name in names {
+                    self.bind(name, Binding::Value(Type::Unknown));
+                }
+            }
+        }
+
+        Ok(Type::Unknown)
+    }
+
+    fn check_assignment(&mut self, assignment: &Assignment) -> Result<Type, Error> {
+        let lhs = self.check_node(assignment.lhs_expression)?;
+        let rhs = self.check_node(assignment.rhs_expression)?;
+
+        if let AssignmentOperator::Assignment = assignment.operator {
+            let span = self.syntax_tree.get(assignment.rhs_expression).span();
+            self.expect_assignable(&rhs, &lhs, span)?;
+        }
+
+        Ok(Type::named(UNIT))
+    }
+
+    fn check_fn_call(&mut self, fn_call: &FnCall) -> Result<Type, Error> {
+        let target_class = match self.syntax_tree.get(fn_call.target) {
+            SyntaxNode::LitIdent(ident) => match self.binding(&ident.identifier) {
+                Some(Binding::Class(name)) => Some(name),
+                _ => None,
+            },
+            _ => None,
+        };
+        let target = self.check_node(fn_call.target)?;
+        let (params, result) = match (target_class, target) {
+            (Some(class), _) => {
+                let params = self.classes.get(&class).and_then(|class| class.constructor.clone());
+                (params.unwrap_or_default(), Type::Named(class))
+            }
+            (None, Type::Function { args, return_ }) => (args, *return_),
+            _ => (Vec::new(), Type::Unknown),
+        };
+
+        for (index, arg) in fn_call.args.iter().enumerate() {
+            let type_ = self.check_node(*arg)?;
+
+            if let Some(expected) = params.get(index) {
+                self.expect_assignable(&type_, expected, self.syntax_tree.get(*arg).span())?;
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn check_if(&mut self, if_expression: &IfExpression) -> Result<Type, Error> {
+        self.check_node(if_expression.condition)?;
+        let primary = self.check_node(if_expression.primary)?;
+
+        match if_expression.secondary {
+            Some(secondary) => {
+                let secondary = self.check_node(secondary)?;
+                Ok(Type::union(vec![primary, secondary]))
+            }
+            None => Ok(Type::Unknown),
+        }
+    }
+
+    fn check_prefix(&mut self, prefix: &Prefix) -> Result<Type, Error> {
+        let type_ = self.check_node(prefix.expression)?;
+
+        let result = match prefix.operator {
+            UnaryOperator::Not => Type::named(BOOL),
+            UnaryOperator::Negate if type_.is_numeric() => type_,
+            UnaryOperator::Negate => Type::Unknown,
+        };
+
+        Ok(result)
+    }
+
+    fn check_binary(&mut self, binary: &Binary) -> Result<Type, Error> {
+        let lhs = self.check_node(binary.lhs_expression)?;
+        let rhs = self.check_node(binary.rhs_expression)?;
+        // NOTE: Operators on anything other than matching numbers may be overloaded, so they can return any value.
+        let is_numeric = lhs.is_numeric() && lhs == rhs;
+
+        let result = match binary.operator {
+            BinaryOperator::Multiply
+            | BinaryOperator::Divide
+            | BinaryOperator::Remainder
+            | BinaryOperator::Add
+            | BinaryOperator::Subtract
+                if is_numeric =>
+            {
+                lhs
+            }
+            BinaryOperator::GreaterThan
+            | BinaryOperator::LessThan
+            | BinaryOperator::GreaterThanEquals
+            | BinaryOperator::LessThanEquals
+            | BinaryOperator::Equals
+            | BinaryOperator::NotEquals
+                if is_numeric =>
+            {
+                Type::named(BOOL)
+            }
+            BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr => Type::named(BOOL),
+            _ => Type::Unknown,
+        };
+
+        Ok(result)
+    }
+
+    fn fn_signature(&self, args: &[FnArg], return_: Option<&TypeAnnotation>) -> Type {
+        Type::Function {
+            args: args.iter().map(|arg| self.arg_type(arg)).collect(),
+            return_: Box::new(return_.map_or(Type::Unknown, |return_| self.resolve_annotation(return_))),
+        }
+    }
+
+    fn arg_type(&self, arg: &FnArg) -> Type {
+        arg.type_
+            .as_ref()
+            .map_or(Type::Unknown, |type_| self.resolve_annotation(type_))
+    }
+
+    fn resolve_annotation(&self, annotation: &TypeAnnotation) -> Type {
+        let type_ = match &annotation.kind {
+            TypeAnnotationKind::Named(name) => match self.type_alias(&name.identifier) {
+                Some(alias) => alias,
+                None if name.identifier == ANY_CLASS => Type::Unknown,
+                None => Type::Named(name.identifier.clone()),
+            },
+            TypeAnnotationKind::Array(element) => Type::Array(Box::new(self.resolve_annotation(element))),
+            TypeAnnotationKind::Function { args, return_ } => Type::Function {
+                args: args.iter().map(|arg| self.resolve_annotation(arg)).collect(),
+                return_: Box::new(
+                    return_
+                        .as_ref()
+                        .map_or(Type::Unknown, |return_| self.resolve_annotation(return_)),
+                ),
+            },
+            TypeAnnotationKind::Union(members) => {
+                Type::union(members.iter().map(|member| self.resolve_annotation(member)).collect())
+            }
+        };
+
+        if annotation.is_nullable {
+            type_.nullable()
+        } else {
+            type_
+        }
+    }
+
+    fn expect_assignable(&self, actual: &Type, expected: &Type, span: Span) -> Result<(), Error> {
+        if self.is_assignable(actual, expected) {
+            Ok(())
+        } else {
+            Err(Error::new(TYPE_MISMATCH).with_span(span).with_tags(tags! {
+                expected => expected.to_string(),
+                actual => actual.to_string()
+            }))
+        }
+    }
+
+    fn is_assignable(&self, from: &Type, to: &Type) -> bool {
+        match (from, to) {
+            (Type::Unknown, _) | (_, Type::Unknown) => true,
+            (Type::Union(members), to) => members.iter().all(|member| self.is_assignable(member, to)),
+            (from, Type::Union(members)) => members.iter().any(|member| self.is_assignable(from, member)),
+            (Type::Null, Type::Null) => true,
+            (Type::Named(from), Type::Named(to)) => self.is_subclass(from, to),
+            (Type::Array(from), Type::Array(to)) => self.is_assignable(from, to),
+            (Type::Array(_), Type::Named(to)) => to == ARRAY,
+            (Type::Named(from), Type::Array(_)) => from == ARRAY,
+            (
+                Type::Function {
+                    args: from_args,
+                    return_: from_return,
+                },
+                Type::Function {
+                    args: to_args,
+                    return_: to_return,
+                },
+            ) => {
+                from_args.len() == to_args.len()
+                    && from_args
+                        .iter()
+                        .zip(to_args)
+                        .all(|(from_arg, to_arg)| self.is_assignable(to_arg, from_arg))
+                    && self.is_assignable(from_return, to_return)
+            }
+            (Type::Function { .. }, Type::Named(to)) => to == FUNCTION,
+            (Type::Named(from), Type::Function { .. }) => from == FUNCTION,
+            _ => false,
+        }
+    }
+
+    fn is_subclass(&self, class: &str, base: &str) -> bool {
+        let mut current = Some(class);
+
+        while let Some(class) = current {
+            if class == base {
+                return true;
+            }
+
+            current = self.classes.get(class).and_then(|class| class.base.as_deref());
+        }
+
+        false
+    }
+
+    fn trailing_span(&self, node: SyntaxNodeId) -> Span {
+        match self.syntax_tree.get(node) {
+            SyntaxNode::Block(Block {
+                trailing_expression: Some(trailing_expression),
+                ..
+            }) => self.trailing_span(*trailing_expression),
+            node => node.span(),
+        }
+    }
+
+    fn bind(&mut self, name: &str, binding: Binding) {
+        self.top_scope().locals.insert(name.to_owned(), binding);
+    }
+
+    fn binding(&self, name: &str) -> Option<Binding> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.locals.get(name).cloned())
+    }
+
+    fn type_alias(&self, name: &str) -> Option<Type> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.type_aliases.get(name).cloned())
+    }
+
+    fn top_scope(&mut self) -> &mut Scope {
+        self.scopes.last_mut().expect("The root scope should always exist.")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::compiler::{Compiler, CompilerOptions};
+    use dice_core::{
+        error::{codes::TYPE_MISMATCH, Error},
+        source::{Source, SourceKind},
+    };
+
+    fn check(source: &str) -> Result<(), Error> {
+        let source = Source::new(source.to_owned(), SourceKind::Script);
+        let options = CompilerOptions {
+            type_check: true,
+            ..Default::default()
+        };
+
+        Compiler::compile_source_with_options(source, options).map(|_| ())
+    }
+
+    /// Assert that the source fails to check, with the mismatch reported on the given snippet of the source.
+    fn assert_mismatch(source: &str, snippet: &str, expected: &str, actual: &str) {
+        let error = check(source).expect_err("The source should fail to check.");
+        let span = error.span();
+
+        assert_eq!(error.error_code(), TYPE_MISMATCH);
+        assert_eq!(&source[span.start..span.end], snippet);
+        assert_eq!(error.tag("expected"), Some(expected));
+        assert_eq!(error.tag("actual"), Some(actual));
+    }
+
+    #[test]
+    fn test_argument_mismatch() {
+        assert_mismatch(r#"fn f(x: Int) { x } f("a")"#, r#""a""#, "Int", "String");
+        check("fn f(x: Int) { x } f(1)").unwrap();
+    }
+
+    #[test]
+    fn test_return_mismatch() {
+        assert_mismatch(r#"fn f() -> Int { "a" }"#, r#""a""#, "Int", "String");
+        assert_mismatch(r#"fn f(x) -> Int { if x { return 1.5 } 1 }"#, "1.5", "Int", "Float");
+        check("fn f() -> Int { 1 }").unwrap();
+    }
+
+    #[test]
+    fn test_let_mismatch() {
+        assert_mismatch(r#"let x: Bool = 1"#, "1", "Bool", "Int");
+        check("let x: Bool = true").unwrap();
+    }
+
+    #[test]
+    fn test_unions_and_nullability() {
+        check("let x: Int | Float = 1.5").unwrap();
+        check("let x: Int? = null").unwrap();
+        check("let a = 1 let b = 2.5 let x: Int | Float = if a < 2 { a } else { b }").unwrap();
+        assert_mismatch("let x: Int = null", "null", "Int", "Null");
+        assert_mismatch(r#"let x: Int | Float = "a""#, r#""a""#, "Int | Float", "String");
+        assert_mismatch(
+            r#"let a = 1 let x: Int = if a < 2 { a } else { "a" }"#,
+            r#"if a < 2 { a } else { "a" }"#,
+            "Int",
+            "Int | String",
+        );
+    }
+
+    #[test]
+    fn test_type_aliases() {
+        check("type Damage = Int | Float let x: Damage = 2.5").unwrap();
+        check("type Damage = Int | Float fn hit(damage: Damage) { damage } hit(1)").unwrap();
+        assert_mismatch(
+            r#"type Damage = Int | Float let x: Damage = "a""#,
+            r#""a""#,
+            "Int | Float",
+            "String",
+        );
+    }
+
+    #[test]
+    fn test_arrays() {
+        check("let x: Array<Int> = [1, 2, 3]").unwrap();
+        check("let x: Array<Int> = []").unwrap();
+        check("let x: Array = [1, 2]").unwrap();
+        assert_mismatch(
+            r#"let x: Array<Int> = [1, "a"]"#,
+            r#"[1, "a"]"#,
+            "Array<Int>",
+            "Array<Int | String>",
+        );
+        assert_mismatch("let x: Int = [1]", "[1]", "Int", "Array<Int>");
+    }
+
+    #[test]
+    fn test_functions() {
+        check("fn f(x: Int) -> Bool { true } let g: Function(Int) -> Bool = f").unwrap();
+        check("let g: Function = |x| x").unwrap();
+        check("fn apply(f: Function(Int) -> Int) { f(1) } apply(|x: Int| x)").unwrap();
+        assert_mismatch(
+            "fn f(x: Int) -> Bool { true } let g: Function(Int) -> Int = f",
+            "f",
+            "Function(Int) -> Int",
+            "Function(Int) -> Bool",
+        );
+        assert_mismatch(
+            "fn f(x: Int, y: Int) { x } let g: Function(Int) -> Any = f",
+            "f",
+            "Function(Int) -> Any",
+            "Function(Int, Int) -> Any",
+        );
+    }
+
+    #[test]
+    fn test_class_subtyping() {
+        let classes = "class Shape {} class Square : Shape { fn new(self) { super() } } class Circle {}";
+
+        check(&format!("{} fn area(shape: Shape) {{ shape }} area(Square())", classes)).unwrap();
+        check(&format!("{} let shape: Shape = Square()", classes)).unwrap();
+        assert_mismatch(
+            &format!("{} fn area(shape: Shape) {{ shape }} area(Circle())", classes),
+            "Circle()",
+            "Shape",
+            "Circle",
+        );
+        assert_mismatch(
+            &format!("{} let square: Square = Shape()", classes),
+            "Shape()",
+            "Square",
+            "Shape",
+        );
+    }
+
+    #[test]
+    fn test_constructor_arguments() {
+        assert_mismatch(
+            r#"class Point { fn new(self, x: Int) { self.x = x } } Point("a")"#,
+            r#""a""#,
+            "Int",
+            "String",
+        );
+    }
+
+    #[test]
+    fn test_unknown_values_are_permitted() {
+        // NOTE: Anything the checker can't infer is left to the runtime assertions.
+        check("let mut x = 1 x = \"a\" let y: Int = x").unwrap();
+        check("let o = #{ a: 1 } let x: String = o.a").unwrap();
+        check("fn f(x) { x } let y: Int = f(\"a\")").unwrap();
+        check("let a = [1] let x: String = a[0].to_string()").unwrap();
+        check("let x: Any = 1").unwrap();
+    }
+}
@@ -0,0 +1,79 @@
+use std::fmt::{Display, Formatter};
+
+pub static INT: &str = "Int";
+pub static FLOAT: &str = "Float";
+pub static BOOL: &str = "Bool";
+pub static STRING: &str = "String";
+pub static UNIT: &str = "Unit";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    // NOTE: Unknown is used for any value the checker can't reason about and is compatible with every other type.
+    Unknown,
+    Null,
+    Named(String),
+    Array(Box<Type>),
+    Function { args: Vec<Type>, return_: Box<Type> },
+    Union(Vec<Type>),
+}
+
+impl Type {
+    pub fn named(name: &str) -> Self {
+        Type::Named(name.to_owned())
+    }
+
+    pub fn nullable(self) -> Self {
+        Type::union(vec![self, Type::Null])
+    }
+
+    /// Create a union, flattening nested unions and removing duplicate members.
+    pub fn union(members: Vec<Type>) -> Self {
+        let mut flattened = Vec::new();
+
+        for member in members {
+            let member = match member {
+                Type::Union(members) => members,
+                member => vec![member],
+            };
+
+            for member in member {
+                if member == Type::Unknown {
+                    return Type::Unknown;
+                }
+
+                if !flattened.contains(&member) {
+                    flattened.push(member);
+                }
+            }
+        }
+
+        if flattened.len() == 1 {
+            flattened.remove(0)
+        } else {
+            Type::Union(flattened)
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Type::Named(name) if name == INT || name == FLOAT)
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::Unknown => write!(f, "Any"),
+            Type::Null => write!(f, "Null"),
+            Type::Named(name) => write!(f, "{}", name),
+            Type::Array(element) => write!(f, "Array<{}>", element),
+            Type::Function { args, return_ } => {
+                let args = args.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+                write!(f, "Function({}) -> {}", args, return_)
+            }
+            Type::Union(members) => {
+                let members = members.iter().map(ToString::to_string).collect::<Vec<_>>().join(" | ");
+                write!(f, "{}", members)
+            }
+        }
+    }
+}
@@ -38,7 +38,7 @@ impl NodeVisitor<&ClassDecl> for Compiler {
         let super_slot = self
             .context()?
             .scope_stack()
-            .add_local(SUPER, State::initialized(true))? as u8;
+            .add_local(SUPER, State::initialized(true))?;
 
         emit_bytecode! {
             self.assembler()?, node.span => [
@@ -67,7 +67,7 @@ impl NodeVisitor<&ClassDecl> for Compiler {
                 *is_initialized = true;
             }
 
-            local.slot as u8
+            local.slot
         };
 
         // NOTE: The base class is already on top of the stack from being stored in the super local.
@@ -102,7 +102,7 @@ impl NodeVisitor<&ClassDecl> for Compiler {
 }
 
 impl Compiler {
-    fn visit_fn(&mut self, slot: u8, fn_decl: FnDecl, class_kind: ClassKind) -> Result<(), Error> {
+    fn visit_fn(&mut self, slot: usize, fn_decl: FnDecl, class_kind: ClassKind) -> Result<(), Error> {
         let self_param = fn_decl.args.first().filter(|arg| arg.name == SELF);
         let kind = if let Some(self_param) = self_param {
             // NOTE: If the self parameter has a type annotation, return an error.
@@ -141,7 +141,7 @@ impl Compiler {
         Ok(())
     }
 
-    fn visit_op(&mut self, slot: u8, op_decl: OpDecl) -> Result<(), Error> {
+    fn visit_op(&mut self, slot: usize, op_decl: OpDecl) -> Result<(), Error> {
         let self_param = op_decl.args.first().filter(|arg| arg.name == SELF);
 
         if let Some(self_param) = self_param {
@@ -170,9 +170,7 @@ impl Compiler {
 
         for variable in scope.variables.clone() {
             if variable.is_captured {
-                self.context()?
-                    .assembler()
-                    .close_upvalue(variable.slot as u8, class.span);
+                self.context()?.assembler().close_upvalue(variable.slot, class.span);
             }
         }
 
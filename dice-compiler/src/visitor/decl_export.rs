@@ -36,7 +36,7 @@ impl NodeVisitor<&ExportDecl> for Compiler {
             .scope_stack()
             .local(EXPORT)
             .expect("#export should always be defined in modules.")
-            .slot as u8;
+            .slot;
         self.assembler()?.load_local(export_slot, node.span);
         self.visit(node.export)?;
 
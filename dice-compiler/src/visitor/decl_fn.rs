@@ -22,7 +22,7 @@ impl NodeVisitor<(&FnDecl, FnKind)> for Compiler {
         let body = self.syntax_tree.child(fn_decl.body);
         let mut fn_context = self.compile_fn(body, &fn_decl.args, fn_decl.return_.clone(), fn_kind)?;
         let upvalues = fn_context.upvalues().clone();
-        let bytecode = fn_context.finish(self.source.clone());
+        let bytecode = fn_context.finish(&fn_decl.name.identifier, self.source.clone(), self.options.optimize);
         let compiled_fn = ConstantValue::Function(FunctionBytecode::new(
             bytecode,
             &*fn_decl.name.identifier,
@@ -79,7 +79,7 @@ impl Compiler {
             _ => unreachable!("Unexpected non-function local state while compiling a function."),
         }
 
-        let slot = local.slot as u8;
+        let slot = local.slot;
 
         emit_bytecode! {
             self.assembler()?, fn_decl.span => [
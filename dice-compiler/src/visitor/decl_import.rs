@@ -4,7 +4,7 @@ use dice_syntax::ImportDecl;
 
 impl NodeVisitor<&ImportDecl> for Compiler {
     fn visit(&mut self, node: &ImportDecl) -> Result<(), Error> {
-        let imports: Vec<(&str, u8)> = node
+        let imports: Vec<(&str, usize)> = node
             .item_imports
             .iter()
             .map(|item| {
@@ -13,7 +13,7 @@ impl NodeVisitor<&ImportDecl> for Compiler {
                     .scope_stack()
                     .add_local(item.clone(), State::initialized(false))?;
 
-                Ok((item.as_str(), slot as u8))
+                Ok((item.as_str(), slot))
             })
             .collect::<Result<Vec<_>, Error>>()?;
 
@@ -36,7 +36,7 @@ impl NodeVisitor<&ImportDecl> for Compiler {
                 .scope_stack()
                 .add_local(module_import.clone(), State::initialized(false))?;
 
-            self.assembler()?.store_local(module_slot as u8, node.span);
+            self.assembler()?.store_local(module_slot, node.span);
         }
 
         Ok(())
@@ -0,0 +1,19 @@
+use dice_core::error::Error;
+use dice_syntax::TypeAlias;
+
+use super::NodeVisitor;
+use crate::compiler::Compiler;
+
+impl NodeVisitor<&TypeAlias> for Compiler {
+    fn visit(&mut self, TypeAlias { name, type_, span }: &TypeAlias) -> Result<(), Error> {
+        // NOTE: Aliases are expanded eagerly, so later aliases with the same name don't change earlier definitions.
+        let type_ = self.resolve_type(type_)?;
+
+        self.context()?
+            .scope_stack()
+            .add_type_alias(name.identifier.clone(), type_)?;
+        self.assembler()?.push_unit(*span);
+
+        Ok(())
+    }
+}
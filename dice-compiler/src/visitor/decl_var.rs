@@ -1,4 +1,4 @@
-use super::NodeVisitor;
+use super::{type_annotation::type_shape, NodeVisitor};
 use crate::{compiler::Compiler, scope_stack::State};
 use dice_core::error::Error;
 use dice_syntax::{VarDecl, VarDeclKind};
@@ -19,7 +19,7 @@ impl Compiler {
         let slot = self
             .context()?
             .scope_stack()
-            .add_local(name, State::initialized(var_decl.is_mutable))? as u8;
+            .add_local(name, State::initialized(var_decl.is_mutable))?;
 
         emit_bytecode! {
             self.assembler()?, var_decl.span => [
@@ -28,13 +28,15 @@ impl Compiler {
         }
 
         if let Some(type_) = &var_decl.type_ {
+            let type_ = self.resolve_type(type_)?;
+
             emit_bytecode! {
                 self.assembler()?, var_decl.span => [
-                    {self.visit(&type_.name)?};
-                    if type_.is_nullable => [
-                        ASSERT_TYPE_OR_NULL_FOR_LOCAL slot;
+                    {self.visit(&type_)?};
+                    if type_.accepts_null() => [
+                        ASSERT_TYPE_OR_NULL_FOR_LOCAL type_shape(&type_), slot;
                     ] else [
-                        ASSERT_TYPE_FOR_LOCAL slot;
+                        ASSERT_TYPE_FOR_LOCAL type_shape(&type_), slot;
                     ]
                 ]
             }
@@ -46,7 +48,7 @@ impl Compiler {
 
 impl Compiler {
     fn destructured_var(&mut self, var_decl: &VarDecl, variables: &[String]) -> Result<(), Error> {
-        let imports: Vec<(&str, u8)> = variables
+        let imports: Vec<(&str, usize)> = variables
             .iter()
             .map(|item| {
                 let slot = self
@@ -54,7 +56,7 @@ impl Compiler {
                     .scope_stack()
                     .add_local(item.clone(), State::initialized(false))?;
 
-                Ok((item.as_str(), slot as u8))
+                Ok((item.as_str(), slot))
             })
             .collect::<Result<Vec<_>, Error>>()?;
 
@@ -142,17 +142,17 @@ impl Compiler {
                 emit_bytecode! {
                     self.assembler()?, span => [
                         {self.visit(rhs_expression)?};
-                        ASSIGN_UPVALUE upvalue as u8;
+                        ASSIGN_UPVALUE upvalue;
                     ]
                 }
             }
             operator => {
                 emit_bytecode! {
                     self.assembler()?, span => [
-                        LOAD_UPVALUE upvalue as u8;
+                        LOAD_UPVALUE upvalue;
                         {self.visit(rhs_expression)?};
                         {self.visit_operator(operator, span)?};
-                        ASSIGN_UPVALUE upvalue as u8;
+                        ASSIGN_UPVALUE upvalue;
                     ]
                 }
             }
@@ -169,7 +169,7 @@ impl Compiler {
         span: Span,
         local: ScopeVariable,
     ) -> Result<(), Error> {
-        let slot = local.slot as u8;
+        let slot = local.slot;
 
         if !local.is_mutable() {
             return Err(Error::new(CANNOT_REASSIGN_IMMUTABLE_VARIABLE)
@@ -1,7 +1,12 @@
 use super::NodeVisitor;
-use crate::compiler::Compiler;
+use crate::{
+    compiler::Compiler,
+    scope_stack::{ScopeKind, State},
+};
 use dice_core::{error::Error, span::Span};
-use dice_syntax::{Binary, BinaryOperator, SyntaxNodeId};
+use dice_syntax::{Binary, BinaryOperator, SyntaxNode, SyntaxNodeId};
+
+static PIPELINE_VALUE: &str = "#pipeline";
 
 impl NodeVisitor<&Binary> for Compiler {
     fn visit(
@@ -49,7 +54,7 @@ impl Compiler {
         self.compiler_stack
             .top_mut()?
             .assembler()
-            .patch_jump(short_circuit_jump);
+            .patch_jump(short_circuit_jump)?;
 
         Ok(())
     }
@@ -79,7 +84,7 @@ impl Compiler {
         self.compiler_stack
             .top_mut()?
             .assembler()
-            .patch_jump(short_circuit_jump);
+            .patch_jump(short_circuit_jump)?;
 
         Ok(())
     }
@@ -90,9 +95,38 @@ impl Compiler {
         rhs_expression: SyntaxNodeId,
         span: Span,
     ) -> Result<(), Error> {
-        self.visit(rhs_expression)?;
+        let fn_call = match self.syntax_tree.get(rhs_expression) {
+            SyntaxNode::FnCall(fn_call) if fn_call.args.iter().any(|arg| self.is_placeholder(*arg)) => fn_call.clone(),
+            _ => {
+                self.visit(rhs_expression)?;
+                self.visit(lhs_expression)?;
+                self.assembler()?.call(1, span);
+
+                return Ok(());
+            }
+        };
+
+        /* NOTE: Calls with placeholder arguments are lowered to a direct call, with the piped value stored in a hidden
+         * local so each `_` can load it.  The local's name can't be referenced by user code, so it's never captured.
+         */
         self.visit(lhs_expression)?;
-        self.assembler()?.call(1, span);
+        self.context()?.scope_stack().push_scope(ScopeKind::Block, None);
+        let slot = self
+            .context()?
+            .scope_stack()
+            .add_local(PIPELINE_VALUE, State::initialized(false))?;
+
+        emit_bytecode! {
+            self.assembler()?, span => [
+                STORE_LOCAL slot;
+                POP;
+            ]
+        }
+
+        self.enter_call()?;
+        self.fn_call(&fn_call, Some(slot))?;
+        self.exit_call()?;
+        self.context()?.scope_stack().pop_scope()?;
 
         Ok(())
     }
@@ -116,7 +150,7 @@ impl Compiler {
         }
 
         self.visit(rhs_expression)?;
-        self.assembler()?.patch_jump(coalesce_jump);
+        self.assembler()?.patch_jump(coalesce_jump)?;
 
         Ok(())
     }
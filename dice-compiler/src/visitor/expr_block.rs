@@ -12,19 +12,24 @@ use crate::{
     visitor::ClassKind,
 };
 
-use super::NodeVisitor;
+use super::{type_annotation::type_shape, NodeVisitor};
 
 impl NodeVisitor<&Block> for Compiler {
     fn visit(&mut self, block: &Block) -> Result<(), Error> {
+        self.visit_block_items(block, false)
+    }
+}
+
+impl Compiler {
+    fn visit_block_items(&mut self, block: &Block, is_tail: bool) -> Result<(), Error> {
         for expression in block.expressions.iter() {
             self.visit(*expression)?;
             self.assembler()?.pop(block.span);
         }
 
         match block.trailing_expression {
-            Some(trailing_expression) => {
-                self.visit(trailing_expression)?;
-            }
+            Some(trailing_expression) if is_tail => self.visit_tail(trailing_expression)?,
+            Some(trailing_expression) => self.visit(trailing_expression)?,
             None => self.assembler()?.push_unit(block.span),
         }
 
@@ -35,13 +40,15 @@ impl NodeVisitor<&Block> for Compiler {
 pub enum BlockKind {
     Block,
     Loop,
+    // NOTE: A block whose value is returned from the enclosing function.
+    Tail,
 }
 
 impl NodeVisitor<(&Block, BlockKind)> for Compiler {
     fn visit(&mut self, (block, kind): (&Block, BlockKind)) -> Result<(), Error> {
         self.context()?.scope_stack().push_scope(ScopeKind::Block, None);
         self.scan_item_decls(block)?;
-        self.visit(block)?;
+        self.visit_block_items(block, matches!(kind, BlockKind::Tail))?;
 
         // NOTE: If in context of a loop, pop the last value off the stack.
         if let BlockKind::Loop = kind {
@@ -80,7 +87,7 @@ impl<'args> NodeVisitor<(&Block, FunctionBlockKind<'args>)> for Compiler {
         self.context()?.scope_stack().push_scope(ScopeKind::Block, None);
         self.visit_args(&kind, kind.args())?;
         self.scan_item_decls(block)?;
-        self.visit(block)?;
+        self.visit_block_items(block, !matches!(kind, FunctionBlockKind::Constructor(..)))?;
         self.visit_close_upvalues(block)?;
 
         if let FunctionBlockKind::Function(_) | FunctionBlockKind::Method(_) = kind {
@@ -92,7 +99,7 @@ impl<'args> NodeVisitor<(&Block, FunctionBlockKind<'args>)> for Compiler {
                 .scope_stack()
                 .local(SELF)
                 .expect("Methods should always have a self.")
-                .slot as u8;
+                .slot;
 
             emit_bytecode! {
                 self.assembler()?, block.span => [
@@ -140,16 +147,18 @@ impl Compiler {
                     is_mutable: false,
                     is_initialized: true,
                 },
-            )? as u8;
+            )?;
 
             if let Some(type_) = &arg.type_ {
+                let type_ = self.resolve_type(type_)?;
+
                 emit_bytecode! {
                     self.assembler()?, arg.span => [
-                        {self.visit(&type_.name)?};
-                        if type_.is_nullable => [
-                            ASSERT_TYPE_OR_NULL_FOR_LOCAL slot;
+                        {self.visit(&type_)?};
+                        if type_.accepts_null() => [
+                            ASSERT_TYPE_OR_NULL_FOR_LOCAL type_shape(&type_), slot;
                         ] else [
-                            ASSERT_TYPE_FOR_LOCAL slot;
+                            ASSERT_TYPE_FOR_LOCAL type_shape(&type_), slot;
                         ]
                     ]
                 }
@@ -164,9 +173,7 @@ impl Compiler {
 
         for variable in scope.variables.clone() {
             if variable.is_captured {
-                self.context()?
-                    .assembler()
-                    .close_upvalue(variable.slot as u8, block.span);
+                self.context()?.assembler().close_upvalue(variable.slot, block.span);
             }
         }
 
@@ -181,13 +188,15 @@ impl Compiler {
             return_type: Some(return_type),
         } = self.context()?.kind()
         {
+            let return_type = self.resolve_type(&return_type)?;
+
             emit_bytecode! {
                 self.assembler()?, span => [
-                    {self.visit(&return_type.name)?};
-                    if return_type.is_nullable => [
-                        ASSERT_TYPE_OR_NULL_AND_RETURN;
+                    {self.visit(&return_type)?};
+                    if return_type.accepts_null() => [
+                        ASSERT_TYPE_OR_NULL_AND_RETURN type_shape(&return_type);
                     ] else [
-                        ASSERT_TYPE_AND_RETURN;
+                        ASSERT_TYPE_AND_RETURN type_shape(&return_type);
                     ]
                 ]
             }
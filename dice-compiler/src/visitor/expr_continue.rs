@@ -11,7 +11,7 @@ impl NodeVisitor<&Continue> for Compiler {
         }
 
         let loop_start = context.scope_stack().entry_point(ScopeKind::Loop)?;
-        context.assembler().jump_back(loop_start as u64, *span);
+        context.assembler().jump_back(loop_start as u64, *span)?;
 
         Ok(())
     }
@@ -1,10 +1,32 @@
 use super::NodeVisitor;
 use crate::compiler::Compiler;
 use dice_core::error::Error;
-use dice_syntax::FnCall;
+use dice_syntax::{FnCall, SyntaxNode, SyntaxNodeId};
+
+pub(super) static PLACEHOLDER: &str = "_";
 
 impl NodeVisitor<&FnCall> for Compiler {
     fn visit(&mut self, node: &FnCall) -> Result<(), Error> {
+        self.fn_call(node, None)
+    }
+}
+
+impl Compiler {
+    pub(super) fn is_placeholder(&self, node: SyntaxNodeId) -> bool {
+        matches!(self.syntax_tree.get(node), SyntaxNode::LitIdent(ident) if ident.identifier == PLACEHOLDER)
+    }
+
+    /// Compile a function call, loading the value stored in the placeholder slot for any `_` arguments.
+    pub(super) fn fn_call(&mut self, node: &FnCall, placeholder_slot: Option<usize>) -> Result<(), Error> {
+        self.call_with(node, placeholder_slot, false)
+    }
+
+    /// Compile a function call whose result is returned from the current function.
+    pub(super) fn tail_fn_call(&mut self, node: &FnCall) -> Result<(), Error> {
+        self.call_with(node, None, true)
+    }
+
+    fn call_with(&mut self, node: &FnCall, placeholder_slot: Option<usize>, is_tail_call: bool) -> Result<(), Error> {
         self.visit(node.target)?;
 
         // NOTE: Store the temporary at the time the function call was started, to be restored later.
@@ -17,12 +39,25 @@ impl NodeVisitor<&FnCall> for Compiler {
             let original_call_context = std::mem::take(&mut self.context()?.scope_stack().top_mut()?.call_context);
             // NOTE: Increment temporary by 1 for each parameter.
             *self.context()?.temporary_count() += 1;
-            self.visit(*arg)?;
+
+            match placeholder_slot {
+                Some(slot) if self.is_placeholder(*arg) => {
+                    let span = self.syntax_tree.get(*arg).span();
+                    self.assembler()?.load_local(slot, span)
+                }
+                _ => self.visit(*arg)?,
+            }
+
             self.context()?.scope_stack().top_mut()?.call_context = original_call_context;
         }
 
         *self.context()?.temporary_count() = original_temporary_count;
-        self.assembler()?.call(node.args.len() as u8, node.span);
+
+        if is_tail_call {
+            self.assembler()?.tail_call(node.args.len(), node.span);
+        } else {
+            self.assembler()?.call(node.args.len(), node.span);
+        }
 
         Ok(())
     }
@@ -36,7 +36,7 @@ impl NodeVisitor<&ForLoop> for Compiler {
         context.scope_stack().push_scope(ScopeKind::Loop, None);
         let variable_slot = context
             .scope_stack()
-            .add_local(for_loop.variable.clone(), State::initialized(false))? as u8;
+            .add_local(for_loop.variable.clone(), State::initialized(false))?;
 
         emit_bytecode! {
             context.assembler(), for_loop.span => [
@@ -65,9 +65,9 @@ impl NodeVisitor<&ForLoop> for Compiler {
         }
 
         // NOTE: Patch all exit points from the loop to jump to after the end of the loop.
-        context.assembler().patch_jump(loop_exit);
+        context.assembler().patch_jump(loop_exit)?;
         for exit_point in scope_context.exit_points {
-            context.assembler().patch_jump(exit_point as u64);
+            context.assembler().patch_jump(exit_point as u64)?;
         }
 
         // NOTE: Clean up the temporaries stored on the stack and push a unit value.
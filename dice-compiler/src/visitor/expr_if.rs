@@ -1,10 +1,16 @@
 use super::NodeVisitor;
 use crate::compiler::Compiler;
 use dice_core::error::Error;
-use dice_syntax::IfExpression;
+use dice_syntax::{IfExpression, SyntaxNodeId};
 
 impl NodeVisitor<&IfExpression> for Compiler {
-    fn visit(
+    fn visit(&mut self, if_expression: &IfExpression) -> Result<(), Error> {
+        self.if_expression(if_expression, false)
+    }
+}
+
+impl Compiler {
+    pub(super) fn if_expression(
         &mut self,
         IfExpression {
             condition,
@@ -12,23 +18,32 @@ impl NodeVisitor<&IfExpression> for Compiler {
             secondary,
             span,
         }: &IfExpression,
+        is_tail: bool,
     ) -> Result<(), Error> {
         self.visit(*condition)?;
         let if_jump = self.assembler()?.jump_if_false(*span);
-        self.visit(*primary)?;
+        self.visit_branch(*primary, is_tail)?;
 
         let else_jump = self.assembler()?.jump(*span);
 
-        self.assembler()?.patch_jump(if_jump);
+        self.assembler()?.patch_jump(if_jump)?;
 
         if let Some(secondary) = secondary {
-            self.visit(*secondary)?;
+            self.visit_branch(*secondary, is_tail)?;
         } else {
             self.assembler()?.push_unit(*span);
         }
 
-        self.assembler()?.patch_jump(else_jump);
+        self.assembler()?.patch_jump(else_jump)?;
 
         Ok(())
     }
+
+    fn visit_branch(&mut self, branch: SyntaxNodeId, is_tail: bool) -> Result<(), Error> {
+        if is_tail {
+            self.visit_tail(branch)
+        } else {
+            self.visit(branch)
+        }
+    }
 }
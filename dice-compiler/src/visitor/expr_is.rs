@@ -1,10 +1,25 @@
 use super::NodeVisitor;
 use crate::compiler::Compiler;
-use dice_core::error::Error;
-use dice_syntax::Is;
+use dice_core::{
+    error::{codes::INVALID_IS_USAGE, Error},
+    tags,
+};
+use dice_syntax::{Is, TypeAnnotationKind};
 
 impl NodeVisitor<&Is> for Compiler {
     fn visit(&mut self, Is { value, type_, span }: &Is) -> Result<(), Error> {
+        let type_ = self.resolve_type(type_)?;
+
+        // TODO: Support checking values against arrays, functions and unions.
+        let name = match &type_.kind {
+            TypeAnnotationKind::Named(name) => name.clone(),
+            _ => {
+                return Err(Error::new(INVALID_IS_USAGE).with_span(*span).with_tags(tags! {
+                    name => type_.to_string()
+                }))
+            }
+        };
+
         if type_.is_nullable {
             // TODO: Replace this with an IS_TYPE_OR_NULL instruction.
             let type_check_jump;
@@ -17,14 +32,14 @@ impl NodeVisitor<&Is> for Compiler {
                     JUMP_IF_TRUE -> type_check_jump;
                     POP;
                     {self.visit(*value)?};
-                    {self.visit(&type_.name)?};
+                    {self.visit(&name)?};
                     IS;
                     PATCH_JUMP <- type_check_jump;
                 ]
             }
         } else {
             self.visit(*value)?;
-            self.visit(&type_.name)?;
+            self.visit(&name)?;
             self.assembler()?.is(*span);
         }
 
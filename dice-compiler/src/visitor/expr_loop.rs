@@ -14,12 +14,12 @@ impl NodeVisitor<&Loop> for Compiler {
                 .push_scope(ScopeKind::Loop, Some(loop_start as usize));
 
             self.visit((&block, BlockKind::Loop))?;
-            self.assembler()?.jump_back(loop_start, *span);
+            self.assembler()?.jump_back(loop_start, *span)?;
 
             let scope_close = self.context()?.scope_stack().pop_scope()?;
 
             for location in scope_close.exit_points.iter() {
-                self.assembler()?.patch_jump(*location as u64);
+                self.assembler()?.patch_jump(*location as u64)?;
             }
 
             self.assembler()?.push_unit(*span);
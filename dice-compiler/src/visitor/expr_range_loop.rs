@@ -35,7 +35,7 @@ impl NodeVisitor<&RangeLoop> for Compiler {
         context.scope_stack().push_scope(ScopeKind::Loop, None);
         let variable_slot = context
             .scope_stack()
-            .add_local(range_loop.variable.clone(), State::initialized(false))? as u8;
+            .add_local(range_loop.variable.clone(), State::initialized(false))?;
 
         // NOTE: Store the start condition and duplicate the end condition, to be consumed by the loop condition.
         // This effectively reverses the order of the end and start conditions on the stack.
@@ -75,9 +75,9 @@ impl NodeVisitor<&RangeLoop> for Compiler {
         }
 
         // NOTE: Patch all exit points from the loop to jump to after the end of the loop.
-        context.assembler().patch_jump(loop_exit);
+        context.assembler().patch_jump(loop_exit)?;
         for exit_point in scope_context.exit_points {
-            context.assembler().patch_jump(exit_point as u64);
+            context.assembler().patch_jump(exit_point as u64)?;
         }
 
         // NOTE: Clean up the temporaries stored on the stack and push a unit value.
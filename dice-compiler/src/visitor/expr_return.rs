@@ -17,7 +17,7 @@ impl NodeVisitor<&Return> for Compiler {
 
         match context.kind() {
             CompilerKind::Function { .. } | CompilerKind::Method { .. } => match expr_return.result {
-                Some(expr) => self.visit(expr)?,
+                Some(expr) => self.visit_tail(expr)?,
                 None => context.assembler().push_unit(expr_return.span),
             },
             CompilerKind::Constructor if expr_return.result.is_none() => {
@@ -26,7 +26,7 @@ impl NodeVisitor<&Return> for Compiler {
                     .local(SELF)
                     .expect("The self parameter should always be declared in constructors.")
                     .slot;
-                self.assembler()?.load_local(self_slot as u8, expr_return.span);
+                self.assembler()?.load_local(self_slot, expr_return.span);
             }
             CompilerKind::Constructor if expr_return.result.is_some() => {
                 return Err(Error::new(NEW_RETURN_CANNOT_HAVE_EXPRESSION).with_span(expr_return.span))
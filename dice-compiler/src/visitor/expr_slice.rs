@@ -0,0 +1,36 @@
+use super::NodeVisitor;
+use crate::compiler::Compiler;
+use dice_core::error::Error;
+use dice_syntax::{Slice, SyntaxNodeId};
+
+impl NodeVisitor<&Slice> for Compiler {
+    fn visit(&mut self, node: &Slice) -> Result<(), Error> {
+        self.visit(node.expression)?;
+
+        // NOTE: Just like indexing, the bounds of the slice are evaluated with their own call context, so chained calls
+        // only short-circuit inside of the bound.
+        let original_call_context = std::mem::take(&mut self.context()?.scope_stack().top_mut()?.call_context);
+        *self.context()?.temporary_count() += 1;
+        self.visit_slice_bound(node.start, node)?;
+        *self.context()?.temporary_count() += 1;
+        self.visit_slice_bound(node.end, node)?;
+        *self.context()?.temporary_count() -= 2;
+        self.context()?.scope_stack().top_mut()?.call_context = original_call_context;
+        self.assembler()?.load_slice(node.is_inclusive, node.span);
+
+        Ok(())
+    }
+}
+
+impl Compiler {
+    // NOTE: Open bounds are represented by null at runtime.
+    fn visit_slice_bound(&mut self, bound: Option<SyntaxNodeId>, node: &Slice) -> Result<(), Error> {
+        match bound {
+            Some(bound) => self.visit(bound),
+            None => {
+                self.assembler()?.push_null(node.span);
+                Ok(())
+            }
+        }
+    }
+}
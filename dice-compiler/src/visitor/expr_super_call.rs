@@ -15,7 +15,7 @@ impl NodeVisitor<&SuperCall> for Compiler {
             .scope_stack()
             .local(SELF)
             .expect("self should always be declared in constructors.")
-            .slot as u8;
+            .slot;
         self.assembler()?.load_local(local_slot, node.span);
 
         // NOTE: Store the temporary at the time the function call was started, to be restored later.
@@ -38,7 +38,7 @@ impl NodeVisitor<&SuperCall> for Compiler {
             identifier: SUPER.to_owned(),
             span: node.span,
         })?;
-        self.assembler()?.call_super(node.args.len() as u8, node.span);
+        self.assembler()?.call_super(node.args.len(), node.span);
 
         Ok(())
     }
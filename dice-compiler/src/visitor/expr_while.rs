@@ -17,13 +17,13 @@ impl NodeVisitor<&WhileLoop> for Compiler {
             let loop_end = self.assembler()?.jump_if_false(*span);
 
             self.visit((&block, BlockKind::Loop))?;
-            self.assembler()?.jump_back(loop_start, *span);
-            self.assembler()?.patch_jump(loop_end);
+            self.assembler()?.jump_back(loop_start, *span)?;
+            self.assembler()?.patch_jump(loop_end)?;
 
             let scope_close = self.context()?.scope_stack().pop_scope()?;
 
             for location in scope_close.exit_points.iter() {
-                self.assembler()?.patch_jump(*location as u64);
+                self.assembler()?.patch_jump(*location as u64)?;
             }
 
             self.assembler()?.push_unit(*span);
@@ -6,6 +6,8 @@ use crate::{compiler::Compiler, visitor::FnKind};
 
 use super::NodeVisitor;
 
+static ANONYMOUS_FN: &str = "<anonymous>";
+
 impl NodeVisitor<&LitAnonymousFn> for Compiler {
     fn visit(&mut self, node: &LitAnonymousFn) -> Result<(), Error> {
         let id = uuid::Uuid::new_v4();
@@ -13,7 +15,7 @@ impl NodeVisitor<&LitAnonymousFn> for Compiler {
         let body = self.syntax_tree.child(node.body);
         let mut fn_context = self.compile_fn(body, &node.args, node.return_.clone(), FnKind::Function)?;
         let upvalues = fn_context.upvalues().clone();
-        let bytecode = fn_context.finish(self.source.clone());
+        let bytecode = fn_context.finish(ANONYMOUS_FN, self.source.clone(), self.options.optimize);
         let value = ConstantValue::Function(FunctionBytecode::new(bytecode, name, id));
         let context = self.context()?;
 
@@ -9,7 +9,7 @@ impl NodeVisitor<&LitList> for Compiler {
             self.visit(*item)?;
         }
 
-        self.assembler()?.create_list(value.len() as u8, *span);
+        self.assembler()?.create_list(value.len(), *span);
 
         Ok(())
     }
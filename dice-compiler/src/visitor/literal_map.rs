@@ -0,0 +1,20 @@
+use super::NodeVisitor;
+use crate::compiler::Compiler;
+use dice_core::error::Error;
+use dice_syntax::LitMap;
+
+impl NodeVisitor<&LitMap> for Compiler {
+    fn visit(&mut self, LitMap { entries, span }: &LitMap) -> Result<(), Error> {
+        self.assembler()?.create_map(*span);
+
+        for (key, value) in entries {
+            self.assembler()?.dup(0, *span);
+            self.visit(*key)?;
+            self.visit(*value)?;
+            self.assembler()?.store_index(*span);
+            self.assembler()?.pop(*span);
+        }
+
+        Ok(())
+    }
+}
@@ -21,7 +21,7 @@ impl NodeVisitor<&LitIdent> for Compiler {
                     }));
                 }
 
-                let slot = scope_variable.slot as u8;
+                let slot = scope_variable.slot;
                 context.assembler().load_local(slot, *span);
 
                 return Ok(());
@@ -30,7 +30,7 @@ impl NodeVisitor<&LitIdent> for Compiler {
 
         if let Some(upvalue) = self.compiler_stack.resolve_upvalue(name_symbol, 0) {
             let context = self.context()?;
-            context.assembler().load_upvalue(upvalue as u8, *span);
+            context.assembler().load_upvalue(upvalue, *span);
 
             return Ok(());
         }
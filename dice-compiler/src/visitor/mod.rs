@@ -3,6 +3,7 @@ mod decl_export;
 mod decl_fn;
 mod decl_import;
 mod decl_op;
+mod decl_type_alias;
 mod decl_var;
 mod expr_assignment;
 mod expr_binary_op;
@@ -21,6 +22,7 @@ mod expr_null_propagate;
 mod expr_prefix_op;
 mod expr_range_loop;
 mod expr_return;
+mod expr_slice;
 mod expr_super_access;
 mod expr_super_call;
 mod expr_while;
@@ -29,12 +31,15 @@ mod literal_bool;
 mod literal_float;
 mod literal_int;
 mod literal_list;
+mod literal_map;
 mod literal_null;
 mod literal_object;
 mod literal_string;
 mod literal_unit;
 mod literal_variable;
 mod syntax_node;
+mod tail_call;
+mod type_annotation;
 
 use dice_core::error::Error;
 pub use expr_block::{BlockKind, FunctionBlockKind};
@@ -20,6 +20,7 @@ impl NodeVisitor<SyntaxNodeId> for Compiler {
             SyntaxNode::LitString(literal) => self.visit(literal)?,
             SyntaxNode::LitAnonymousFn(literal) => self.visit(literal)?,
             SyntaxNode::LitObject(literal) => self.visit(literal)?,
+            SyntaxNode::LitMap(literal) => self.visit(literal)?,
             SyntaxNode::LitList(literal) => self.visit(literal)?,
             SyntaxNode::Assignment(assignment) => self.visit(assignment)?,
             SyntaxNode::Prefix(unary) => self.visit(unary)?,
@@ -31,6 +32,7 @@ impl NodeVisitor<SyntaxNodeId> for Compiler {
             SyntaxNode::ClassDecl(class) => self.visit(class)?,
             SyntaxNode::ImportDecl(import) => self.visit(import)?,
             SyntaxNode::ExportDecl(export) => self.visit(export)?,
+            SyntaxNode::TypeAlias(type_alias) => self.visit(type_alias)?,
             SyntaxNode::IfExpression(conditional) => self.visit(conditional)?,
             SyntaxNode::Loop(loop_) => self.visit(loop_)?,
             SyntaxNode::WhileLoop(while_loop) => self.visit(while_loop)?,
@@ -74,6 +76,11 @@ impl NodeVisitor<SyntaxNodeId> for Compiler {
                 self.visit(index)?;
                 self.exit_call()?;
             }
+            SyntaxNode::Slice(slice) => {
+                self.enter_call()?;
+                self.visit(slice)?;
+                self.exit_call()?;
+            }
         }
 
         Ok(())
@@ -81,21 +88,21 @@ impl NodeVisitor<SyntaxNodeId> for Compiler {
 }
 
 impl Compiler {
-    fn enter_call(&mut self) -> Result<(), Error> {
+    pub(super) fn enter_call(&mut self) -> Result<(), Error> {
         let context = &mut self.context()?.scope_stack().top_mut()?.call_context;
         context.depth += 1;
 
         Ok(())
     }
 
-    fn exit_call(&mut self) -> Result<(), Error> {
+    pub(super) fn exit_call(&mut self) -> Result<(), Error> {
         let context = &mut self.context()?.scope_stack().top_mut()?.call_context;
         context.depth -= 1;
 
         if context.depth == 0 {
             let exit_points = std::mem::take(&mut context.exit_points);
             for exit_point in exit_points.into_iter() {
-                self.assembler()?.patch_jump(exit_point as u64);
+                self.assembler()?.patch_jump(exit_point as u64)?;
             }
         }
 
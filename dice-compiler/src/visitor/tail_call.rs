@@ -0,0 +1,83 @@
+use dice_core::error::Error;
+use dice_syntax::{SyntaxNode, SyntaxNodeId};
+
+use crate::{compiler::Compiler, compiler_stack::CompilerKind, scope_stack::ScopeKind};
+
+use super::{BlockKind, NodeVisitor};
+
+impl Compiler {
+    /// Visit an expression whose value is returned from the current function, compiling any call in tail position to
+    /// a `TAIL_CALL`, so that recursion doesn't grow the stack.
+    pub(super) fn visit_tail(&mut self, node: SyntaxNodeId) -> Result<(), Error> {
+        if !self.can_tail_call()? {
+            return self.visit(node);
+        }
+
+        match self.syntax_tree.get(node).clone() {
+            SyntaxNode::FnCall(fn_call) => {
+                self.enter_call()?;
+                self.tail_fn_call(&fn_call)?;
+                self.exit_call()?;
+            }
+            SyntaxNode::IfExpression(if_expression) => self.if_expression(&if_expression, true)?,
+            SyntaxNode::Block(block) => self.visit((&block, BlockKind::Tail))?,
+            _ => self.visit(node)?,
+        }
+
+        Ok(())
+    }
+
+    /* NOTE: Returns with a declared type assert the result after the call, so they can't be replaced.  Temporaries and
+     * loop state must also not be left on the stack, as the callee takes over the frame from the top of the stack.
+     */
+    fn can_tail_call(&mut self) -> Result<bool, Error> {
+        let context = self.context()?;
+        let is_untyped_function = matches!(
+            context.kind(),
+            CompilerKind::Function { return_type: None } | CompilerKind::Method { return_type: None }
+        );
+
+        Ok(is_untyped_function
+            && *context.temporary_count() == 0
+            && !context.scope_stack().in_context_of(ScopeKind::Loop))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use dice_bytecode::{Bytecode, ConstantValue};
+    use dice_core::source::{Source, SourceKind};
+
+    use crate::compiler::Compiler;
+
+    fn compile_fn(source: &str) -> Bytecode {
+        let source = Source::new(source.to_owned(), SourceKind::Script);
+        let bytecode = Compiler::compile_source(source).expect("Source should compile.");
+        bytecode.verify().expect("Bytecode should verify.");
+
+        bytecode
+            .constants()
+            .iter()
+            .find_map(|constant| match constant {
+                ConstantValue::Function(function) => Some(function.bytecode.clone()),
+                _ => None,
+            })
+            .expect("Script should declare a function.")
+    }
+
+    #[test]
+    fn test_calls_in_tail_position_are_tail_calls() {
+        let function = compile_fn("fn count(n) { if n == 0 { return 0 } if n > 0 { count(n - 1) } else { count(0) } }");
+        let disassembly = function.to_string();
+
+        assert_eq!(2, disassembly.matches("TAIL_CALL").count());
+        assert!(!disassembly.contains(" CALL"));
+    }
+
+    #[test]
+    fn test_calls_with_typed_returns_are_not_tail_calls() {
+        let function = compile_fn("fn count(n) -> Int { if n == 0 { return 0 } count(n - 1) }");
+
+        assert!(!function.to_string().contains("TAIL_CALL"));
+    }
+}
@@ -0,0 +1,78 @@
+use dice_bytecode::{TypeShape, TypeShapeKind};
+use dice_core::error::Error;
+use dice_syntax::{TypeAnnotation, TypeAnnotationKind};
+
+use super::NodeVisitor;
+use crate::compiler::Compiler;
+
+// NOTE: Pushes the classes referenced by a type annotation onto the stack in pre-order, matching the layout
+// expected by the shape produced by `type_shape`.  Type aliases must be resolved before visiting.
+impl NodeVisitor<&TypeAnnotation> for Compiler {
+    fn visit(&mut self, type_: &TypeAnnotation) -> Result<(), Error> {
+        match &type_.kind {
+            TypeAnnotationKind::Named(name) => self.visit(name)?,
+            TypeAnnotationKind::Array(element) => self.visit(&**element)?,
+            // NOTE: Functions don't carry type information at runtime, so only the value being callable is checked.
+            TypeAnnotationKind::Function { .. } => {}
+            TypeAnnotationKind::Union(members) => {
+                for member in members {
+                    self.visit(member)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Compiler {
+    /// Expand any type aliases referenced by the annotation.
+    pub(super) fn resolve_type(&mut self, type_: &TypeAnnotation) -> Result<TypeAnnotation, Error> {
+        let kind = match &type_.kind {
+            TypeAnnotationKind::Named(name) => match self.compiler_stack.resolve_type_alias(&name.identifier) {
+                Some(alias) => {
+                    return Ok(TypeAnnotation {
+                        is_nullable: type_.is_nullable || alias.is_nullable,
+                        span: type_.span,
+                        ..alias
+                    })
+                }
+                None => TypeAnnotationKind::Named(name.clone()),
+            },
+            TypeAnnotationKind::Array(element) => TypeAnnotationKind::Array(Box::new(self.resolve_type(element)?)),
+            TypeAnnotationKind::Function { args, return_ } => TypeAnnotationKind::Function {
+                args: args
+                    .iter()
+                    .map(|arg| self.resolve_type(arg))
+                    .collect::<Result<Vec<_>, _>>()?,
+                return_: match return_ {
+                    Some(return_) => Some(Box::new(self.resolve_type(return_)?)),
+                    None => None,
+                },
+            },
+            TypeAnnotationKind::Union(members) => TypeAnnotationKind::Union(
+                members
+                    .iter()
+                    .map(|member| self.resolve_type(member))
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+        };
+
+        Ok(TypeAnnotation {
+            kind,
+            is_nullable: type_.is_nullable,
+            span: type_.span,
+        })
+    }
+}
+
+pub(super) fn type_shape(type_: &TypeAnnotation) -> TypeShape {
+    let kind = match &type_.kind {
+        TypeAnnotationKind::Named(_) => TypeShapeKind::Class,
+        TypeAnnotationKind::Array(element) => TypeShapeKind::Array(Box::new(type_shape(element))),
+        TypeAnnotationKind::Function { .. } => TypeShapeKind::Function,
+        TypeAnnotationKind::Union(members) => TypeShapeKind::Union(members.iter().map(type_shape).collect()),
+    };
+
+    TypeShape::new(kind, type_.is_nullable)
+}
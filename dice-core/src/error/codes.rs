@@ -17,6 +17,7 @@ pub static INTERNAL_COMPILER_ERROR: ErrorCode = "E2000";
 
 pub static TOO_MANY_UPVALUES: ErrorCode = "E2100";
 pub static TOO_MANY_CONSTANTS: ErrorCode = "E2101";
+pub static JUMP_TOO_FAR: ErrorCode = "E2102";
 
 pub static NEW_METHOD_CANNOT_HAVE_RETURN_TYPE: ErrorCode = "E2200";
 pub static NEW_METHOD_MUST_HAVE_RECEIVER: ErrorCode = "E2201";
@@ -41,6 +42,11 @@ pub static INVALID_CONTINUE_USAGE: ErrorCode = "E2502";
 pub static INVALID_ERROR_PROPAGATE_USAGE: ErrorCode = "E2503";
 pub static INVALID_EXPORT_USAGE: ErrorCode = "E2504";
 pub static INVALID_IMPORT_USAGE: ErrorCode = "E2505";
+pub static INVALID_IS_USAGE: ErrorCode = "E2506";
+
+pub static TYPE_MISMATCH: ErrorCode = "E2600";
+
+pub static UNSUPPORTED_BY_REGISTER_BACKEND: ErrorCode = "E2700";
 
 // Runtime errors
 pub static INVALID_BOOL_CONVERSION: ErrorCode = "E3000";
@@ -51,6 +57,10 @@ pub static INVALID_STRING_CONVERSION: ErrorCode = "E3004";
 pub static INVALID_SYMBOL_CONVERSION: ErrorCode = "E3005";
 pub static INVALID_OBJECT_CONVERSION: ErrorCode = "E3006";
 pub static INVALID_CLASS_CONVERSION: ErrorCode = "E3007";
+pub static INVALID_TYPE_SHAPE_CONVERSION: ErrorCode = "E3008";
+pub static INVALID_MAP_CONVERSION: ErrorCode = "E3009";
+pub static INVALID_MAP_KEY_CONVERSION: ErrorCode = "E3010";
+pub static INVALID_CONSTANT_CONVERSION: ErrorCode = "E3011";
 
 pub static TYPE_ASSERTION_FAILURE: ErrorCode = "E3100";
 pub static TYPE_ASSERTION_NULLABILITY_FAILURE: ErrorCode = "E3101";
@@ -69,8 +79,36 @@ pub static NEW_FUNCTION_CANNOT_BE_ACCESS_DIRECTLY: ErrorCode = "E3400";
 pub static CLASS_MUST_HAVE_NEW_IF_SUPER_HAS_NEW: ErrorCode = "E3401";
 pub static CLASS_CANNOT_INHERIT_VALUE_TYPE: ErrorCode = "E3402";
 
+pub static INDEX_OUT_OF_BOUNDS: ErrorCode = "E3500";
+
+pub static INSTRUCTION_LIMIT_EXCEEDED: ErrorCode = "E3600";
+pub static TIME_LIMIT_EXCEEDED: ErrorCode = "E3601";
+pub static CALL_DEPTH_LIMIT_EXCEEDED: ErrorCode = "E3602";
+pub static HEAP_LIMIT_EXCEEDED: ErrorCode = "E3603";
+pub static STACK_OVERFLOW: ErrorCode = "E3604";
+pub static SCRIPT_INTERRUPTED: ErrorCode = "E3605";
+
 pub static PANIC: ErrorCode = "E4000";
 pub static IO_ERROR: ErrorCode = "E4001";
 pub static INVALID_SCRIPT_LOCATION: ErrorCode = "E4002";
 pub static MODULE_ALREADY_EXISTS: ErrorCode = "E4003";
 pub static GLOBAL_ALREADY_EXISTS: ErrorCode = "E4002";
+
+pub static INVALID_BYTECODE_FORMAT: ErrorCode = "E4100";
+pub static BYTECODE_VERSION_MISMATCH: ErrorCode = "E4101";
+pub static BYTECODE_CHECKSUM_MISMATCH: ErrorCode = "E4102";
+pub static INVALID_OPCODE: ErrorCode = "E4103";
+pub static INCOMPLETE_INSTRUCTION: ErrorCode = "E4104";
+pub static INVALID_CONSTANT_INDEX: ErrorCode = "E4105";
+pub static INVALID_CONSTANT_TYPE: ErrorCode = "E4106";
+pub static INVALID_LOCAL_SLOT: ErrorCode = "E4107";
+pub static INVALID_UPVALUE_INDEX: ErrorCode = "E4108";
+pub static INVALID_JUMP_TARGET: ErrorCode = "E4109";
+pub static STACK_UNDERFLOW: ErrorCode = "E4110";
+pub static INCONSISTENT_STACK_DEPTH: ErrorCode = "E4111";
+pub static INVALID_FLAG: ErrorCode = "E4112";
+pub static INVALID_CACHE_SLOT: ErrorCode = "E4113";
+
+pub static SNAPSHOT_UNSUPPORTED_VALUE: ErrorCode = "E4200";
+pub static INVALID_SNAPSHOT: ErrorCode = "E4201";
+pub static SNAPSHOT_GLOBAL_UNDEFINED: ErrorCode = "E4202";
@@ -38,3 +38,4 @@ pub static EXPORT_ONLY_ALLOWED_IN_TOP_LEVEL_SCOPE: ContextMsgId = "export-only-a
 pub static IMPORT_REQUIRES_ITEMS_TO_BE_IMPORTED: ContextMsgId = "import-requires-items-to-be-imported";
 pub static IMPORT_REQUIRES_ITEMS_TO_BE_IMPORTED_HELP: ContextMsgId = "import-requires-items-to-be-imported-help";
 pub static MISMATCHED_TYPE_ASSERTIONS: ContextMsgId = "mismatched-type-assertions";
+pub static TRACE_TRUNCATED: ContextMsgId = "trace-truncated";
@@ -1,13 +1,11 @@
 use colored::Colorize;
 
-use crate::{
-    error::{
-        localization::{localize_error_code, Locale},
-        Error,
-    },
+use crate::error::{
+    localization::{localize_error_code, Locale},
+    Error,
 };
-use std::fmt::Write;
 use crate::source::Source;
+use std::fmt::Write;
 
 use super::{context::ContextKind, localization::localize_context_msg_id};
 
@@ -129,33 +127,29 @@ impl HumanReadableErrorFormatter {
     fn fmt_trace(buffer: &mut impl Write, error: &Error) -> std::fmt::Result {
         if !error.trace.is_empty() {
             writeln!(buffer)?;
-            writeln!(buffer, "Trace:")?;
+            writeln!(buffer, "Trace (most recent call last):")?;
 
+            // NOTE: Traces are pushed as the error unwinds, so the outermost call is at the end.
             for trace in error.trace.iter().rev() {
                 let position = trace.source.line_index().position_of(trace.span.start);
+                let path = trace.source.path().unwrap_or("<Script>");
 
-                if let Some(path) = &trace.source.path() {
-                    writeln!(
-                        buffer,
-                        "  Location: {}:{}:{}",
-                        path,
-                        position.line + 1,
-                        position.column_utf16 + 1
-                    )?;
-                } else {
-                    writeln!(
-                        buffer,
-                        "  Location: <Script>:{}:{}",
-                        position.line + 1,
-                        position.column_utf16 + 1
-                    )?;
-                }
+                writeln!(
+                    buffer,
+                    "  at {} ({}:{}:{})",
+                    trace.function.bold(),
+                    path,
+                    position.line + 1,
+                    position.column_utf16 + 1
+                )?;
 
                 for line in trace.source.line_index().lines(trace.span) {
+                    let line_no = trace.source.line_index().position_of(line.start).line + 1;
+
                     writeln!(
                         buffer,
                         "    {:<4} | {}",
-                        position.line + 1,
+                        line_no,
                         &trace.source.source()[line.range()].trim()
                     )?
                 }
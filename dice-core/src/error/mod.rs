@@ -6,19 +6,19 @@ pub mod tag;
 pub mod trace;
 
 use self::{codes::IO_ERROR, trace::ErrorTrace};
+use crate::source::Source;
+use crate::span::Span;
 use crate::{
     error::{
         codes::ErrorCode,
         fmt::{ErrorFormatter, HumanReadableErrorFormatter},
         localization::Locale,
     },
-    tags
+    tags,
 };
 use context::Context;
 use std::fmt::{Debug, Display, Formatter};
 use tag::Tags;
-use crate::source::Source;
-use crate::span::Span;
 
 #[derive(thiserror::Error, Clone)]
 pub struct Error {
@@ -46,6 +46,18 @@ impl Error {
         &self.error_code
     }
 
+    pub fn trace(&self) -> &[ErrorTrace] {
+        &self.trace
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn tag(&self, key: &str) -> Option<&str> {
+        self.tags.get(key)
+    }
+
     pub const fn with_span(mut self, span: Span) -> Self {
         self.span = span;
         self
@@ -9,6 +9,13 @@ impl Tags {
     pub fn push(&mut self, key: &'static str, value: impl Into<String>) {
         self.0.push((key, value.into()));
     }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(tag, _)| *tag == key)
+            .map(|(_, value)| value.as_str())
+    }
 }
 
 #[macro_export]
@@ -3,17 +3,17 @@ use crate::span::Span;
 
 #[derive(Debug, Clone)]
 pub struct ErrorTrace {
+    pub function: String,
     pub source: Source,
     pub span: Span,
 }
 
 impl ErrorTrace {
-    // pub fn from_bytecode(bytecode: &Bytecode, offset: u64) -> Self {
-    //     todo!();
-
-    //     // let span = bytecode.source_map()[&offset];
-    //     // let source = bytecode.source().clone();
-
-    //     // Self { source, span }
-    // }
+    pub fn new(function: impl Into<String>, source: Source, span: Span) -> Self {
+        Self {
+            function: function.into(),
+            source,
+            span,
+        }
+    }
 }
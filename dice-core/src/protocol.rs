@@ -39,6 +39,12 @@ pub mod object {
     pub static MODULE_CLASS: &str = "Module";
 }
 
+pub mod types {
+    pub static ARRAY: &str = "Array";
+    pub static FUNCTION: &str = "Function";
+    pub static MAP: &str = "Map";
+}
+
 pub mod error {
     pub static IS_OK: &str = "is_ok";
     pub static RESULT: &str = "result";
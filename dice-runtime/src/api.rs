@@ -0,0 +1,26 @@
+use dice_core::error::Error;
+
+use crate::{
+    runtime::RuntimeContext,
+    value::{Class, Object, Value},
+};
+
+/// The interface exposed to hosts and native functions while the runtime's arena is being mutated.
+pub trait Runtime<'gc> {
+    /// The context used to allocate values and intern symbols.
+    fn context(&self) -> RuntimeContext<'gc>;
+
+    fn new_module(&mut self, name: &str) -> Result<Object<'gc>, Error>;
+    fn new_class(&mut self, name: &str) -> Result<Class<'gc>, Error>;
+    fn new_object(&mut self) -> Result<Object<'gc>, Error>;
+
+    /// Load the module at the given path and define each of its exports as a global.
+    fn load_prelude(&mut self, path: &str) -> Result<(), Error>;
+    fn add_global(&mut self, name: &str, value: Value<'gc>) -> Result<(), Error>;
+
+    fn call_function(&mut self, target: Value<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error>;
+
+    fn any_class(&self) -> Result<Class<'gc>, Error>;
+    fn class_of(&self, value: &Value<'gc>) -> Result<Class<'gc>, Error>;
+    fn is_value_of_type(&self, value: &Value<'gc>, class: &Class<'gc>) -> Result<bool, Error>;
+}
@@ -1,66 +1,70 @@
-use crate::module::ModuleLoader;
 use dice_core::{
     error::Error,
-    protocol::{
-        object::{ANY_CLASS, TO_STRING},
-        ProtocolSymbol,
-    },
-    runtime::Runtime,
-    value::{Class, NativeFn, Value},
+    protocol::object::{ANY_CLASS, TO_STRING},
 };
 
-impl<L> crate::Runtime<L>
-where
-    L: ModuleLoader,
-{
-    pub fn new_any_class() -> Class {
-        let class = Class::new(ANY_CLASS.get());
+use crate::{
+    api::Runtime,
+    classes::set_method,
+    runtime::RuntimeContext,
+    value::{Class, NativeFn, OwnedValue, Value},
+};
 
-        class.set_method(TO_STRING.get(), Box::new(to_string) as NativeFn);
-        class.set_method("fields", Box::new(fields) as NativeFn);
-        class.set_method("methods", Box::new(methods) as NativeFn);
-        class.set_method("class_of", Box::new(class_of) as NativeFn);
-        class
-    }
+pub fn new_any_class<'gc>(ctx: &RuntimeContext<'gc>) -> Class<'gc> {
+    let class = Class::new(ctx, ctx.symbol(ANY_CLASS));
+
+    set_method(ctx, &class, TO_STRING, Box::new(to_string) as NativeFn);
+    set_method(ctx, &class, "fields", Box::new(fields) as NativeFn);
+    set_method(ctx, &class, "methods", Box::new(methods) as NativeFn);
+    set_method(ctx, &class, "class_of", Box::new(class_of) as NativeFn);
+    class
 }
 
-fn to_string(_: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn to_string<'gc>(runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
     match args {
-        [value, ..] => Ok(Value::with_string(format!("{}", value))),
+        [value, ..] => Ok(Value::with_string(
+            OwnedValue::from_value(&runtime.context(), value).to_string(),
+        )),
         _ => Ok(Value::Null),
     }
 }
 
-fn fields(_: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn fields<'gc>(runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
+    let ctx = runtime.context();
     let result = args
         .first()
         .and_then(|value| value.as_object().ok())
         .map_or(Value::Null, |object| {
-            let fields = object.fields().keys().map(Value::with_string).collect::<Vec<_>>();
+            let fields = object
+                .fields()
+                .keys()
+                .map(|key| Value::String(ctx.string(*key)))
+                .collect::<Vec<_>>();
 
-            Value::with_vec(fields)
+            Value::with_vec(&ctx, fields)
         });
 
     Ok(result)
 }
 
-fn methods(runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn methods<'gc>(runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
     match args {
         [this, ..] => {
+            let ctx = runtime.context();
             let class = runtime.class_of(this)?;
             let result = class
                 .methods()
                 .iter()
-                .map(|(key, _)| Value::with_string(key))
+                .map(|(key, _)| Value::String(ctx.string(*key)))
                 .collect::<Vec<_>>();
 
-            Ok(Value::with_vec(result))
+            Ok(Value::with_vec(&ctx, result))
         }
         _ => Ok(Value::Null),
     }
 }
 
-fn class_of(runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn class_of<'gc>(runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
     match args {
         [this, ..] => Ok(Value::Class(runtime.class_of(this)?)),
         _ => Ok(Value::Null),
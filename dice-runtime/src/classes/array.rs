@@ -1,46 +1,52 @@
-use crate::module::ModuleLoader;
 use dice_core::{
     error::Error,
     protocol::{
         class::NEW,
         iterator::{DONE, NEXT, VALUE},
-        ProtocolSymbol,
     },
-    runtime::Runtime,
-    value::{NativeFn, Symbol, Value, ValueKind},
 };
-use std::cell::RefCell;
 
-impl<L> crate::Runtime<L>
+use crate::{
+    api::Runtime,
+    classes::set_method,
+    module::ModuleLoader,
+    value::{FnBound, NativeFn, Value, ValueKind},
+};
+
+// NOTE: Native functions can't capture values from the arena, so iterators keep their position in fields.
+static ITERATOR_ARRAY: &str = "#array";
+static ITERATOR_INDEX: &str = "#index";
+
+impl<L> crate::interpreter::Interpreter<'_, '_, L>
 where
     L: ModuleLoader,
 {
     pub(super) fn register_array(&mut self) {
-        let class = self.any_class.derive("Array");
+        let class = self.derive_any_class("Array");
 
-        class.set_method(&NEW, Box::new(construct_array) as NativeFn);
-        class.set_method("push", Box::new(push) as NativeFn);
-        class.set_method("pop", Box::new(pop) as NativeFn);
-        class.set_method("length", Box::new(length) as NativeFn);
-        class.set_method("first", Box::new(first) as NativeFn);
-        class.set_method("filter", Box::new(filter) as NativeFn);
-        class.set_method("map", Box::new(map) as NativeFn);
-        class.set_method("iter", Box::new(iter) as NativeFn);
+        set_method(&self.ctx, &class, NEW, Box::new(construct_array) as NativeFn);
+        set_method(&self.ctx, &class, "push", Box::new(push) as NativeFn);
+        set_method(&self.ctx, &class, "pop", Box::new(pop) as NativeFn);
+        set_method(&self.ctx, &class, "length", Box::new(length) as NativeFn);
+        set_method(&self.ctx, &class, "first", Box::new(first) as NativeFn);
+        set_method(&self.ctx, &class, "filter", Box::new(filter) as NativeFn);
+        set_method(&self.ctx, &class, "map", Box::new(map) as NativeFn);
+        set_method(&self.ctx, &class, "iter", Box::new(iter) as NativeFn);
 
         self.set_value_class(ValueKind::Array, class);
     }
 }
 
-fn construct_array(_runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn construct_array<'gc>(runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
     match args {
-        [_, rest @ ..] => Ok(Value::Array(rest.to_vec().into())),
+        [_, rest @ ..] => Ok(Value::with_vec(&runtime.context(), rest.to_vec())),
         _ => Ok(Value::Null),
     }
 }
 
-fn push(_runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn push<'gc>(runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
     if let [Value::Array(arr), param, ..] = args {
-        arr.push(param.clone());
+        arr.push(&runtime.context(), param.clone());
 
         Ok(Value::Unit)
     } else {
@@ -48,9 +54,9 @@ fn push(_runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
     }
 }
 
-fn pop(_runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn pop<'gc>(runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
     if let [Value::Array(arr), ..] = args {
-        let result = arr.pop().unwrap_or(Value::Unit);
+        let result = arr.pop(&runtime.context()).unwrap_or(Value::Unit);
 
         Ok(result)
     } else {
@@ -58,7 +64,7 @@ fn pop(_runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
     }
 }
 
-fn length(_runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn length<'gc>(_runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
     if let [Value::Array(arr), ..] = args {
         Ok(Value::Int(arr.elements().len() as i64))
     } else {
@@ -66,7 +72,7 @@ fn length(_runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
     }
 }
 
-fn first(runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn first<'gc>(runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
     match args {
         [Value::Array(arr), predicate, ..] => Ok(arr
             .elements()
@@ -85,10 +91,11 @@ fn first(runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
     }
 }
 
-fn filter(runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn filter<'gc>(runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
     match args {
-        [Value::Array(arr), predicate, ..] => Ok(Value::Array(
-            arr.elements()
+        [Value::Array(arr), predicate, ..] => {
+            let result = arr
+                .elements()
                 .iter()
                 .filter(|value| {
                     runtime
@@ -98,17 +105,19 @@ fn filter(runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
                         .unwrap_or(false)
                 })
                 .cloned()
-                .collect::<Vec<_>>()
-                .into(),
-        )),
+                .collect::<Vec<_>>();
+
+            Ok(Value::with_vec(&runtime.context(), result))
+        }
         _ => Ok(Value::Null),
     }
 }
 
-fn map(runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn map<'gc>(runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
     match args {
-        [Value::Array(arr), selector, ..] => Ok(Value::Array(
-            arr.elements()
+        [Value::Array(arr), selector, ..] => {
+            let result = arr
+                .elements()
                 .iter()
                 .map(|value| {
                     runtime
@@ -116,39 +125,58 @@ fn map(runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
                         .ok()
                         .unwrap_or(Value::Null)
                 })
-                .collect::<Vec<_>>()
-                .into(),
-        )),
+                .collect::<Vec<_>>();
+
+            Ok(Value::with_vec(&runtime.context(), result))
+        }
         _ => Ok(Value::Null),
     }
 }
 
-fn iter(runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn iter<'gc>(runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
     match args {
         [Value::Array(arr), ..] => {
-            let arr = arr.clone();
-            let current = RefCell::new(0);
-            let value_symbol: Symbol = VALUE.get();
-            let done_symbol: Symbol = DONE.get();
-            let next: NativeFn = Box::new(move |runtime, _| {
-                let result = runtime.new_object()?;
-
-                if *current.borrow() < arr.elements().len() {
-                    result.set_field(value_symbol.clone(), arr.elements()[*current.borrow()].clone());
-                    result.set_field(done_symbol.clone(), Value::Bool(false));
-                    *current.borrow_mut() += 1;
-                } else {
-                    result.set_field(done_symbol.clone(), Value::Bool(true));
-                }
-
-                Ok(Value::Object(result))
-            });
-
+            let ctx = runtime.context();
             let iterator = runtime.new_object()?;
-            iterator.set_field(&NEXT, Value::with_native_fn(next));
+            iterator.set_field(&ctx, ctx.symbol(ITERATOR_ARRAY), Value::Array(arr.clone()));
+            iterator.set_field(&ctx, ctx.symbol(ITERATOR_INDEX), Value::Int(0));
+
+            let next = FnBound::new(
+                &ctx,
+                Value::Object(iterator.clone()),
+                Value::with_native_fn(Box::new(next) as NativeFn),
+            );
+            iterator.set_field(&ctx, ctx.symbol(NEXT), Value::FnBound(next));
 
             Ok(Value::Object(iterator))
         }
         _ => Ok(Value::Null),
     }
 }
+
+fn next<'gc>(runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
+    match args {
+        [Value::Object(iterator), ..] => {
+            let ctx = runtime.context();
+            let array = iterator.field(ctx.symbol(ITERATOR_ARRAY)).unwrap_or_default();
+            let index = iterator
+                .field(ctx.symbol(ITERATOR_INDEX))
+                .unwrap_or_default()
+                .as_int()?;
+            let result = runtime.new_object()?;
+            let element = array.as_array()?.elements().get(index as usize).cloned();
+
+            match element {
+                Some(element) => {
+                    result.set_field(&ctx, ctx.symbol(VALUE), element);
+                    result.set_field(&ctx, ctx.symbol(DONE), Value::Bool(false));
+                    iterator.set_field(&ctx, ctx.symbol(ITERATOR_INDEX), Value::Int(index + 1));
+                }
+                None => result.set_field(&ctx, ctx.symbol(DONE), Value::Bool(true)),
+            }
+
+            Ok(Value::Object(result))
+        }
+        _ => Ok(Value::Null),
+    }
+}
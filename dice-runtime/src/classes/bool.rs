@@ -1,25 +1,26 @@
-use crate::module::ModuleLoader;
-use dice_core::{
-    error::Error,
-    protocol::class::NEW,
-    runtime::Runtime,
+use dice_core::{error::Error, protocol::class::NEW};
+
+use crate::{
+    api::Runtime,
+    classes::set_method,
+    module::ModuleLoader,
     value::{NativeFn, Value, ValueKind},
 };
 
-impl<L> crate::Runtime<L>
+impl<L> crate::interpreter::Interpreter<'_, '_, L>
 where
     L: ModuleLoader,
 {
     pub(super) fn register_bool(&mut self) {
-        let class = self.any_class.derive("Bool");
+        let class = self.derive_any_class("Bool");
 
-        class.set_method(&NEW, Box::new(construct_bool) as NativeFn);
+        set_method(&self.ctx, &class, NEW, Box::new(construct_bool) as NativeFn);
 
         self.set_value_class(ValueKind::Bool, class);
     }
 }
 
-fn construct_bool(_runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn construct_bool<'gc>(_runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
     match args {
         [_, param, ..] => match param {
             value @ Value::Bool(_) => Ok(value.clone()),
@@ -1,31 +1,33 @@
-use crate::module::ModuleLoader;
-use dice_core::{
-    error::Error,
-    runtime::Runtime,
+use dice_core::error::Error;
+
+use crate::{
+    api::Runtime,
+    classes::set_method,
+    module::ModuleLoader,
     value::{NativeFn, Value, ValueKind},
 };
 
-impl<L> crate::Runtime<L>
+impl<L> crate::interpreter::Interpreter<'_, '_, L>
 where
     L: ModuleLoader,
 {
     pub fn register_class(&mut self) {
-        let class = self.any_class.derive("Class");
-        class.set_method("name", Box::new(name) as NativeFn);
-        class.set_method("base", Box::new(base_class) as NativeFn);
+        let class = self.derive_any_class("Class");
+        set_method(&self.ctx, &class, "name", Box::new(name) as NativeFn);
+        set_method(&self.ctx, &class, "base", Box::new(base_class) as NativeFn);
 
         self.set_value_class(ValueKind::Class, class);
     }
 }
 
-fn name(_: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn name<'gc>(runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
     match args {
-        [Value::Class(class), ..] => Ok(Value::with_string(class.name())),
+        [Value::Class(class), ..] => Ok(Value::String(runtime.context().string(class.name()))),
         _ => Ok(Value::Null),
     }
 }
 
-fn base_class(_: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn base_class<'gc>(_: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
     match args {
         [Value::Class(class), ..] => {
             let result = class.base().map_or_else(|| Value::Null, Value::Class);
@@ -1,72 +1,83 @@
-use crate::module::ModuleLoader;
-use dice_core::{
-    error::Error,
-    protocol::class::NEW,
-    runtime::Runtime,
-    value::{NativeFn, Value, ValueKind},
+use dice_core::{error::Error, protocol::class::NEW};
+
+use crate::{
+    api::Runtime,
+    classes::{set_field, set_method},
+    module::ModuleLoader,
+    value::{native_fn, NativeFn, Value, ValueKind},
 };
 
-impl<L> crate::Runtime<L>
+impl<L> crate::interpreter::Interpreter<'_, '_, L>
 where
     L: ModuleLoader,
 {
     pub fn register_float(&mut self) {
-        let class = self.any_class.derive("Float");
+        let class = self.derive_any_class("Float");
 
         // NOTE: This does not currently expose all possible functions rust has, just a subset.
         // If the need arises, this list can be further expanded.
-        class.set_method(&NEW, Box::new(construct_float) as NativeFn);
-        class.set_method("abs", bind_f64_ret_f64(f64::abs));
-        class.set_method("sqrt", bind_f64_ret_f64(f64::sqrt));
-        class.set_method("cbrt", bind_f64_ret_f64(f64::cbrt));
-        class.set_method("floor", bind_f64_ret_f64(f64::floor));
-        class.set_method("ceil", bind_f64_ret_f64(f64::ceil));
-        class.set_method("round", bind_f64_ret_f64(f64::round));
-        class.set_method("cos", bind_f64_ret_f64(f64::cos));
-        class.set_method("sin", bind_f64_ret_f64(f64::sin));
-        class.set_method("tan", bind_f64_ret_f64(f64::tan));
-        class.set_method("acos", bind_f64_ret_f64(f64::acos));
-        class.set_method("asin", bind_f64_ret_f64(f64::asin));
-        class.set_method("atan", bind_f64_ret_f64(f64::atan));
-        class.set_method("atan2", bind_f64_f64_ret_f64(f64::atan2));
-        class.set_method("cosh", bind_f64_ret_f64(f64::cosh));
-        class.set_method("sinh", bind_f64_ret_f64(f64::sinh));
-        class.set_method("tanh", bind_f64_ret_f64(f64::tanh));
-        class.set_method("acosh", bind_f64_ret_f64(f64::acosh));
-        class.set_method("asinh", bind_f64_ret_f64(f64::asinh));
-        class.set_method("atanh", bind_f64_ret_f64(f64::atanh));
-        class.set_method("log", bind_f64_f64_ret_f64(f64::log));
-        class.set_method("log2", bind_f64_ret_f64(f64::log2));
-        class.set_method("ln", bind_f64_ret_f64(f64::ln));
-        class.set_method("log10", bind_f64_ret_f64(f64::log10));
-        class.set_method("pow", bind_f64_f64_ret_f64(f64::powf));
-        class.set_method("to_degrees", bind_f64_ret_f64(f64::to_degrees));
-        class.set_method("to_radians", bind_f64_ret_f64(f64::to_radians));
-        class.set_method("min", bind_f64_f64_ret_f64(f64::min));
-        class.set_method("max", bind_f64_f64_ret_f64(f64::max));
+        set_method(&self.ctx, &class, NEW, Box::new(construct_float) as NativeFn);
+        set_method(&self.ctx, &class, "abs", bind_f64_ret_f64(f64::abs));
+        set_method(&self.ctx, &class, "sqrt", bind_f64_ret_f64(f64::sqrt));
+        set_method(&self.ctx, &class, "cbrt", bind_f64_ret_f64(f64::cbrt));
+        set_method(&self.ctx, &class, "floor", bind_f64_ret_f64(f64::floor));
+        set_method(&self.ctx, &class, "ceil", bind_f64_ret_f64(f64::ceil));
+        set_method(&self.ctx, &class, "round", bind_f64_ret_f64(f64::round));
+        set_method(&self.ctx, &class, "cos", bind_f64_ret_f64(f64::cos));
+        set_method(&self.ctx, &class, "sin", bind_f64_ret_f64(f64::sin));
+        set_method(&self.ctx, &class, "tan", bind_f64_ret_f64(f64::tan));
+        set_method(&self.ctx, &class, "acos", bind_f64_ret_f64(f64::acos));
+        set_method(&self.ctx, &class, "asin", bind_f64_ret_f64(f64::asin));
+        set_method(&self.ctx, &class, "atan", bind_f64_ret_f64(f64::atan));
+        set_method(&self.ctx, &class, "atan2", bind_f64_f64_ret_f64(f64::atan2));
+        set_method(&self.ctx, &class, "cosh", bind_f64_ret_f64(f64::cosh));
+        set_method(&self.ctx, &class, "sinh", bind_f64_ret_f64(f64::sinh));
+        set_method(&self.ctx, &class, "tanh", bind_f64_ret_f64(f64::tanh));
+        set_method(&self.ctx, &class, "acosh", bind_f64_ret_f64(f64::acosh));
+        set_method(&self.ctx, &class, "asinh", bind_f64_ret_f64(f64::asinh));
+        set_method(&self.ctx, &class, "atanh", bind_f64_ret_f64(f64::atanh));
+        set_method(&self.ctx, &class, "log", bind_f64_f64_ret_f64(f64::log));
+        set_method(&self.ctx, &class, "log2", bind_f64_ret_f64(f64::log2));
+        set_method(&self.ctx, &class, "ln", bind_f64_ret_f64(f64::ln));
+        set_method(&self.ctx, &class, "log10", bind_f64_ret_f64(f64::log10));
+        set_method(&self.ctx, &class, "pow", bind_f64_f64_ret_f64(f64::powf));
+        set_method(&self.ctx, &class, "to_degrees", bind_f64_ret_f64(f64::to_degrees));
+        set_method(&self.ctx, &class, "to_radians", bind_f64_ret_f64(f64::to_radians));
+        set_method(&self.ctx, &class, "min", bind_f64_f64_ret_f64(f64::min));
+        set_method(&self.ctx, &class, "max", bind_f64_f64_ret_f64(f64::max));
 
-        class.set_method("is_finite", bind_f64_ret_bool(f64::is_finite));
-        class.set_method("is_infinite", bind_f64_ret_bool(f64::is_infinite));
-        class.set_method("is_nan", bind_f64_ret_bool(f64::is_nan));
-        class.set_method("is_sign_negative", bind_f64_ret_bool(f64::is_sign_negative));
-        class.set_method("is_sign_positive", bind_f64_ret_bool(f64::is_sign_positive));
-        class.set_method("is_normal", bind_f64_ret_bool(f64::is_normal));
+        set_method(&self.ctx, &class, "is_finite", bind_f64_ret_bool(f64::is_finite));
+        set_method(&self.ctx, &class, "is_infinite", bind_f64_ret_bool(f64::is_infinite));
+        set_method(&self.ctx, &class, "is_nan", bind_f64_ret_bool(f64::is_nan));
+        set_method(
+            &self.ctx,
+            &class,
+            "is_sign_negative",
+            bind_f64_ret_bool(f64::is_sign_negative),
+        );
+        set_method(
+            &self.ctx,
+            &class,
+            "is_sign_positive",
+            bind_f64_ret_bool(f64::is_sign_positive),
+        );
+        set_method(&self.ctx, &class, "is_normal", bind_f64_ret_bool(f64::is_normal));
 
-        class.set_field("MAX", Value::Float(f64::MAX));
-        class.set_field("MIN", Value::Float(f64::MIN));
-        class.set_field("MIN_POSITIVE", Value::Float(f64::MIN_POSITIVE));
-        class.set_field("EPSILON", Value::Float(f64::EPSILON));
-        class.set_field("PI", Value::Float(std::f64::consts::PI));
-        class.set_field("E", Value::Float(std::f64::consts::E));
-        class.set_field("NAN", Value::Float(std::f64::NAN));
-        class.set_field("INFINITY", Value::Float(std::f64::INFINITY));
-        class.set_field("NEG_INFINITY", Value::Float(std::f64::NEG_INFINITY));
+        set_field(&self.ctx, &class, "MAX", Value::Float(f64::MAX));
+        set_field(&self.ctx, &class, "MIN", Value::Float(f64::MIN));
+        set_field(&self.ctx, &class, "MIN_POSITIVE", Value::Float(f64::MIN_POSITIVE));
+        set_field(&self.ctx, &class, "EPSILON", Value::Float(f64::EPSILON));
+        set_field(&self.ctx, &class, "PI", Value::Float(std::f64::consts::PI));
+        set_field(&self.ctx, &class, "E", Value::Float(std::f64::consts::E));
+        set_field(&self.ctx, &class, "NAN", Value::Float(std::f64::NAN));
+        set_field(&self.ctx, &class, "INFINITY", Value::Float(std::f64::INFINITY));
+        set_field(&self.ctx, &class, "NEG_INFINITY", Value::Float(std::f64::NEG_INFINITY));
 
         self.set_value_class(ValueKind::Float, class);
     }
 }
 
-fn construct_float(_runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn construct_float<'gc>(_runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
     match args {
         [_, param, ..] => match param {
             value @ Value::Float(_) => Ok(value.clone()),
@@ -79,21 +90,21 @@ fn construct_float(_runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value,
 }
 
 fn bind_f64_ret_f64(function: impl Fn(f64) -> f64 + 'static) -> NativeFn {
-    Box::new(move |_: &mut dyn Runtime, args: &[Value]| match args {
+    native_fn(move |_, args| match args {
         [Value::Float(this), ..] => Ok(Value::Float(function(*this))),
         _ => Ok(Value::Null),
     })
 }
 
 fn bind_f64_ret_bool(function: impl Fn(f64) -> bool + 'static) -> NativeFn {
-    Box::new(move |_: &mut dyn Runtime, args: &[Value]| match args {
+    native_fn(move |_, args| match args {
         [Value::Float(this), ..] => Ok(Value::Bool(function(*this))),
         _ => Ok(Value::Null),
     })
 }
 
 fn bind_f64_f64_ret_f64(function: impl Fn(f64, f64) -> f64 + 'static) -> NativeFn {
-    Box::new(move |_: &mut dyn Runtime, args: &[Value]| match args {
+    native_fn(move |_, args| match args {
         [Value::Float(first), Value::Float(second), ..] => Ok(Value::Float(function(*first, *second))),
         _ => Ok(Value::Null),
     })
@@ -0,0 +1,97 @@
+use dice_core::{
+    error::Error,
+    protocol::{class::NEW, types::MAP},
+};
+
+use crate::{
+    api::Runtime,
+    classes::set_method,
+    module::ModuleLoader,
+    value::{Map, MapKey, NativeFn, Value, ValueKind},
+};
+
+impl<L> crate::interpreter::Interpreter<'_, '_, L>
+where
+    L: ModuleLoader,
+{
+    pub(super) fn register_map(&mut self) {
+        let class = self.derive_any_class(MAP);
+
+        set_method(&self.ctx, &class, NEW, Box::new(construct_map) as NativeFn);
+        set_method(&self.ctx, &class, "length", Box::new(length) as NativeFn);
+        set_method(&self.ctx, &class, "keys", Box::new(keys) as NativeFn);
+        set_method(&self.ctx, &class, "values", Box::new(values) as NativeFn);
+        set_method(&self.ctx, &class, "entries", Box::new(entries) as NativeFn);
+        set_method(&self.ctx, &class, "remove", Box::new(remove) as NativeFn);
+        set_method(&self.ctx, &class, "contains", Box::new(contains) as NativeFn);
+
+        self.set_value_class(ValueKind::Map, class);
+    }
+}
+
+fn construct_map<'gc>(runtime: &mut dyn Runtime<'gc>, _args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
+    Ok(Value::Map(Map::new(&runtime.context())))
+}
+
+fn length<'gc>(_runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
+    if let [Value::Map(map), ..] = args {
+        Ok(Value::Int(map.entries().len() as i64))
+    } else {
+        Ok(Value::Null)
+    }
+}
+
+fn keys<'gc>(runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
+    if let [Value::Map(map), ..] = args {
+        let ctx = runtime.context();
+        let keys = map.entries().keys().map(|key| key.to_value(&ctx)).collect::<Vec<_>>();
+
+        Ok(Value::with_vec(&ctx, keys))
+    } else {
+        Ok(Value::Null)
+    }
+}
+
+fn values<'gc>(runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
+    if let [Value::Map(map), ..] = args {
+        let values = map.entries().values().cloned().collect::<Vec<_>>();
+
+        Ok(Value::with_vec(&runtime.context(), values))
+    } else {
+        Ok(Value::Null)
+    }
+}
+
+// NOTE: Each entry is returned as a two element array of the key and value.
+fn entries<'gc>(runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
+    if let [Value::Map(map), ..] = args {
+        let ctx = runtime.context();
+        let entries = map
+            .entries()
+            .iter()
+            .map(|(key, value)| Value::with_vec(&ctx, vec![key.to_value(&ctx), value.clone()]))
+            .collect::<Vec<_>>();
+
+        Ok(Value::with_vec(&ctx, entries))
+    } else {
+        Ok(Value::Null)
+    }
+}
+
+fn remove<'gc>(runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
+    if let [Value::Map(map), key, ..] = args {
+        let removed = map.remove(&runtime.context(), &MapKey::from_value(key)?);
+
+        Ok(removed.unwrap_or(Value::Null))
+    } else {
+        Ok(Value::Null)
+    }
+}
+
+fn contains<'gc>(_runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
+    if let [Value::Map(map), key, ..] = args {
+        Ok(Value::Bool(map.contains(&MapKey::from_value(key)?)))
+    } else {
+        Ok(Value::Null)
+    }
+}
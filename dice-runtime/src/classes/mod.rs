@@ -6,24 +6,48 @@ mod class;
 mod float;
 mod function;
 mod int;
-mod module;
+mod map;
+pub mod module;
 mod string;
 mod unit;
 
-use crate::{module::ModuleLoader, Runtime};
+use crate::{
+    interpreter::Interpreter,
+    module::ModuleLoader,
+    runtime::RuntimeContext,
+    value::{Class, NativeFn, Value, ValueKind},
+};
 
-impl<L> Runtime<L>
+impl<'gc, L> Interpreter<'gc, '_, L>
 where
     L: ModuleLoader,
 {
-    pub(super) fn register_known_types(&mut self) {
+    pub(crate) fn register_known_types(&mut self) {
         self.register_array();
         self.register_bool();
         self.register_class();
         self.register_float();
         self.register_function();
         self.register_int();
+        self.register_map();
         self.register_string();
         self.register_unit();
     }
+
+    fn derive_any_class(&self, name: &str) -> Class<'gc> {
+        self.state.any_class.derive(&self.ctx, self.ctx.symbol(name))
+    }
+
+    fn set_value_class(&mut self, value_kind: ValueKind, class: Class<'gc>) {
+        self.state.value_class_mapping.insert(value_kind, class.clone());
+        self.state.globals.insert(class.name(), Value::Class(class));
+    }
+}
+
+fn set_method<'gc>(ctx: &RuntimeContext<'gc>, class: &Class<'gc>, name: &str, method: NativeFn) {
+    class.set_method(ctx, ctx.symbol(name), Value::with_native_fn(method));
+}
+
+fn set_field<'gc>(ctx: &RuntimeContext<'gc>, class: &Class<'gc>, name: &str, value: Value<'gc>) {
+    class.set_field(ctx, ctx.symbol(name), value);
 }
@@ -1,13 +1,7 @@
-use crate::module::ModuleLoader;
-use dice_core::{protocol::object::MODULE_CLASS, value::Class};
+use dice_core::protocol::object::MODULE_CLASS;
 
-impl<'gc, L> crate::Runtime<L>
-where
-    L: ModuleLoader,
-{
-    pub fn new_module_class(base: &Class) -> Class {
-        let class = base.derive(&MODULE_CLASS);
+use crate::{runtime::RuntimeContext, value::Class};
 
-        class
-    }
+pub fn new_module_class<'gc>(ctx: &RuntimeContext<'gc>, base: &Class<'gc>) -> Class<'gc> {
+    base.derive(ctx, ctx.symbol(MODULE_CLASS))
 }
@@ -1,20 +1,24 @@
-use crate::module::ModuleLoader;
 use dice_core::{
     error::Error,
     protocol::{class::NEW, operator::ADD},
-    runtime::Runtime,
-    value::{NativeFn, Value, ValueKind},
 };
 
-impl<L> crate::Runtime<L>
+use crate::{
+    api::Runtime,
+    classes::set_method,
+    module::ModuleLoader,
+    value::{NativeFn, OwnedValue, Value, ValueKind},
+};
+
+impl<L> crate::interpreter::Interpreter<'_, '_, L>
 where
     L: ModuleLoader,
 {
     pub(super) fn register_string(&mut self) {
-        let class = self.any_class.derive("String");
+        let class = self.derive_any_class("String");
 
-        class.set_method(&NEW, Box::new(construct_string) as NativeFn);
-        class.set_method(&ADD, Box::new(concat) as NativeFn);
+        set_method(&self.ctx, &class, NEW, Box::new(construct_string) as NativeFn);
+        set_method(&self.ctx, &class, ADD, Box::new(concat) as NativeFn);
 
         // TODO: Figure out what methods to expose for strings.
 
@@ -22,15 +26,17 @@ where
     }
 }
 
-fn construct_string(_runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn construct_string<'gc>(runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
     match args {
-        [_, param, ..] => Ok(Value::with_string(param.to_string())),
+        [_, param, ..] => Ok(Value::with_string(
+            OwnedValue::from_value(&runtime.context(), param).to_string(),
+        )),
         _ => Ok(Value::Null),
     }
 }
-fn concat(_runtime: &mut dyn Runtime, args: &[Value]) -> Result<Value, Error> {
+fn concat<'gc>(_runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
     match args {
-        [Value::String(this), Value::String(other), ..] => Ok(Value::with_string(format!("{}{}", &*this, &*other))),
+        [Value::String(this), Value::String(other), ..] => Ok(Value::with_string(format!("{}{}", this, other))),
         _ => Ok(Value::Null),
     }
 }
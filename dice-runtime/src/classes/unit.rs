@@ -1,12 +1,11 @@
-use crate::module::ModuleLoader;
-use dice_core::value::ValueKind;
+use crate::{module::ModuleLoader, value::ValueKind};
 
-impl<L> crate::Runtime<L>
+impl<L> crate::interpreter::Interpreter<'_, '_, L>
 where
     L: ModuleLoader,
 {
     pub(super) fn register_unit(&mut self) {
-        let class = self.any_class.derive("Unit");
+        let class = self.derive_any_class("Unit");
 
         self.set_value_class(ValueKind::Unit, class);
     }
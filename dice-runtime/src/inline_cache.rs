@@ -0,0 +1,96 @@
+use std::cell::RefCell;
+
+use crate::{
+    type_id::TypeId,
+    value::{Class, Symbol},
+};
+
+/// What a field or method instruction looked up the last time it ran.
+#[derive(Copy, Clone, Default)]
+pub(crate) struct InlineCache {
+    pub(crate) key: Option<Symbol>,
+    field: Option<CachedField>,
+    method: Option<CachedMethod>,
+}
+
+impl InlineCache {
+    /// The cached field slot, if the cache was filled for the same shape.
+    pub(crate) fn field_slot(&self, shape_id: u64) -> Option<Option<usize>> {
+        self.field
+            .filter(|field| field.shape_id == shape_id)
+            .map(|field| field.slot)
+    }
+
+    pub(crate) fn set_field_slot(&mut self, shape_id: u64, slot: Option<usize>) {
+        self.field = Some(CachedField { shape_id, slot });
+    }
+
+    /// The cached method slot, if the cache was filled for the same class with the same methods.
+    pub(crate) fn method_slot(&self, class: &Class) -> Option<Option<usize>> {
+        self.method
+            .filter(|method| method.type_id == class.instance_type_id() && method.version == class.method_version())
+            .map(|method| method.slot)
+    }
+
+    pub(crate) fn set_method_slot(&mut self, class: &Class, slot: Option<usize>) {
+        self.method = Some(CachedMethod {
+            type_id: class.instance_type_id(),
+            version: class.method_version(),
+            slot,
+        });
+    }
+}
+
+/// The slot objects of a shape store a field in, or `None` if the shape has no such field.
+// NOTE: Shapes never change once created, so this never needs to be invalidated.
+#[derive(Copy, Clone)]
+struct CachedField {
+    shape_id: u64,
+    slot: Option<usize>,
+}
+
+/// The slot a class stores a method in, or `None` if the class had no such method.
+#[derive(Copy, Clone)]
+struct CachedMethod {
+    type_id: TypeId,
+    version: u64,
+    slot: Option<usize>,
+}
+
+/// The inline caches of a single function, indexed by the cache slot the compiler gave each instruction.
+// NOTE: The caches are only allocated once an instruction fills one, so functions that are never called cost nothing.
+pub(crate) struct InlineCaches {
+    count: usize,
+    caches: RefCell<Vec<InlineCache>>,
+}
+
+impl InlineCaches {
+    pub(crate) fn new(count: usize) -> Self {
+        Self {
+            count,
+            caches: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn get(&self, slot: usize) -> InlineCache {
+        self.caches.borrow().get(slot).copied().unwrap_or_default()
+    }
+
+    pub(crate) fn set(&self, slot: usize, cache: InlineCache) {
+        let mut caches = self.caches.borrow_mut();
+
+        if caches.is_empty() {
+            caches.resize(self.count, InlineCache::default());
+        }
+
+        if let Some(current) = caches.get_mut(slot) {
+            *current = cache;
+        }
+    }
+}
+
+impl std::fmt::Debug for InlineCaches {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "InlineCaches({})", self.count)
+    }
+}
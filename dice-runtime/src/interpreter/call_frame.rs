@@ -0,0 +1,48 @@
+use gc_arena::Collect;
+
+use crate::{
+    stack::StackFrame,
+    upvalue::Upvalue,
+    value::{FnScript, Value},
+};
+
+/// A script function being executed, kept in the runtime's state so execution can be suspended between mutations.
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+pub(crate) struct CallFrame<'gc> {
+    // NOTE: Either a script function or a closure.
+    pub(crate) function: Value<'gc>,
+    #[collect(require_static)]
+    pub(crate) stack_frame: StackFrame,
+    // NOTE: The length the frame was reserved with, which tail calls may change while it runs.
+    pub(crate) length: usize,
+    pub(crate) position: u64,
+    pub(crate) last_instruction_offset: u64,
+}
+
+impl<'gc> CallFrame<'gc> {
+    pub(crate) fn new(function: Value<'gc>, stack_frame: StackFrame) -> Self {
+        Self {
+            function,
+            stack_frame,
+            length: stack_frame.length(),
+            position: 0,
+            last_instruction_offset: 0,
+        }
+    }
+
+    pub(crate) fn fn_script(&self) -> &FnScript {
+        match &self.function {
+            Value::FnClosure(closure) => closure.fn_script(),
+            Value::FnScript(fn_script) => fn_script,
+            _ => unreachable!("Only script functions are executed in call frames."),
+        }
+    }
+
+    pub(crate) fn upvalues(&self) -> Option<&[Upvalue<'gc>]> {
+        match &self.function {
+            Value::FnClosure(closure) => Some(closure.upvalues()),
+            _ => None,
+        }
+    }
+}
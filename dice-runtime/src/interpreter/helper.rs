@@ -1,23 +1,84 @@
+use std::ops::Range;
+
+use dice_bytecode::{Bytecode, ConstantValue};
 use dice_core::{
     error::{
         codes::{
-            CLASS_MUST_HAVE_NEW_IF_SUPER_HAS_NEW, GLOBAL_OPERATOR_UNDEFINED, NEW_FUNCTION_CANNOT_BE_ACCESS_DIRECTLY,
+            CLASS_MUST_HAVE_NEW_IF_SUPER_HAS_NEW, GLOBAL_OPERATOR_UNDEFINED, INDEX_OUT_OF_BOUNDS,
+            INVALID_CONSTANT_CONVERSION, INVALID_SYMBOL_CONVERSION, NEW_FUNCTION_CANNOT_BE_ACCESS_DIRECTLY,
             TYPE_ASSERTION_FUNCTION_FAILURE,
         },
         Error,
     },
-    protocol::{class::NEW, ProtocolSymbol},
+    protocol::class::NEW,
     tags,
 };
 
-use crate::{module::ModuleLoader, runtime::Runtime};
 use crate::{
+    inline_cache::InlineCache,
     upvalue::{Upvalue, UpvalueState},
-    value::{Class, FnBound, FnNative, FnScript, Object, Symbol, Value, ValueKind},
+    value::{Class, FnBound, FnNative, FnScript, Object, PreparedConstant, Symbol, Value, ValueKind},
+};
+use crate::{
+    interpreter::{CallFrame, Interpreter},
+    module::ModuleLoader,
 };
 
-impl<L: ModuleLoader> Runtime<L> {
-    pub(super) fn find_open_upvalue(&self, offset: usize) -> Option<(usize, Upvalue)> {
+impl<'gc, L: ModuleLoader> Interpreter<'gc, '_, L> {
+    /// Load a constant of the function, reusing the strings and functions prepared when the function was created.
+    pub(super) fn constant(&self, fn_script: &FnScript, index: usize) -> Result<Value<'gc>, Error> {
+        match fn_script.prepared_constant(index) {
+            Some(PreparedConstant::String(string)) => Ok(Value::String(string.clone())),
+            Some(PreparedConstant::FnScript(fn_script)) => Ok(Value::FnScript(fn_script.clone())),
+            None => self.constant_value(&fn_script.bytecode().constants()[index]),
+        }
+    }
+
+    /// Convert a constant into a value, interning symbols and wrapping function bytecode as they're loaded.
+    pub(super) fn constant_value(&self, constant: &ConstantValue) -> Result<Value<'gc>, Error> {
+        let value = match constant {
+            ConstantValue::Int(int) => Value::Int(*int),
+            ConstantValue::Float(float) => Value::Float(*float),
+            ConstantValue::String(string) => Value::String(self.ctx.intern_string(string)),
+            ConstantValue::Symbol(symbol) => Value::Symbol(self.ctx.symbol(symbol)),
+            ConstantValue::Function(function) => Value::FnScript(FnScript::new(
+                &self.ctx,
+                self.ctx.symbol(&function.name),
+                function.bytecode.clone(),
+                function.id,
+            )),
+            // NOTE: Type shapes are only read by type assertions, so they never become values.
+            ConstantValue::TypeShape(_) => return Err(Error::new(INVALID_CONSTANT_CONVERSION)),
+        };
+
+        Ok(value)
+    }
+
+    pub(super) fn symbol_constant(&self, bytecode: &Bytecode, index: usize) -> Result<Symbol, Error> {
+        match &bytecode.constants()[index] {
+            ConstantValue::Symbol(symbol) => Ok(self.ctx.symbol(symbol)),
+            _ => Err(Error::new(INVALID_SYMBOL_CONVERSION)),
+        }
+    }
+
+    /// Resolve the field name an instruction refers to, interning it only the first time the instruction runs.
+    pub(super) fn cached_symbol(
+        &self,
+        cache: &mut InlineCache,
+        bytecode: &Bytecode,
+        index: usize,
+    ) -> Result<Symbol, Error> {
+        if let Some(key) = cache.key {
+            return Ok(key);
+        }
+
+        let key = self.symbol_constant(bytecode, index)?;
+        cache.key = Some(key);
+
+        Ok(key)
+    }
+
+    pub(super) fn find_open_upvalue(&self, offset: usize) -> Option<(usize, Upvalue<'gc>)> {
         let mut found_upvalue = None;
 
         for (index, upvalue) in self.state.open_upvalues.iter().enumerate() {
@@ -31,95 +92,170 @@ impl<L: ModuleLoader> Runtime<L> {
         found_upvalue
     }
 
-    pub(super) fn call_binary_op(&mut self, operator: impl Into<Symbol>, rhs: Value) -> Result<(), Error> {
-        fn call_binary_op<L: ModuleLoader>(
-            runtime: &mut Runtime<L>,
-            operator: Symbol,
-            rhs: Value,
-        ) -> Result<(), Error> {
-            let lhs = runtime.stack.pop();
-            let method = runtime.get_field(operator, lhs.clone())?;
-
-            if method != Value::Null {
-                runtime.stack.push(method);
-                runtime.stack.push(rhs);
-                runtime.call_fn(1)?;
-            } else {
-                let value = runtime.globals.get(&operator).ok_or_else(|| {
+    pub(super) fn call_binary_op(&mut self, operator: &str, rhs: Value<'gc>) -> Result<(), Error> {
+        let lhs = self.state.stack.pop();
+        let method = self.get_field(self.ctx.symbol(operator), lhs.clone())?;
+
+        if method != Value::Null {
+            self.state.stack.push(method);
+            self.state.stack.push(rhs);
+            self.call_fn(1)?;
+        } else {
+            let value = self
+                .state
+                .globals
+                .get(&self.ctx.symbol(operator))
+                .cloned()
+                .ok_or_else(|| {
                     Error::new(GLOBAL_OPERATOR_UNDEFINED).with_tags(tags! {
                         name => operator.to_string()
                     })
                 })?;
 
-                runtime.stack.push(value.clone());
-                runtime.stack.push(lhs);
-                runtime.stack.push(rhs);
-                runtime.call_fn(2)?;
-            }
-
-            Ok(())
+            self.state.stack.push(value);
+            self.state.stack.push(lhs);
+            self.state.stack.push(rhs);
+            self.call_fn(2)?;
         }
 
-        call_binary_op(self, operator.into(), rhs)
+        Ok(())
     }
 
-    pub(super) fn get_field(&self, key: Symbol, value: Value) -> Result<Value, Error> {
-        if value.kind() == ValueKind::Object || value.kind() == ValueKind::Class || value.kind() == ValueKind::Array {
+    pub(super) fn get_field(&self, key: Symbol, value: Value<'gc>) -> Result<Value<'gc>, Error> {
+        self.get_cached_field(key, value, &mut InlineCache::default())
+    }
+
+    pub(super) fn get_cached_field(
+        &self,
+        key: Symbol,
+        value: Value<'gc>,
+        cache: &mut InlineCache,
+    ) -> Result<Value<'gc>, Error> {
+        if matches!(
+            value.kind(),
+            ValueKind::Object | ValueKind::Class | ValueKind::Array | ValueKind::Map
+        ) {
             let object = value.as_object()?;
-            let fields = object.fields();
-            if let Some(field) = fields.get(&key) {
-                return Ok(field.clone());
+            if let Some(field) = self.get_cached_object_field(object, key, cache) {
+                return Ok(field);
             }
         }
 
-        if key == NEW.get() {
-            return Err(Error::new(NEW_FUNCTION_CANNOT_BE_ACCESS_DIRECTLY));
-        }
-
         // NOTE: If the type is an object, try to resolve its class.  It it's not an object or has
         // no class, try to find it in known types.
         let class = value
             .as_object()
             .ok()
             .and_then(|object| object.class())
-            .or_else(|| self.value_class_mapping.get(&value.kind()).cloned());
+            .or_else(|| self.state.value_class_mapping.get(&value.kind()).cloned());
+
+        // NOTE: A cache filled for the class means the key was already checked, the last time the instruction ran.
+        let is_cached = matches!(&class, Some(class) if cache.method_slot(class).is_some());
+        if !is_cached && key == self.ctx.symbol(NEW) {
+            return Err(Error::new(NEW_FUNCTION_CANNOT_BE_ACCESS_DIRECTLY));
+        }
 
-        let value = self.get_method(class.as_ref(), key, &value);
+        let value = self.get_cached_method(class.as_ref(), key, &value, cache);
 
         Ok(value)
     }
 
-    pub(super) fn get_method(&self, class: Option<&Class>, key: Symbol, receiver: &Value) -> Value {
-        let value = match class {
-            Some(class) => match class.method(key) {
-                Some(method) => Value::FnBound(FnBound::new(receiver.clone(), method)),
-                None => Value::Null,
-            },
-            None => Value::Null,
+    /// Read a field of the object, using the slot cached for its shape if there is one.
+    fn get_cached_object_field(
+        &self,
+        object: &Object<'gc>,
+        key: Symbol,
+        cache: &mut InlineCache,
+    ) -> Option<Value<'gc>> {
+        let slot = match object.shape_id() {
+            Some(shape_id) => self.cached_field_slot(object, shape_id, key, cache)?,
+            None => return object.field(key),
+        };
+
+        Some(object.field_at(slot))
+    }
+
+    /// Set a field of the object, writing straight to its slot when the object already has the field.
+    pub(super) fn set_cached_field(
+        &self,
+        object: &Object<'gc>,
+        key: Symbol,
+        value: Value<'gc>,
+        cache: &mut InlineCache,
+    ) {
+        if let Some(shape_id) = object.shape_id() {
+            if let Some(slot) = self.cached_field_slot(object, shape_id, key, cache) {
+                object.set_field_at(&self.ctx, slot, value);
+
+                return;
+            }
+        }
+
+        object.set_field(&self.ctx, key, value);
+    }
+
+    fn cached_field_slot(
+        &self,
+        object: &Object<'gc>,
+        shape_id: u64,
+        key: Symbol,
+        cache: &mut InlineCache,
+    ) -> Option<usize> {
+        match cache.field_slot(shape_id) {
+            Some(slot) => slot,
+            None => {
+                let slot = object.field_slot(key);
+                cache.set_field_slot(shape_id, slot);
+
+                slot
+            }
+        }
+    }
+
+    /// Look up a method, only searching the class when the cache was filled for a different class or methods.
+    pub(super) fn get_cached_method(
+        &self,
+        class: Option<&Class<'gc>>,
+        key: Symbol,
+        receiver: &Value<'gc>,
+        cache: &mut InlineCache,
+    ) -> Value<'gc> {
+        let class = match class {
+            Some(class) => class,
+            None => return Value::Null,
         };
+        let slot = match cache.method_slot(class) {
+            Some(slot) => slot,
+            None => {
+                let slot = class.method_slot(key);
+                cache.set_method_slot(class, slot);
 
-        value
+                slot
+            }
+        };
+
+        match slot {
+            Some(slot) => Value::FnBound(FnBound::new(&self.ctx, receiver.clone(), class.method_at(slot))),
+            None => Value::Null,
+        }
     }
 
     pub(crate) fn call_fn(&mut self, arg_count: usize) -> Result<(), Error> {
-        let (function, receiver) = match self.stack.peek(arg_count) {
+        let (function, receiver) = match self.state.stack.peek(arg_count) {
             Value::FnBound(fn_bound) => (fn_bound.function(), Some(fn_bound.receiver())),
             value => (value.clone(), None),
         };
 
         let value = match &function {
-            Value::FnClosure(closure) => {
-                self.call_fn_script(arg_count, receiver, &closure.fn_script(), Some(closure.upvalues()))?
-            }
-            Value::FnScript(fn_script) => self.call_fn_script(arg_count, receiver, &fn_script, None)?,
+            Value::FnClosure(_) | Value::FnScript(_) => self.call_fn_script(arg_count, receiver, function)?,
             Value::Class(class) => {
-                self.call_class_constructor(arg_count, class, Value::Object(Object::new(class.clone())))?
+                self.call_class_constructor(arg_count, class, Value::Object(Object::new(&self.ctx, class.clone())))?
             }
             Value::FnNative(fn_native) => self.call_fn_native(arg_count, receiver, fn_native)?,
             _ => return Err(Error::new(TYPE_ASSERTION_FUNCTION_FAILURE)),
         };
 
-        self.stack.push(value);
+        self.state.stack.push(value);
 
         Ok(())
     }
@@ -127,29 +263,33 @@ impl<L: ModuleLoader> Runtime<L> {
     pub(crate) fn call_class_constructor(
         &mut self,
         arg_count: usize,
-        class: &Class,
-        mut object: Value,
-    ) -> Result<Value, Error> {
+        class: &Class<'gc>,
+        mut object: Value<'gc>,
+    ) -> Result<Value<'gc>, Error> {
         let class = class.clone();
 
-        if let Some(new) = class.method(&NEW) {
-            let bound = Value::FnBound(FnBound::new(object.clone(), new));
+        if let Some(new) = class.method(self.ctx.symbol(NEW)) {
+            let bound = Value::FnBound(FnBound::new(&self.ctx, object.clone(), new));
 
-            *self.stack.peek_mut(arg_count) = bound;
+            *self.state.stack.peek_mut(arg_count) = bound;
             self.call_fn(arg_count)?;
 
             // NOTE: Replace the returned object with the top of stack.
             // In most cases this will be the object itself, but this allows for native constructors
             // to override the result.
-            object = self.stack.peek(0).clone();
+            object = self.state.stack.peek(0).clone();
         } else if arg_count > 0 {
-            self.stack.pop_count(arg_count);
-        } else if class.base().filter(|base| base.method(&NEW).is_some()).is_some() {
+            self.state.stack.pop_count(arg_count);
+        } else if class
+            .base()
+            .filter(|base| base.method(self.ctx.symbol(NEW)).is_some())
+            .is_some()
+        {
             return Err(Error::new(CLASS_MUST_HAVE_NEW_IF_SUPER_HAS_NEW));
         }
 
         // NOTE: Regardless of whether or not there was a constructor, clean up the stack.
-        self.stack.pop();
+        self.state.stack.pop();
 
         Ok(object)
     }
@@ -157,12 +297,12 @@ impl<L: ModuleLoader> Runtime<L> {
     fn call_fn_native(
         &mut self,
         arg_count: usize,
-        receiver: Option<Value>,
+        receiver: Option<Value<'gc>>,
         fn_native: &FnNative,
-    ) -> Result<Value, Error> {
+    ) -> Result<Value<'gc>, Error> {
         let fn_native = fn_native.clone();
         // NOTE: Include the function/receiver slot as the first parameter to the native function call.
-        let mut args = self.stack.pop_count(arg_count + 1);
+        let mut args = self.state.stack.pop_count(arg_count + 1);
 
         if let Some(receiver) = receiver {
             args[0] = receiver;
@@ -174,27 +314,110 @@ impl<L: ModuleLoader> Runtime<L> {
     fn call_fn_script(
         &mut self,
         arg_count: usize,
-        receiver: Option<Value>,
-        fn_script: &FnScript,
-        parent_upvalues: Option<&[Upvalue]>,
-    ) -> Result<Value, Error> {
-        let slots = fn_script.bytecode().slot_count();
+        receiver: Option<Value<'gc>>,
+        function: Value<'gc>,
+    ) -> Result<Value<'gc>, Error> {
+        let base = self.state.frames.len();
+        self.push_call_frame(arg_count, receiver, function)?;
+
+        self.complete(base)
+    }
+
+    /// Call the function on the stack, returning whether a frame was pushed for it instead of calling it immediately.
+    pub(super) fn enter_fn(&mut self, arg_count: usize) -> Result<bool, Error> {
+        let (function, receiver) = match self.state.stack.peek(arg_count) {
+            Value::FnBound(fn_bound) => (fn_bound.function(), Some(fn_bound.receiver())),
+            value => (value.clone(), None),
+        };
+
+        match function {
+            Value::FnClosure(_) | Value::FnScript(_) => {
+                self.push_call_frame(arg_count, receiver, function)?;
+
+                Ok(true)
+            }
+            _ => self.call_fn(arg_count).map(|_| false),
+        }
+    }
+
+    fn push_call_frame(
+        &mut self,
+        arg_count: usize,
+        receiver: Option<Value<'gc>>,
+        function: Value<'gc>,
+    ) -> Result<(), Error> {
+        self.check_call_depth()?;
+
+        let slots = match &function {
+            Value::FnClosure(closure) => closure.fn_script().bytecode().slot_count(),
+            Value::FnScript(fn_script) => fn_script.bytecode().slot_count(),
+            _ => unreachable!("Frames are only pushed for script functions."),
+        };
         let reserved = if arg_count < slots { slots - arg_count } else { slots };
         // NOTE: Reserve only the slots needed to cover locals beyond the arguments already on the stack.
-        let stack_frame = self.stack.reserve_slots(reserved);
+        let stack_frame = self.state.stack.reserve_slots(reserved)?;
         // NOTE: Calling convention includes an extra parameter. This parameter is the function itself for bare functions
         // and the receiver for methods.
         let stack_frame = stack_frame.prepend(arg_count + 1);
 
         if let Some(receiver) = receiver {
-            self.stack[stack_frame][0] = receiver;
+            self.state.stack[stack_frame][0] = receiver;
         }
 
-        let result = self.execute(fn_script.bytecode(), stack_frame, parent_upvalues)?;
+        self.state.frames.push(CallFrame::new(function, stack_frame));
+
+        Ok(())
+    }
+}
 
-        // NOTE: Release the number of reserved slots plus the number of arguments plus a slot for the function itself.
-        self.stack.release_stack_frame(stack_frame);
+/// Resolve a possibly negative index against the length of a sequence, where negative indices count from the end.
+pub(super) fn resolve_index(index: i64, length: usize) -> Result<usize, Error> {
+    let resolved = if index < 0 { index + length as i64 } else { index };
 
-        Ok(result)
+    if resolved < 0 || resolved >= length as i64 {
+        return Err(Error::new(INDEX_OUT_OF_BOUNDS).with_tags(tags! {
+            index => index.to_string(),
+            length => length.to_string()
+        }));
     }
+
+    Ok(resolved as usize)
+}
+
+/// Resolve the bounds of a slice, where missing bounds default to the start and end of the sequence.
+pub(super) fn resolve_slice(
+    start: Option<i64>,
+    end: Option<i64>,
+    is_inclusive: bool,
+    length: usize,
+) -> Result<Range<usize>, Error> {
+    fn resolve_bound(bound: i64, length: usize) -> Result<usize, Error> {
+        let resolved = if bound < 0 { bound + length as i64 } else { bound };
+
+        if resolved < 0 || resolved > length as i64 {
+            return Err(Error::new(INDEX_OUT_OF_BOUNDS).with_tags(tags! {
+                index => bound.to_string(),
+                length => length.to_string()
+            }));
+        }
+
+        Ok(resolved as usize)
+    }
+
+    let start = start.map_or(Ok(0), |start| resolve_bound(start, length))?;
+    let end = match end {
+        Some(end) if is_inclusive => resolve_bound(end, length)? + 1,
+        Some(end) => resolve_bound(end, length)?,
+        None => length,
+    };
+
+    if end > length {
+        return Err(Error::new(INDEX_OUT_OF_BOUNDS).with_tags(tags! {
+            index => (end - 1).to_string(),
+            length => length.to_string()
+        }));
+    }
+
+    // NOTE: Slices where the start comes after the end are empty, rather than an error.
+    Ok(start..end.max(start))
 }
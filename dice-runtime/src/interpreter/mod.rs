@@ -1,67 +1,250 @@
 use std::collections::hash_map::Entry;
 
-use dice_bytecode::{Bytecode, BytecodeCursor, Instruction};
+use dice_bytecode::{Bytecode, BytecodeCursor, ConstantValue, Instruction, TypeShape, TypeShapeKind};
 use dice_core::{
     error::{
         codes::{
-            CLASS_CANNOT_INHERIT_VALUE_TYPE, DIVIDE_BY_ZERO, GLOBAL_VARIABLE_ALREADY_DEFINED,
-            GLOBAL_VARIABLE_UNDEFINED, TYPE_ASSERTION_BOOL_FAILURE, TYPE_ASSERTION_FAILURE,
+            CALL_DEPTH_LIMIT_EXCEEDED, CLASS_CANNOT_INHERIT_VALUE_TYPE, DIVIDE_BY_ZERO,
+            GLOBAL_VARIABLE_ALREADY_DEFINED, GLOBAL_VARIABLE_UNDEFINED, HEAP_LIMIT_EXCEEDED,
+            INSTRUCTION_LIMIT_EXCEEDED, INVALID_ARRAY_CONVERSION, INVALID_TYPE_SHAPE_CONVERSION, SCRIPT_INTERRUPTED,
+            STACK_OVERFLOW, TIME_LIMIT_EXCEEDED, TYPE_ASSERTION_BOOL_FAILURE, TYPE_ASSERTION_FAILURE,
             TYPE_ASSERTION_FUNCTION_FAILURE, TYPE_ASSERTION_NULLABILITY_FAILURE, TYPE_ASSERTION_NUMBER_FAILURE,
             TYPE_ASSERTION_SUPER_FAILURE,
         },
-        context::{Context, ContextKind, INVALID_INDEX_TYPES, MISMATCHED_TYPE_ASSERTIONS},
-        Error,
-        ResultExt, trace::ErrorTrace,
+        context::{Context, ContextKind, INVALID_INDEX_TYPES, MISMATCHED_TYPE_ASSERTIONS, TRACE_TRUNCATED},
+        Error, ResultExt,
     },
     protocol::operator::{ADD, DIV, EQ, GT, GTE, LT, LTE, MUL, NEQ, RANGE_EXCLUSIVE, RANGE_INCLUSIVE, REM, SUB},
     tags,
 };
 
-use crate::{module::ModuleLoader, runtime::Runtime, stack::StackFrame};
+use gc_arena::{Gc, Mutation};
+
+use crate::{
+    api::Runtime,
+    module::ModuleLoader,
+    runtime::{RuntimeContext, State},
+    stack::StackFrame,
+};
 use crate::{
+    inline_cache::InlineCaches,
     upvalue::{Upvalue, UpvalueState},
-    value::{Class, FnClosure, Object, Value, ValueKind},
+    value::{Array, Class, FnClosure, FnScript, Map, MapKey, Object, Symbol, Value, ValueKind},
 };
 
+pub(crate) use call_frame::CallFrame;
+
+mod call_frame;
 mod helper;
+#[cfg(feature = "register-vm")]
+mod register;
+
+use helper::{resolve_index, resolve_slice};
+
+// NOTE: Native functions and operators calling back into scripts recurse on the native stack, which is much smaller
+// than the value stack. Unoptimized builds use far more native stack per execution, so they allow fewer of them to fit
+// in the 2MB stacks threads are spawned with.
+const MAX_NESTED_EXECUTIONS: usize = if cfg!(debug_assertions) { 32 } else { 256 };
+const MAX_TRACED_CALLERS: usize = 32;
+
+/// How execution of the frame on top of the call stack stopped.
+enum Completion<'gc> {
+    Return(Value<'gc>),
+    // NOTE: A different frame is now on top of the call stack, either a callee or the target of a tail call.
+    Call,
+    Suspend,
+}
+
+/// How far execution got before handing control back.
+pub(crate) enum Execution<'gc> {
+    Complete(Value<'gc>),
+    Suspended,
+}
+
+/// Executes code against the runtime's state for the duration of a single mutation of the arena.
+pub(crate) struct Interpreter<'gc, 'a, L> {
+    pub(crate) ctx: RuntimeContext<'gc>,
+    pub(crate) state: &'a mut State<'gc, L>,
+    // NOTE: Instructions left until the next check of the limits and pending collection work, out of the batch
+    // started at the last check.
+    steps: usize,
+    batch: usize,
+    // NOTE: How many executions are running on the native stack, inside native functions, operators and constructors.
+    nesting: usize,
+}
 
-impl<L> Runtime<L>
+impl<'gc, 'a, L> Interpreter<'gc, 'a, L>
 where
     L: ModuleLoader,
 {
-    pub(super) fn execute(
-        &mut self,
-        bytecode: &Bytecode,
-        stack_frame: StackFrame,
-        parent_upvalues: Option<&[Upvalue]>,
-    ) -> Result<Value, Error> {
+    pub(crate) fn new(mutation: &'gc Mutation<'gc>, state: &'a mut State<'gc, L>) -> Self {
+        let ctx = RuntimeContext {
+            mutation,
+            interner: Gc::as_ref(state.interner),
+        };
+        let mut interpreter = Self {
+            ctx,
+            state,
+            steps: 0,
+            batch: 0,
+            nesting: 0,
+        };
+        interpreter.start_batch();
+
+        interpreter
+    }
+
+    /// Push a frame for top level code, with the given value in the first slot of its frame.
+    pub(crate) fn start(&mut self, name: Symbol, bytecode: Bytecode, receiver: Value<'gc>) -> Result<(), Error> {
+        self.check_call_depth()?;
+
+        let stack_frame = self.state.stack.reserve_slots(bytecode.slot_count())?;
+        // NOTE: Index through the frame, so top level code without any slots doesn't index past the end of the stack.
+        if let Some(slot) = self.state.stack[stack_frame].first_mut() {
+            *slot = receiver;
+        }
+
+        let function = Value::FnScript(FnScript::new(&self.ctx, name, bytecode, uuid::Uuid::new_v4()));
+        self.state.frames.push(CallFrame::new(function, stack_frame));
+
+        Ok(())
+    }
+
+    /// Run top level code to completion.
+    pub(crate) fn run(&mut self, name: Symbol, bytecode: Bytecode, receiver: Value<'gc>) -> Result<Value<'gc>, Error> {
+        let base = self.state.frames.len();
+        self.start(name, bytecode, receiver)?;
+
+        self.complete(base)
+    }
+
+    /// Run frames until the call stack is back down to the base, without suspending.
+    pub(crate) fn complete(&mut self, base: usize) -> Result<Value<'gc>, Error> {
+        if self.nesting >= MAX_NESTED_EXECUTIONS {
+            return Err(self.unwind(base, Error::new(STACK_OVERFLOW)));
+        }
+
+        self.nesting += 1;
+        let execution = self.execute(base, false);
+        self.nesting -= 1;
+
+        match execution? {
+            Execution::Complete(value) => Ok(value),
+            Execution::Suspended => unreachable!("Execution only suspends when it's allowed to."),
+        }
+    }
+
+    /// Run frames until the call stack is back down to the base.
+    /// When suspendable, execution stops whenever the arena has collection work pending, and resumes from the same
+    /// instruction the next time this is called.
+    // NOTE: Calls between script functions don't recurse, so that everything live is rooted in the state when
+    // suspended. Calls made by native functions, operators and constructors still recurse and can't be suspended.
+    pub(crate) fn execute(&mut self, base: usize, suspendable: bool) -> Result<Execution<'gc>, Error> {
+        loop {
+            let completion = match self.execute_frame(suspendable) {
+                Ok(completion) => completion,
+                Err(error) => return Err(self.unwind(base, error)),
+            };
+
+            match completion {
+                Completion::Return(value) => {
+                    let frame = self.state.frames.pop().expect("A frame was executed.");
+                    // NOTE: Restore the frame to its original size, so the right number of slots is released.
+                    let stack_frame = self.state.stack.resize_frame(frame.stack_frame, frame.length);
+                    self.state.stack.release_stack_frame(stack_frame);
+
+                    if self.state.frames.len() == base {
+                        return Ok(Execution::Complete(value));
+                    }
+
+                    self.state.stack.push(value);
+                }
+                Completion::Call => {}
+                Completion::Suspend => return Ok(Execution::Suspended),
+            }
+        }
+    }
+
+    /// Pop the frames above the base after an error, adding each caller to the error's trace.
+    /// Only the innermost and outermost calls are traced when there are many, such as after a stack overflow.
+    fn unwind(&mut self, base: usize, mut error: Error) -> Error {
+        // NOTE: The frame that failed has already added itself to the trace.
+        if let Some(frame) = self.state.frames.pop() {
+            self.release_frame(&frame);
+        }
+
+        let callers = self.state.frames.len().saturating_sub(base);
+        let omitted = callers.saturating_sub(MAX_TRACED_CALLERS);
+
+        for index in 0..callers {
+            let frame = self.state.frames.pop().expect("The frame is above the base.");
+            self.release_frame(&frame);
+
+            if index < MAX_TRACED_CALLERS / 2 || index >= omitted + MAX_TRACED_CALLERS / 2 {
+                error = error.push_trace(frame.fn_script().bytecode().trace(frame.last_instruction_offset));
+            }
+        }
+
+        if omitted > 0 {
+            error = error.push_context(Context::new(TRACE_TRUNCATED, ContextKind::Note).with_tags(tags! {
+                count => omitted.to_string()
+            }));
+        }
+
+        error
+    }
+
+    /// Release the slots of a frame popped by an error, along with anything it left on top of the stack.
+    fn release_frame(&mut self, frame: &CallFrame<'gc>) {
+        self.close_frame_upvalues(frame.stack_frame);
+        self.state.stack.truncate(frame.stack_frame.start());
+    }
+
+    fn execute_frame(&mut self, suspendable: bool) -> Result<Completion<'gc>, Error> {
+        let frame_index = self.state.frames.len() - 1;
+        let frame = self.state.frames[frame_index].clone();
+        let fn_script = frame.fn_script();
+        let bytecode = fn_script.bytecode();
+        let parent_upvalues = frame.upvalues();
+        let caches = fn_script.inline_caches();
+        let stack_frame = frame.stack_frame;
         let mut cursor = bytecode.cursor();
+        cursor.set_position(frame.position);
 
         // NOTE: Use IIFE to wrap the loop, to make building error traces easier.
         (|| {
             use Instruction::*;
 
-            #[cfg(debug_assertions)]
-            let initial_stack_depth = self.stack.len();
+            loop {
+                if self.tick()? && suspendable {
+                    self.save_position(frame_index, &cursor);
+
+                    return Ok(Completion::Suspend);
+                }
+
+                let instruction = match cursor.read_instruction() {
+                    Some(instruction) => instruction,
+                    None => break,
+                };
 
-            while let Some(instruction) = cursor.read_instruction() {
                 match instruction {
-                    PushNull => self.stack.push(Value::Null),
-                    PushUnit => self.stack.push(Value::Unit),
-                    PushFalse => self.stack.push(Value::Bool(false)),
-                    PushTrue => self.stack.push(Value::Bool(true)),
-                    PushI0 => self.stack.push(Value::Int(0)),
-                    PushI1 => self.stack.push(Value::Int(1)),
-                    PushF0 => self.stack.push(Value::Float(0.0)),
-                    PushF1 => self.stack.push(Value::Float(1.0)),
-                    PushConst => self.push_const(bytecode, &mut cursor),
-                    Pop => std::mem::drop(self.stack.pop()),
-                    Swap => self.stack.swap(),
+                    PushNull => self.state.stack.push(Value::Null),
+                    PushUnit => self.state.stack.push(Value::Unit),
+                    PushFalse => self.state.stack.push(Value::Bool(false)),
+                    PushTrue => self.state.stack.push(Value::Bool(true)),
+                    PushI0 => self.state.stack.push(Value::Int(0)),
+                    PushI1 => self.state.stack.push(Value::Int(1)),
+                    PushF0 => self.state.stack.push(Value::Float(0.0)),
+                    PushF1 => self.state.stack.push(Value::Float(1.0)),
+                    PushConst => self.push_const(fn_script, &mut cursor)?,
+                    Pop => std::mem::drop(self.state.stack.pop()),
+                    Swap => self.state.stack.swap(),
                     Dup => self.dup(&mut cursor),
                     CreateArray => self.create_list(&mut cursor),
                     CreateObject => self.create_object(),
-                    InheritClass => self.inherit_class(&bytecode, &mut cursor)?,
-                    CreateClosure => self.create_closure(bytecode, stack_frame, parent_upvalues, &mut cursor)?,
+                    CreateMap => self.create_map(),
+                    InheritClass => self.inherit_class(bytecode, &mut cursor)?,
+                    CreateClosure => self.create_closure(fn_script, stack_frame, parent_upvalues, &mut cursor)?,
                     Negate => self.neg()?,
                     Not => self.not()?,
                     Multiply => self.mul()?,
@@ -90,46 +273,146 @@ where
                     CloseUpvalue => self.close_upvalue(stack_frame, &mut cursor)?,
                     LoadGlobal => self.load_global(bytecode, &mut cursor)?,
                     StoreGlobal => self.store_global(bytecode, &mut cursor)?,
-                    LoadField => self.load_field(bytecode, &mut cursor)?,
-                    StoreField => self.store_field(bytecode, &mut cursor)?,
-                    AssignField => self.assign_field(bytecode, &mut cursor)?,
+                    LoadField => self.load_field(bytecode, caches, &mut cursor)?,
+                    StoreField => self.store_field(bytecode, caches, &mut cursor)?,
+                    AssignField => self.assign_field(bytecode, caches, &mut cursor)?,
                     LoadIndex => self.load_index()?,
                     StoreIndex => self.store_index()?,
                     AssignIndex => self.assign_index()?,
-                    LoadMethod => self.load_method(bytecode, &mut cursor)?,
+                    LoadSlice => self.load_slice(&mut cursor)?,
+                    LoadMethod => self.load_method(bytecode, caches, &mut cursor)?,
                     StoreMethod => self.store_method(bytecode, &mut cursor)?,
-                    LoadFieldToLocal => self.load_field_to_local(bytecode, stack_frame, &mut cursor)?,
-                    Call => self.call(&mut cursor)?,
+                    LoadFieldToLocal => self.load_field_to_local(bytecode, caches, stack_frame, &mut cursor)?,
+                    Call => {
+                        if self.call(frame_index, &mut cursor)? {
+                            return Ok(Completion::Call);
+                        }
+                    }
+                    TailCall => {
+                        if self.tail_call(frame_index, stack_frame, &mut cursor)? {
+                            return Ok(Completion::Call);
+                        }
+                    }
                     CallSuper => self.call_super(&mut cursor)?,
-                    LoadModule => self.load_module(&bytecode, &mut cursor)?,
+                    LoadModule => self.load_module(bytecode, &mut cursor)?,
                     AssertBool => self.assert_bool()?,
-                    AssertTypeForLocal => self.assert_type_for_local(stack_frame, &mut cursor)?,
-                    AssertTypeOrNullForLocal => self.assert_type_or_null_for_local(stack_frame, &mut cursor)?,
+                    AssertTypeForLocal => self.assert_type_for_local(bytecode, stack_frame, &mut cursor)?,
+                    AssertTypeOrNullForLocal => {
+                        self.assert_type_or_null_for_local(bytecode, stack_frame, &mut cursor)?
+                    }
                     AssertTypeAndReturn => {
-                        self.assert_type_and_return()?;
+                        self.assert_type_and_return(bytecode, &mut cursor)?;
                         break;
                     }
                     AssertTypeOrNullAndReturn => {
-                        self.assert_type_or_null_and_return()?;
+                        self.assert_type_or_null_and_return(bytecode, &mut cursor)?;
                         break;
                     }
+                    LoadLocalLoadLocal => self.load_local_load_local(stack_frame, &mut cursor)?,
+                    AddLocalConst => self.add_local_const(fn_script, stack_frame, &mut cursor)?,
+                    JumpUnlessLessThanLocal => self.jump_unless_less_than_local(fn_script, stack_frame, &mut cursor)?,
                     Return => break,
+                    Wide => unreachable!("Wide prefixes are consumed by the cursor."),
                 };
             }
 
             // NOTE: subtract 1 to compensate for the last item of the stack not yet being popped.
             #[cfg(debug_assertions)]
             assert_eq!(
-                initial_stack_depth,
-                self.stack.len() - 1,
+                stack_frame.end(),
+                self.state.stack.len() - 1,
                 "Stack was left in a bad state. Initial depth {}, final depth {}",
-                initial_stack_depth,
-                self.stack.len() - 1
+                stack_frame.end(),
+                self.state.stack.len() - 1
             );
 
-            Ok(self.stack.pop())
+            Ok(Completion::Return(self.state.stack.pop()))
         })()
-        .push_trace(|| ErrorTrace::from_bytecode(&bytecode, cursor.last_instruction_offset()))
+        .push_trace(|| bytecode.trace(cursor.last_instruction_offset()))
+    }
+
+    /// Count down to the next check of the limits and interrupts.
+    /// Returns whether execution should suspend to collect garbage.
+    #[inline]
+    fn tick(&mut self) -> Result<bool, Error> {
+        if self.steps == 0 {
+            return self.check_limits();
+        }
+
+        self.steps -= 1;
+
+        Ok(false)
+    }
+
+    fn check_limits(&mut self) -> Result<bool, Error> {
+        self.state.instructions_executed += self.batch as u64;
+
+        if self.state.interrupt.take() {
+            return Err(Error::new(SCRIPT_INTERRUPTED));
+        }
+
+        let limits = &self.state.limits;
+        let metrics = self.ctx.mutation.metrics();
+
+        if let Some(limit) = limits.instructions() {
+            if self.state.instructions_executed >= limit {
+                return Err(Error::new(INSTRUCTION_LIMIT_EXCEEDED).with_tags(tags! {
+                    limit => limit.to_string()
+                }));
+            }
+        }
+
+        if let Some(limit) = limits.time() {
+            if self.state.started_at.elapsed() > limit {
+                return Err(Error::new(TIME_LIMIT_EXCEEDED).with_tags(tags! {
+                    limit => limit.as_millis().to_string()
+                }));
+            }
+        }
+
+        if let Some(limit) = limits.heap_size() {
+            if metrics.total_allocation() > limit {
+                return Err(Error::new(HEAP_LIMIT_EXCEEDED).with_tags(tags! {
+                    limit => limit.to_string()
+                }));
+            }
+        }
+
+        self.start_batch();
+        // NOTE: Count the instruction about to run against the new batch.
+        self.steps -= 1;
+
+        Ok(metrics.allocation_debt() > 0.0)
+    }
+
+    /// Size the next batch of instructions so the instruction limit is checked exactly when it's reached.
+    fn start_batch(&mut self) {
+        let remaining = self
+            .state
+            .limits
+            .instructions()
+            .map_or(u64::MAX, |limit| limit.saturating_sub(self.state.instructions_executed));
+
+        self.batch = self.state.collection_interval.min(remaining as usize);
+        self.steps = self.batch;
+    }
+
+    pub(super) fn check_call_depth(&self) -> Result<(), Error> {
+        if let Some(limit) = self.state.limits.call_depth() {
+            if self.state.frames.len() >= limit {
+                return Err(Error::new(CALL_DEPTH_LIMIT_EXCEEDED).with_tags(tags! {
+                    limit => limit.to_string()
+                }));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn save_position(&mut self, frame_index: usize, cursor: &BytecodeCursor) {
+        let frame = &mut self.state.frames[frame_index];
+        frame.position = cursor.position();
+        frame.last_instruction_offset = cursor.last_instruction_offset();
     }
 
     fn jump(&mut self, cursor: &mut BytecodeCursor) -> Result<(), Error> {
@@ -140,86 +423,107 @@ where
     }
 
     fn dup(&mut self, cursor: &mut BytecodeCursor) {
-        let value = self.stack.peek_mut(cursor.read_u8() as usize).clone();
-        self.stack.push(value);
+        let value = self.state.stack.peek_mut(cursor.read_arg()).clone();
+        self.state.stack.push(value);
     }
 
     fn assert_bool(&mut self) -> Result<(), Error> {
-        if self.stack.peek_mut(0).kind() != ValueKind::Bool {
+        if self.state.stack.peek_mut(0).kind() != ValueKind::Bool {
             return Err(Error::new(TYPE_ASSERTION_BOOL_FAILURE));
         }
 
         Ok(())
     }
 
-    fn assert_type_for_local(&mut self, stack_frame: StackFrame, cursor: &mut BytecodeCursor) -> Result<(), Error> {
-        let class = self.stack.pop();
-        let class = class.as_class()?;
-        let value = &self.stack[stack_frame][cursor.read_u8() as usize];
+    fn assert_type_for_local(
+        &mut self,
+        bytecode: &Bytecode,
+        stack_frame: StackFrame,
+        cursor: &mut BytecodeCursor,
+    ) -> Result<(), Error> {
+        let shape = Self::read_type_shape(bytecode, cursor)?;
+        let classes = self.pop_type_classes(shape)?;
+        let value = &self.state.stack[stack_frame][cursor.read_arg()];
 
         if *value == Value::Null {
             return Err(Error::new(TYPE_ASSERTION_NULLABILITY_FAILURE));
         }
 
-        self.assert_type(&class, &value)
+        self.assert_type(shape, &classes, value)
     }
 
     fn assert_type_or_null_for_local(
         &mut self,
+        bytecode: &Bytecode,
         stack_frame: StackFrame,
         cursor: &mut BytecodeCursor,
     ) -> Result<(), Error> {
-        let class = self.stack.pop();
-        let class = class.as_class()?;
-        let value = &self.stack[stack_frame][cursor.read_u8() as usize];
+        let shape = Self::read_type_shape(bytecode, cursor)?;
+        let classes = self.pop_type_classes(shape)?;
+        let value = &self.state.stack[stack_frame][cursor.read_arg()];
 
         if *value == Value::Null {
             return Ok(());
         }
 
-        self.assert_type(&class, &value)
+        self.assert_type(shape, &classes, value)
     }
 
-    fn assert_type_and_return(&mut self) -> Result<(), Error> {
-        let class = self.stack.pop();
-        let class = class.as_class()?;
-        let value = self.stack.peek(0);
+    fn assert_type_and_return(&mut self, bytecode: &Bytecode, cursor: &mut BytecodeCursor) -> Result<(), Error> {
+        let shape = Self::read_type_shape(bytecode, cursor)?;
+        let classes = self.pop_type_classes(shape)?;
+        let value = self.state.stack.peek(0);
 
         if *value == Value::Null {
             return Err(Error::new(TYPE_ASSERTION_NULLABILITY_FAILURE));
         }
 
-        self.assert_type(&class, &value)
+        self.assert_type(shape, &classes, value)
     }
 
-    fn assert_type_or_null_and_return(&mut self) -> Result<(), Error> {
-        let class = self.stack.pop();
-        let class = class.as_class()?;
-        let value = self.stack.peek(0);
+    fn assert_type_or_null_and_return(
+        &mut self,
+        bytecode: &Bytecode,
+        cursor: &mut BytecodeCursor,
+    ) -> Result<(), Error> {
+        let shape = Self::read_type_shape(bytecode, cursor)?;
+        let classes = self.pop_type_classes(shape)?;
+        let value = self.state.stack.peek(0);
 
         if *value == Value::Null {
             return Ok(());
         }
 
-        self.assert_type(&class, &value)
+        self.assert_type(shape, &classes, value)
     }
 
-    fn assert_type(&self, class: &Class, value: &&Value) -> Result<(), Error> {
-        let actual_class = value
-            .as_object()
-            .ok()
-            .and_then(|object| object.class())
-            .or_else(|| self.value_class_mapping.get(&value.kind()).cloned());
-        let is_type = actual_class
-            .as_ref()
-            .map_or(false, |local_class| local_class.is_class(&class));
+    fn read_type_shape<'b>(bytecode: &'b Bytecode, cursor: &mut BytecodeCursor) -> Result<&'b TypeShape, Error> {
+        match &bytecode.constants()[cursor.read_arg()] {
+            ConstantValue::TypeShape(shape) => Ok(shape),
+            _ => Err(Error::new(INVALID_TYPE_SHAPE_CONVERSION)),
+        }
+    }
 
-        if is_type {
+    // NOTE: The classes referenced by a shape are pushed in pre-order, so they can be consumed front to back.
+    fn pop_type_classes(&mut self, shape: &TypeShape) -> Result<Vec<Class<'gc>>, Error> {
+        self.state
+            .stack
+            .pop_count(shape.class_count())
+            .iter()
+            .map(Value::as_class)
+            .collect()
+    }
+
+    fn assert_type(&self, shape: &TypeShape, classes: &[Class<'gc>], value: &Value<'gc>) -> Result<(), Error> {
+        if self.is_value_of_shape(shape, classes, value) {
             Ok(())
         } else {
-            let expected_type = class.name().as_string();
-            let actual_type =
-                actual_class.map_or(String::from("<unknown>"), |local_class| local_class.name().as_string());
+            let expected_type = self.shape_name(shape, classes);
+            let actual_type = self
+                .class_of_value(value)
+                .map_or(String::from("<unknown>"), |local_class| {
+                    self.ctx.resolve(local_class.name()).to_string()
+                });
 
             Err(Error::new(TYPE_ASSERTION_FAILURE).push_context(
                 Context::new(MISMATCHED_TYPE_ASSERTIONS, ContextKind::Note).with_tags(tags! {
@@ -230,8 +534,74 @@ where
         }
     }
 
+    fn is_value_of_shape(&self, shape: &TypeShape, classes: &[Class<'gc>], value: &Value<'gc>) -> bool {
+        if *value == Value::Null {
+            return shape.accepts_null();
+        }
+
+        match &shape.kind {
+            TypeShapeKind::Class => self
+                .class_of_value(value)
+                .is_some_and(|local_class| local_class.is_class(&classes[0])),
+            TypeShapeKind::Array(element) => match value {
+                Value::Array(array) => array
+                    .elements()
+                    .iter()
+                    .all(|item| self.is_value_of_shape(element, classes, item)),
+                _ => false,
+            },
+            TypeShapeKind::Function => value.kind() == ValueKind::Function,
+            TypeShapeKind::Union(members) => {
+                let mut remaining = classes;
+
+                members.iter().any(|member| {
+                    let (member_classes, rest) = remaining.split_at(member.class_count());
+                    remaining = rest;
+
+                    self.is_value_of_shape(member, member_classes, value)
+                })
+            }
+        }
+    }
+
+    pub(crate) fn class_of_value(&self, value: &Value<'gc>) -> Option<Class<'gc>> {
+        value
+            .as_object()
+            .ok()
+            .and_then(|object| object.class())
+            .or_else(|| self.state.value_class_mapping.get(&value.kind()).cloned())
+    }
+
+    fn shape_name(&self, shape: &TypeShape, classes: &[Class<'gc>]) -> String {
+        let name = match &shape.kind {
+            TypeShapeKind::Class => self.ctx.resolve(classes[0].name()).to_string(),
+            TypeShapeKind::Array(element) => format!("Array<{}>", self.shape_name(element, classes)),
+            TypeShapeKind::Function => String::from("Function"),
+            TypeShapeKind::Union(members) => {
+                let mut remaining = classes;
+
+                members
+                    .iter()
+                    .map(|member| {
+                        let (member_classes, rest) = remaining.split_at(member.class_count());
+                        remaining = rest;
+
+                        self.shape_name(member, member_classes)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            }
+        };
+
+        if shape.is_nullable {
+            format!("{}?", name)
+        } else {
+            name
+        }
+    }
+
     fn not(&mut self) -> Result<(), Error> {
-        match self.stack.peek_mut(0) {
+        match self.state.stack.peek_mut(0) {
             Value::Bool(value) => *value = !*value,
             _ => return Err(Error::new(TYPE_ASSERTION_BOOL_FAILURE)),
         }
@@ -240,7 +610,7 @@ where
     }
 
     fn neg(&mut self) -> Result<(), Error> {
-        match self.stack.peek_mut(0) {
+        match self.state.stack.peek_mut(0) {
             Value::Int(value) => *value = -*value,
             Value::Float(value) => *value = -*value,
             _ => {
@@ -252,17 +622,17 @@ where
     }
 
     fn mul(&mut self) -> Result<(), Error> {
-        match (self.stack.pop(), self.stack.peek_mut(0)) {
+        match (self.state.stack.pop(), self.state.stack.peek_mut(0)) {
             (Value::Int(rhs), Value::Int(lhs)) => *lhs *= rhs,
             (Value::Float(rhs), Value::Float(lhs)) => *lhs *= rhs,
-            (rhs, _) => self.call_binary_op(&MUL, rhs)?,
+            (rhs, _) => self.call_binary_op(MUL, rhs)?,
         }
 
         Ok(())
     }
 
     fn div(&mut self) -> Result<(), Error> {
-        match (self.stack.pop(), self.stack.peek_mut(0)) {
+        match (self.state.stack.pop(), self.state.stack.peek_mut(0)) {
             (Value::Int(rhs), Value::Int(lhs)) => {
                 if rhs == 0 {
                     return Err(Error::new(DIVIDE_BY_ZERO));
@@ -271,14 +641,14 @@ where
                 *lhs /= rhs;
             }
             (Value::Float(rhs), Value::Float(lhs)) => *lhs /= rhs,
-            (rhs, _) => self.call_binary_op(&DIV, rhs)?,
+            (rhs, _) => self.call_binary_op(DIV, rhs)?,
         }
 
         Ok(())
     }
 
     fn rem(&mut self) -> Result<(), Error> {
-        match (self.stack.pop(), self.stack.peek_mut(0)) {
+        match (self.state.stack.pop(), self.state.stack.peek_mut(0)) {
             (Value::Int(rhs), Value::Int(lhs)) => {
                 if rhs == 0 {
                     return Err(Error::new(DIVIDE_BY_ZERO));
@@ -287,151 +657,158 @@ where
                 *lhs %= rhs;
             }
             (Value::Float(rhs), Value::Float(lhs)) => *lhs %= rhs,
-            (rhs, _) => self.call_binary_op(&REM, rhs)?,
+            (rhs, _) => self.call_binary_op(REM, rhs)?,
         }
 
         Ok(())
     }
 
     fn add(&mut self) -> Result<(), Error> {
-        match (self.stack.pop(), self.stack.peek_mut(0)) {
+        match (self.state.stack.pop(), self.state.stack.peek_mut(0)) {
             (Value::Int(rhs), Value::Int(lhs)) => *lhs += rhs,
             (Value::Float(rhs), Value::Float(lhs)) => *lhs += rhs,
-            (rhs, _) => self.call_binary_op(&ADD, rhs)?,
+            (rhs, _) => self.call_binary_op(ADD, rhs)?,
         }
 
         Ok(())
     }
 
     fn gt(&mut self) -> Result<(), Error> {
-        match (self.stack.pop(), self.stack.peek_mut(0)) {
+        match (self.state.stack.pop(), self.state.stack.peek_mut(0)) {
             (Value::Bool(rhs), Value::Bool(lhs)) => *lhs &= !rhs,
-            (Value::Int(rhs), Value::Int(lhs)) => *self.stack.peek_mut(0) = Value::Bool(*lhs > rhs),
-            (Value::Float(rhs), Value::Float(lhs)) => *self.stack.peek_mut(0) = Value::Bool(*lhs > rhs),
-            (rhs, _) => self.call_binary_op(&GT, rhs)?,
+            (Value::Int(rhs), Value::Int(lhs)) => *self.state.stack.peek_mut(0) = Value::Bool(*lhs > rhs),
+            (Value::Float(rhs), Value::Float(lhs)) => *self.state.stack.peek_mut(0) = Value::Bool(*lhs > rhs),
+            (rhs, _) => self.call_binary_op(GT, rhs)?,
         }
 
         Ok(())
     }
 
     fn gte(&mut self) -> Result<(), Error> {
-        match (self.stack.pop(), self.stack.peek_mut(0)) {
+        match (self.state.stack.pop(), self.state.stack.peek_mut(0)) {
             (Value::Bool(rhs), Value::Bool(lhs)) => *lhs = *lhs >= rhs,
-            (Value::Int(rhs), Value::Int(lhs)) => *self.stack.peek_mut(0) = Value::Bool(*lhs >= rhs),
-            (Value::Float(rhs), Value::Float(lhs)) => *self.stack.peek_mut(0) = Value::Bool(*lhs >= rhs),
-            (rhs, _) => self.call_binary_op(&GTE, rhs)?,
+            (Value::Int(rhs), Value::Int(lhs)) => *self.state.stack.peek_mut(0) = Value::Bool(*lhs >= rhs),
+            (Value::Float(rhs), Value::Float(lhs)) => *self.state.stack.peek_mut(0) = Value::Bool(*lhs >= rhs),
+            (rhs, _) => self.call_binary_op(GTE, rhs)?,
         }
 
         Ok(())
     }
 
     fn lt(&mut self) -> Result<(), Error> {
-        match (self.stack.pop(), self.stack.peek_mut(0)) {
+        match (self.state.stack.pop(), self.state.stack.peek_mut(0)) {
             (Value::Bool(rhs), Value::Bool(lhs)) => *lhs = !(*lhs) & rhs,
-            (Value::Int(rhs), Value::Int(lhs)) => *self.stack.peek_mut(0) = Value::Bool(*lhs < rhs),
-            (Value::Float(rhs), Value::Float(lhs)) => *self.stack.peek_mut(0) = Value::Bool(*lhs < rhs),
-            (rhs, _) => self.call_binary_op(&LT, rhs)?,
+            (Value::Int(rhs), Value::Int(lhs)) => *self.state.stack.peek_mut(0) = Value::Bool(*lhs < rhs),
+            (Value::Float(rhs), Value::Float(lhs)) => *self.state.stack.peek_mut(0) = Value::Bool(*lhs < rhs),
+            (rhs, _) => self.call_binary_op(LT, rhs)?,
         }
 
         Ok(())
     }
 
     fn lte(&mut self) -> Result<(), Error> {
-        match (self.stack.pop(), self.stack.peek_mut(0)) {
+        match (self.state.stack.pop(), self.state.stack.peek_mut(0)) {
             (Value::Bool(rhs), Value::Bool(lhs)) => *lhs = *lhs <= rhs,
-            (Value::Int(rhs), Value::Int(lhs)) => *self.stack.peek_mut(0) = Value::Bool(*lhs <= rhs),
-            (Value::Float(rhs), Value::Float(lhs)) => *self.stack.peek_mut(0) = Value::Bool(*lhs <= rhs),
-            (rhs, _) => self.call_binary_op(&LTE, rhs)?,
+            (Value::Int(rhs), Value::Int(lhs)) => *self.state.stack.peek_mut(0) = Value::Bool(*lhs <= rhs),
+            (Value::Float(rhs), Value::Float(lhs)) => *self.state.stack.peek_mut(0) = Value::Bool(*lhs <= rhs),
+            (rhs, _) => self.call_binary_op(LTE, rhs)?,
         }
 
         Ok(())
     }
 
     fn sub(&mut self) -> Result<(), Error> {
-        match (self.stack.pop(), self.stack.peek_mut(0)) {
+        match (self.state.stack.pop(), self.state.stack.peek_mut(0)) {
             (Value::Int(rhs), Value::Int(lhs)) => *lhs -= rhs,
             (Value::Float(rhs), Value::Float(lhs)) => *lhs -= rhs,
-            (rhs, _) => self.call_binary_op(&SUB, rhs)?,
+            (rhs, _) => self.call_binary_op(SUB, rhs)?,
         }
 
         Ok(())
     }
 
     fn eq(&mut self) -> Result<(), Error> {
-        match (self.stack.pop(), self.stack.peek_mut(0)) {
-            (Value::Null, Value::Null) => *self.stack.peek_mut(0) = Value::Bool(true),
-            (Value::Null, _) => *self.stack.peek_mut(0) = Value::Bool(false),
-            (_, Value::Null) => *self.stack.peek_mut(0) = Value::Bool(false),
-            (Value::Unit, Value::Unit) => *self.stack.peek_mut(0) = Value::Bool(true),
-            (Value::Unit, _) => *self.stack.peek_mut(0) = Value::Bool(false),
-            (_, Value::Unit) => *self.stack.peek_mut(0) = Value::Bool(false),
+        match (self.state.stack.pop(), self.state.stack.peek_mut(0)) {
+            (Value::Null, Value::Null) => *self.state.stack.peek_mut(0) = Value::Bool(true),
+            (Value::Null, _) => *self.state.stack.peek_mut(0) = Value::Bool(false),
+            (_, Value::Null) => *self.state.stack.peek_mut(0) = Value::Bool(false),
+            (Value::Unit, Value::Unit) => *self.state.stack.peek_mut(0) = Value::Bool(true),
+            (Value::Unit, _) => *self.state.stack.peek_mut(0) = Value::Bool(false),
+            (_, Value::Unit) => *self.state.stack.peek_mut(0) = Value::Bool(false),
             (Value::Bool(rhs), Value::Bool(lhs)) => *lhs = *lhs == rhs,
-            (Value::Int(rhs), Value::Int(lhs)) => *self.stack.peek_mut(0) = Value::Bool(*lhs == rhs),
-            (Value::Float(rhs), Value::Float(lhs)) => *self.stack.peek_mut(0) = Value::Bool(*lhs == rhs),
-            (rhs, _) => self.call_binary_op(&EQ, rhs)?,
+            (Value::Int(rhs), Value::Int(lhs)) => *self.state.stack.peek_mut(0) = Value::Bool(*lhs == rhs),
+            (Value::Float(rhs), Value::Float(lhs)) => *self.state.stack.peek_mut(0) = Value::Bool(*lhs == rhs),
+            (Value::String(rhs), Value::String(lhs)) => *self.state.stack.peek_mut(0) = Value::Bool(*lhs == rhs),
+            (rhs, _) => self.call_binary_op(EQ, rhs)?,
         }
 
         Ok(())
     }
 
     fn neq(&mut self) -> Result<(), Error> {
-        match (self.stack.pop(), self.stack.peek_mut(0)) {
-            (Value::Null, Value::Null) => *self.stack.peek_mut(0) = Value::Bool(false),
-            (Value::Null, _) => *self.stack.peek_mut(0) = Value::Bool(true),
-            (_, Value::Null) => *self.stack.peek_mut(0) = Value::Bool(true),
-            (Value::Unit, Value::Unit) => *self.stack.peek_mut(0) = Value::Bool(false),
-            (Value::Unit, _) => *self.stack.peek_mut(0) = Value::Bool(true),
-            (_, Value::Unit) => *self.stack.peek_mut(0) = Value::Bool(true),
+        match (self.state.stack.pop(), self.state.stack.peek_mut(0)) {
+            (Value::Null, Value::Null) => *self.state.stack.peek_mut(0) = Value::Bool(false),
+            (Value::Null, _) => *self.state.stack.peek_mut(0) = Value::Bool(true),
+            (_, Value::Null) => *self.state.stack.peek_mut(0) = Value::Bool(true),
+            (Value::Unit, Value::Unit) => *self.state.stack.peek_mut(0) = Value::Bool(false),
+            (Value::Unit, _) => *self.state.stack.peek_mut(0) = Value::Bool(true),
+            (_, Value::Unit) => *self.state.stack.peek_mut(0) = Value::Bool(true),
             (Value::Bool(rhs), Value::Bool(lhs)) => *lhs = *lhs != rhs,
-            (Value::Int(rhs), Value::Int(lhs)) => *self.stack.peek_mut(0) = Value::Bool(*lhs != rhs),
-            (Value::Float(rhs), Value::Float(lhs)) => *self.stack.peek_mut(0) = Value::Bool(*lhs != rhs),
-            (rhs, _) => self.call_binary_op(&NEQ, rhs)?,
+            (Value::Int(rhs), Value::Int(lhs)) => *self.state.stack.peek_mut(0) = Value::Bool(*lhs != rhs),
+            (Value::Float(rhs), Value::Float(lhs)) => *self.state.stack.peek_mut(0) = Value::Bool(*lhs != rhs),
+            (Value::String(rhs), Value::String(lhs)) => *self.state.stack.peek_mut(0) = Value::Bool(*lhs != rhs),
+            (rhs, _) => self.call_binary_op(NEQ, rhs)?,
         }
 
         Ok(())
     }
 
     fn range_inclusive(&mut self) -> Result<(), Error> {
-        let rhs = self.stack.pop();
-        self.call_binary_op(&RANGE_INCLUSIVE, rhs)
+        let rhs = self.state.stack.pop();
+        self.call_binary_op(RANGE_INCLUSIVE, rhs)
     }
 
     fn range_exclusive(&mut self) -> Result<(), Error> {
-        let rhs = self.stack.pop();
-        self.call_binary_op(&RANGE_EXCLUSIVE, rhs)
+        let rhs = self.state.stack.pop();
+        self.call_binary_op(RANGE_EXCLUSIVE, rhs)
     }
 
     fn is(&mut self) -> Result<(), Error> {
-        let class = self.stack.pop();
+        let class = self.state.stack.pop();
         let class = class.as_class()?;
-        let instance = self.stack.peek(0);
-        let is_type = self.is_value_of_type(&instance, &class)?;
+        let instance = self.state.stack.peek(0);
+        let is_type = self.is_value_of_type(instance, &class)?;
 
-        *self.stack.peek_mut(0) = Value::Bool(is_type);
+        *self.state.stack.peek_mut(0) = Value::Bool(is_type);
 
         Ok(())
     }
 
     fn create_list(&mut self, cursor: &mut BytecodeCursor) {
-        let count = cursor.read_u8() as usize;
-        let items = self.stack.pop_count(count);
+        let count = cursor.read_arg();
+        let items = self.state.stack.pop_count(count);
 
-        self.stack.push(Value::Array(items.to_vec().into()));
+        self.state.stack.push(Value::Array(Array::from_vec(&self.ctx, items)));
     }
 
     fn create_object(&mut self) {
-        let object = Object::new(self.any_class.clone());
+        let object = Object::new(&self.ctx, self.state.any_class.clone());
+
+        self.state.stack.push(Value::Object(object));
+    }
 
-        self.stack.push(Value::Object(object));
+    fn create_map(&mut self) {
+        self.state.stack.push(Value::Map(Map::new(&self.ctx)));
     }
 
     fn inherit_class(&mut self, bytecode: &Bytecode, cursor: &mut BytecodeCursor) -> Result<(), Error> {
-        let name_slot = cursor.read_u8() as usize;
-        let name = bytecode.constants()[name_slot].as_symbol()?;
-        let base = self.stack.pop().as_class()?;
+        let name_slot = cursor.read_arg();
+        let name = self.symbol_constant(bytecode, name_slot)?;
+        let base = self.state.stack.pop().as_class()?;
 
-        if base != self.any_class
+        if base != self.state.any_class
             && self
+                .state
                 .value_class_mapping
                 .iter()
                 .find(|(_, value_class)| base == **value_class)
@@ -440,22 +817,24 @@ where
             return Err(Error::new(CLASS_CANNOT_INHERIT_VALUE_TYPE));
         }
 
-        let class = Class::with_base(name, base);
+        let class = Class::with_base(&self.ctx, name, base);
 
-        self.stack.push(Value::Class(class));
+        self.state.stack.push(Value::Class(class));
 
         Ok(())
     }
 
-    fn push_const(&mut self, bytecode: &Bytecode, cursor: &mut BytecodeCursor) {
-        let const_pos = cursor.read_u8() as usize;
-        let value = bytecode.constants()[const_pos].clone();
-        self.stack.push(value);
+    fn push_const(&mut self, fn_script: &FnScript, cursor: &mut BytecodeCursor) -> Result<(), Error> {
+        let const_pos = cursor.read_arg();
+        let value = self.constant(fn_script, const_pos)?;
+        self.state.stack.push(value);
+
+        Ok(())
     }
 
     fn jump_if_false(&mut self, cursor: &mut BytecodeCursor) -> Result<(), Error> {
         let offset = cursor.read_offset();
-        let value = self.stack.pop().as_bool()?;
+        let value = self.state.stack.pop().as_bool()?;
 
         if !value {
             cursor.offset_position(offset)
@@ -466,7 +845,7 @@ where
 
     fn jump_if_true(&mut self, cursor: &mut BytecodeCursor) -> Result<(), Error> {
         let offset = cursor.read_offset();
-        let value = self.stack.pop().as_bool()?;
+        let value = self.state.stack.pop().as_bool()?;
 
         if value {
             cursor.offset_position(offset)
@@ -476,44 +855,48 @@ where
     }
 
     fn load_local(&mut self, stack_frame: StackFrame, cursor: &mut BytecodeCursor) -> Result<(), Error> {
-        let slot = cursor.read_u8() as usize;
-        let frame = &self.stack[stack_frame];
+        let slot = cursor.read_arg();
+        let frame = &self.state.stack[stack_frame];
         let value = frame[slot].clone();
-        self.stack.push(value);
+        self.state.stack.push(value);
 
         Ok(())
     }
 
     fn store_local(&mut self, stack_frame: StackFrame, cursor: &mut BytecodeCursor) -> Result<(), Error> {
-        let value = self.stack.pop();
-        let slot = cursor.read_u8() as usize;
+        let value = self.state.stack.pop();
+        let slot = cursor.read_arg();
 
-        self.stack[stack_frame][slot] = value.clone();
-        self.stack.push(value);
+        self.state.stack[stack_frame][slot] = value.clone();
+        self.state.stack.push(value);
 
         Ok(())
     }
 
     fn assign_local(&mut self, stack_frame: StackFrame, cursor: &mut BytecodeCursor) -> Result<(), Error> {
-        let value = self.stack.pop();
-        let slot = cursor.read_u8() as usize;
+        let value = self.state.stack.pop();
+        let slot = cursor.read_arg();
 
-        self.stack[stack_frame][slot] = value;
-        self.stack.push(Value::Unit);
+        self.state.stack[stack_frame][slot] = value;
+        self.state.stack.push(Value::Unit);
 
         Ok(())
     }
 
-    fn load_upvalue(&mut self, parent_upvalues: Option<&[Upvalue]>, cursor: &mut BytecodeCursor) -> Result<(), Error> {
+    fn load_upvalue(
+        &mut self,
+        parent_upvalues: Option<&[Upvalue<'gc>]>,
+        cursor: &mut BytecodeCursor,
+    ) -> Result<(), Error> {
         if let Some(parent_upvalues) = parent_upvalues {
-            let upvalue_slot = cursor.read_u8() as usize;
+            let upvalue_slot = cursor.read_arg();
             let upvalue = parent_upvalues[upvalue_slot].clone();
-            let value = match &*upvalue.state_mut() {
-                UpvalueState::Open(slot) => self.stack[*slot].clone(),
+            let value = match &*upvalue.state_mut(&self.ctx) {
+                UpvalueState::Open(slot) => self.state.stack[*slot].clone(),
                 UpvalueState::Closed(value) => value.clone(),
             };
 
-            self.stack.push(value);
+            self.state.stack.push(value);
 
             Ok(())
         } else {
@@ -521,14 +904,18 @@ where
         }
     }
 
-    fn store_upvalue(&mut self, parent_upvalues: Option<&[Upvalue]>, cursor: &mut BytecodeCursor) -> Result<(), Error> {
+    fn store_upvalue(
+        &mut self,
+        parent_upvalues: Option<&[Upvalue<'gc>]>,
+        cursor: &mut BytecodeCursor,
+    ) -> Result<(), Error> {
         if let Some(parent_upvalues) = parent_upvalues {
-            let upvalue_slot = cursor.read_u8() as usize;
+            let upvalue_slot = cursor.read_arg();
             let upvalue = parent_upvalues[upvalue_slot].clone();
-            let value = self.stack.pop();
-            let result = match &mut *upvalue.state_mut() {
+            let value = self.state.stack.pop();
+            let result = match &mut *upvalue.state_mut(&self.ctx) {
                 UpvalueState::Open(slot) => {
-                    self.stack[*slot] = value.clone();
+                    self.state.stack[*slot] = value.clone();
                     value
                 }
                 UpvalueState::Closed(closed_value) => {
@@ -537,7 +924,7 @@ where
                 }
             };
 
-            self.stack.push(result);
+            self.state.stack.push(result);
 
             Ok(())
         } else {
@@ -547,19 +934,19 @@ where
 
     fn assign_upvalue(
         &mut self,
-        parent_upvalues: Option<&[Upvalue]>,
+        parent_upvalues: Option<&[Upvalue<'gc>]>,
         cursor: &mut BytecodeCursor,
     ) -> Result<(), Error> {
         if let Some(parent_upvalues) = parent_upvalues {
-            let upvalue_slot = cursor.read_u8() as usize;
+            let upvalue_slot = cursor.read_arg();
             let upvalue = parent_upvalues[upvalue_slot].clone();
-            let value = self.stack.pop();
-            match &mut *upvalue.state_mut() {
-                UpvalueState::Open(slot) => self.stack[*slot] = value,
+            let value = self.state.stack.pop();
+            match &mut *upvalue.state_mut(&self.ctx) {
+                UpvalueState::Open(slot) => self.state.stack[*slot] = value,
                 UpvalueState::Closed(closed_value) => *closed_value = value,
             };
 
-            self.stack.push(Value::Unit);
+            self.state.stack.push(Value::Unit);
 
             Ok(())
         } else {
@@ -568,14 +955,14 @@ where
     }
 
     fn close_upvalue(&mut self, stack_frame: StackFrame, cursor: &mut BytecodeCursor) -> Result<(), Error> {
-        let offset = cursor.read_u8() as usize;
-        let value = std::mem::replace(&mut self.stack[stack_frame][offset], Value::Null);
+        let offset = cursor.read_arg();
+        let value = std::mem::replace(&mut self.state.stack[stack_frame][offset], Value::Null);
         let offset = stack_frame.start() + offset;
         let found_upvalue = self.find_open_upvalue(offset);
 
         if let Some((index, _)) = found_upvalue {
-            if let Some(upvalue) = self.open_upvalues.remove(index) {
-                upvalue.close(value);
+            if let Some(upvalue) = self.state.open_upvalues.remove(index) {
+                upvalue.close(&self.ctx, value);
             }
         }
 
@@ -583,15 +970,14 @@ where
     }
 
     fn store_global(&mut self, bytecode: &Bytecode, cursor: &mut BytecodeCursor) -> Result<(), Error> {
-        let const_pos = cursor.read_u8() as usize;
-        let value = &bytecode.constants()[const_pos];
-        let global_name = value.as_symbol()?;
-        let global = self.stack.pop();
+        let const_pos = cursor.read_arg();
+        let global_name = self.symbol_constant(bytecode, const_pos)?;
+        let global = self.state.stack.pop();
 
-        match self.globals.entry(global_name.clone()) {
+        match self.state.globals.entry(global_name) {
             Entry::Occupied(_) => {
                 return Err(Error::new(GLOBAL_VARIABLE_ALREADY_DEFINED).with_tags(tags! {
-                    name => global_name.to_string()
+                    name => self.ctx.resolve(global_name).to_string()
                 }))
             }
             Entry::Vacant(entry) => {
@@ -603,64 +989,98 @@ where
     }
 
     fn load_global(&mut self, bytecode: &Bytecode, cursor: &mut BytecodeCursor) -> Result<(), Error> {
-        let const_pos = cursor.read_u8() as usize;
-        let global = bytecode.constants()[const_pos].as_symbol()?;
-        let value = self.globals.get(&global).cloned().ok_or_else(|| {
+        let const_pos = cursor.read_arg();
+        let global = self.symbol_constant(bytecode, const_pos)?;
+        let value = self.state.globals.get(&global).cloned().ok_or_else(|| {
             Error::new(GLOBAL_VARIABLE_UNDEFINED).with_tags(tags! {
-                name => global.to_string()
+                name => self.ctx.resolve(global).to_string()
             })
         })?;
 
-        self.stack.push(value);
+        self.state.stack.push(value);
 
         Ok(())
     }
 
-    fn load_field(&mut self, bytecode: &Bytecode, cursor: &mut BytecodeCursor) -> Result<(), Error> {
-        let key_index = cursor.read_u8() as usize;
-        let key = bytecode.constants()[key_index].as_symbol()?;
+    fn load_field(
+        &mut self,
+        bytecode: &Bytecode,
+        caches: &InlineCaches,
+        cursor: &mut BytecodeCursor,
+    ) -> Result<(), Error> {
+        let key_index = cursor.read_arg();
+        let cache_slot = cursor.read_arg();
+        let mut cache = caches.get(cache_slot);
+        let key = self.cached_symbol(&mut cache, bytecode, key_index)?;
 
-        let value = self.stack.pop();
-        let value = self.get_field(key, value)?;
+        let value = self.state.stack.pop();
+        let value = self.get_cached_field(key, value, &mut cache)?;
+        caches.set(cache_slot, cache);
 
-        self.stack.push(value);
+        self.state.stack.push(value);
 
         Ok(())
     }
 
-    fn store_field(&mut self, bytecode: &Bytecode, cursor: &mut BytecodeCursor) -> Result<(), Error> {
-        let key_index = cursor.read_u8() as usize;
-        let key = bytecode.constants()[key_index].as_symbol()?;
-        let value = self.stack.pop();
-        let object = self.stack.pop();
+    fn store_field(
+        &mut self,
+        bytecode: &Bytecode,
+        caches: &InlineCaches,
+        cursor: &mut BytecodeCursor,
+    ) -> Result<(), Error> {
+        let key_index = cursor.read_arg();
+        let cache_slot = cursor.read_arg();
+        let mut cache = caches.get(cache_slot);
+        let key = self.cached_symbol(&mut cache, bytecode, key_index)?;
+        let value = self.state.stack.pop();
+        let object = self.state.stack.pop();
         let object = object.as_object()?;
 
-        object.set_field(key, value.clone());
-        self.stack.push(value);
+        self.set_cached_field(object, key, value.clone(), &mut cache);
+        caches.set(cache_slot, cache);
+        self.state.stack.push(value);
 
         Ok(())
     }
 
-    fn assign_field(&mut self, bytecode: &Bytecode, cursor: &mut BytecodeCursor) -> Result<(), Error> {
-        let key_index = cursor.read_u8() as usize;
-        let key = bytecode.constants()[key_index].as_symbol()?;
-        let value = self.stack.pop();
-        let object = self.stack.pop();
+    fn assign_field(
+        &mut self,
+        bytecode: &Bytecode,
+        caches: &InlineCaches,
+        cursor: &mut BytecodeCursor,
+    ) -> Result<(), Error> {
+        let key_index = cursor.read_arg();
+        let cache_slot = cursor.read_arg();
+        let mut cache = caches.get(cache_slot);
+        let key = self.cached_symbol(&mut cache, bytecode, key_index)?;
+        let value = self.state.stack.pop();
+        let object = self.state.stack.pop();
         let object = object.as_object()?;
 
-        object.set_field(key, value);
-        self.stack.push(Value::Unit);
+        self.set_cached_field(object, key, value, &mut cache);
+        caches.set(cache_slot, cache);
+        self.state.stack.push(Value::Unit);
 
         Ok(())
     }
 
     fn load_index(&mut self) -> Result<(), Error> {
-        let index = self.stack.pop();
-        let target = self.stack.peek(0);
+        let index = self.state.stack.pop();
+        let target = self.state.stack.peek(0);
         let result = match target {
             Value::Array(array) if index.kind() == ValueKind::Int => {
-                let index = index.as_int()?;
-                array.elements().get(index as usize).cloned().unwrap_or(Value::Null)
+                let elements = array.elements();
+                let index = resolve_index(index.as_int()?, elements.len())?;
+
+                elements[index].clone()
+            }
+            Value::Map(map) => map.get(&MapKey::from_value(&index)?).unwrap_or(Value::Null),
+            // NOTE: Strings are indexed by character, rather than by byte, to keep indexing UTF-8 safe.
+            Value::String(string) if index.kind() == ValueKind::Int => {
+                let index = resolve_index(index.as_int()?, string.chars().count())?;
+                let character = string.chars().nth(index).expect("Index should already be in bounds.");
+
+                Value::with_string(character.to_string())
             }
             target => {
                 let field = index
@@ -670,20 +1090,26 @@ where
             }
         };
 
-        *self.stack.peek_mut(0) = result;
+        *self.state.stack.peek_mut(0) = result;
 
         Ok(())
     }
 
     fn store_index(&mut self) -> Result<(), Error> {
-        let value = self.stack.pop();
-        let index = self.stack.pop();
-        let target = self.stack.peek_mut(0);
+        let value = self.state.stack.pop();
+        let index = self.state.stack.pop();
+        let target = self.state.stack.peek_mut(0);
 
         match target {
             Value::Array(array) if index.kind() == ValueKind::Int => {
-                let index = index.as_int()?;
-                array.elements_mut()[index as usize] = value.clone();
+                let mut elements = array.elements_mut(&self.ctx);
+                let index = resolve_index(index.as_int()?, elements.len())?;
+                elements[index] = value.clone();
+                drop(elements);
+                *target = value;
+            }
+            Value::Map(map) => {
+                map.insert(&self.ctx, MapKey::from_value(&index)?, value.clone());
                 *target = value;
             }
             target => {
@@ -691,7 +1117,7 @@ where
                 let field = index
                     .as_symbol()
                     .push_context(|| Context::new(INVALID_INDEX_TYPES, ContextKind::Note))?;
-                object.set_field(field, value.clone());
+                object.set_field(&self.ctx, field, value.clone());
                 *target = value;
             }
         };
@@ -700,14 +1126,20 @@ where
     }
 
     fn assign_index(&mut self) -> Result<(), Error> {
-        let value = self.stack.pop();
-        let index = self.stack.pop();
-        let target = self.stack.peek_mut(0);
+        let value = self.state.stack.pop();
+        let index = self.state.stack.pop();
+        let target = self.state.stack.peek_mut(0);
 
         match target {
             Value::Array(array) if index.kind() == ValueKind::Int => {
-                let index = index.as_int()?;
-                array.elements_mut()[index as usize] = value;
+                let mut elements = array.elements_mut(&self.ctx);
+                let index = resolve_index(index.as_int()?, elements.len())?;
+                elements[index] = value;
+                drop(elements);
+                *target = Value::Unit;
+            }
+            Value::Map(map) => {
+                map.insert(&self.ctx, MapKey::from_value(&index)?, value);
                 *target = Value::Unit;
             }
             target => {
@@ -715,7 +1147,7 @@ where
                 let field = index
                     .as_symbol()
                     .push_context(|| Context::new(INVALID_INDEX_TYPES, ContextKind::Note))?;
-                object.set_field(field, value);
+                object.set_field(&self.ctx, field, value);
                 *target = Value::Unit;
             }
         };
@@ -723,30 +1155,77 @@ where
         Ok(())
     }
 
-    fn load_method(&mut self, bytecode: &Bytecode, cursor: &mut BytecodeCursor) -> Result<(), Error> {
-        let key_index = cursor.read_u8() as usize;
-        let key = bytecode.constants()[key_index].as_symbol()?;
-        let receiver = self.stack.pop();
-        let class = self.stack.pop().as_class()?;
+    fn load_slice(&mut self, cursor: &mut BytecodeCursor) -> Result<(), Error> {
+        let is_inclusive = cursor.read_u8() != 0;
+        let end = self.state.stack.pop();
+        let start = self.state.stack.pop();
+        let start = match start {
+            Value::Null => None,
+            start => Some(start.as_int()?),
+        };
+        let end = match end {
+            Value::Null => None,
+            end => Some(end.as_int()?),
+        };
+        let target = self.state.stack.peek(0);
+
+        let result = match target {
+            Value::Array(array) => {
+                let elements = array.elements();
+                let range = resolve_slice(start, end, is_inclusive, elements.len())?;
+
+                Value::Array(Array::from_vec(&self.ctx, elements[range].to_vec()))
+            }
+            Value::String(string) => {
+                let range = resolve_slice(start, end, is_inclusive, string.chars().count())?;
+                let slice = string
+                    .chars()
+                    .skip(range.start)
+                    .take(range.end - range.start)
+                    .collect::<std::string::String>();
+
+                Value::with_string(slice)
+            }
+            _ => return Err(Error::new(INVALID_ARRAY_CONVERSION)),
+        };
+
+        *self.state.stack.peek_mut(0) = result;
+
+        Ok(())
+    }
+
+    fn load_method(
+        &mut self,
+        bytecode: &Bytecode,
+        caches: &InlineCaches,
+        cursor: &mut BytecodeCursor,
+    ) -> Result<(), Error> {
+        let key_index = cursor.read_arg();
+        let cache_slot = cursor.read_arg();
+        let mut cache = caches.get(cache_slot);
+        let key = self.cached_symbol(&mut cache, bytecode, key_index)?;
+        let receiver = self.state.stack.pop();
+        let class = self.state.stack.pop().as_class()?;
 
         if !self.is_value_of_type(&receiver, &class)? {
             return Err(Error::new(TYPE_ASSERTION_SUPER_FAILURE));
         }
 
-        let method = self.get_method(Some(&class), key, &receiver);
-        self.stack.push(method);
+        let method = self.get_cached_method(Some(&class), key, &receiver, &mut cache);
+        caches.set(cache_slot, cache);
+        self.state.stack.push(method);
 
         Ok(())
     }
 
     fn store_method(&mut self, bytecode: &Bytecode, cursor: &mut BytecodeCursor) -> Result<(), Error> {
-        let key_index = cursor.read_u8() as usize;
-        let key = bytecode.constants()[key_index].as_symbol()?;
-        let value = self.stack.pop();
-        let object = self.stack.pop();
+        let key_index = cursor.read_arg();
+        let key = self.symbol_constant(bytecode, key_index)?;
+        let value = self.state.stack.pop();
+        let object = self.state.stack.pop();
         let class = object.as_class()?;
 
-        class.set_method(key, value);
+        class.set_method(&self.ctx, key, value);
 
         Ok(())
     }
@@ -754,45 +1233,125 @@ where
     fn load_field_to_local(
         &mut self,
         bytecode: &Bytecode,
+        caches: &InlineCaches,
         stack_frame: StackFrame,
         cursor: &mut BytecodeCursor,
     ) -> Result<(), Error> {
-        let key_index = cursor.read_u8() as usize;
-        let local_slot = cursor.read_u8() as usize;
-        let key = bytecode.constants()[key_index].as_symbol()?;
-        let value = self.stack.pop();
-        let value = self.get_field(key, value)?;
+        let key_index = cursor.read_arg();
+        let local_slot = cursor.read_arg();
+        let cache_slot = cursor.read_arg();
+        let mut cache = caches.get(cache_slot);
+        let key = self.cached_symbol(&mut cache, bytecode, key_index)?;
+        let value = self.state.stack.pop();
+        let value = self.get_cached_field(key, value, &mut cache)?;
+        caches.set(cache_slot, cache);
+
+        self.state.stack[stack_frame][local_slot] = value.clone();
+        self.state.stack.push(value);
 
-        self.stack[stack_frame][local_slot] = value.clone();
-        self.stack.push(value);
+        Ok(())
+    }
+
+    fn load_local_load_local(&mut self, stack_frame: StackFrame, cursor: &mut BytecodeCursor) -> Result<(), Error> {
+        let first_slot = cursor.read_arg();
+        let second_slot = cursor.read_arg();
+        let frame = &self.state.stack[stack_frame];
+        let first = frame[first_slot].clone();
+        let second = frame[second_slot].clone();
+
+        self.state.stack.push(first);
+        self.state.stack.push(second);
+
+        Ok(())
+    }
+
+    fn add_local_const(
+        &mut self,
+        fn_script: &FnScript,
+        stack_frame: StackFrame,
+        cursor: &mut BytecodeCursor,
+    ) -> Result<(), Error> {
+        let slot = cursor.read_arg();
+        let const_pos = cursor.read_arg();
+
+        // NOTE: Numbers are added directly, only values that can overload the operator go through the stack.
+        let sum = match (
+            &self.state.stack[stack_frame][slot],
+            &fn_script.bytecode().constants()[const_pos],
+        ) {
+            (Value::Int(lhs), ConstantValue::Int(rhs)) => Value::Int(lhs + rhs),
+            (Value::Float(lhs), ConstantValue::Float(rhs)) => Value::Float(lhs + rhs),
+            (value, _) => {
+                let value = value.clone();
+                self.state.stack.push(value);
+                self.state.stack.push(self.constant(fn_script, const_pos)?);
+
+                return self.add();
+            }
+        };
+
+        self.state.stack.push(sum);
+
+        Ok(())
+    }
+
+    fn jump_unless_less_than_local(
+        &mut self,
+        fn_script: &FnScript,
+        stack_frame: StackFrame,
+        cursor: &mut BytecodeCursor,
+    ) -> Result<(), Error> {
+        let slot = cursor.read_arg();
+        let const_pos = cursor.read_arg();
+        let offset = cursor.read_offset();
+
+        let is_less_than = match (
+            &self.state.stack[stack_frame][slot],
+            &fn_script.bytecode().constants()[const_pos],
+        ) {
+            (Value::Int(lhs), ConstantValue::Int(rhs)) => lhs < rhs,
+            (Value::Float(lhs), ConstantValue::Float(rhs)) => lhs < rhs,
+            (value, _) => {
+                let value = value.clone();
+                self.state.stack.push(value);
+                self.state.stack.push(self.constant(fn_script, const_pos)?);
+                self.lt()?;
+
+                self.state.stack.pop().as_bool()?
+            }
+        };
+
+        if !is_less_than {
+            cursor.offset_position(offset);
+        }
 
         Ok(())
     }
 
     fn create_closure(
         &mut self,
-        bytecode: &Bytecode,
+        fn_script: &FnScript,
         stack_frame: StackFrame,
-        parent_upvalues: Option<&[Upvalue]>,
+        parent_upvalues: Option<&[Upvalue<'gc>]>,
         cursor: &mut BytecodeCursor,
     ) -> Result<(), Error> {
-        let const_pos = cursor.read_u8() as usize;
+        let const_pos = cursor.read_arg();
 
-        match bytecode.constants()[const_pos] {
-            Value::FnScript(ref fn_script) => {
+        match self.constant(fn_script, const_pos)? {
+            Value::FnScript(fn_script) => {
                 let upvalue_count = fn_script.bytecode().upvalue_count();
                 let mut upvalues = Vec::with_capacity(upvalue_count);
 
                 for _ in 0..upvalue_count {
                     let is_parent_local = cursor.read_u8() == 1;
-                    let index = cursor.read_u8() as usize;
+                    let index = cursor.read_arg();
 
                     if is_parent_local {
                         let offset = stack_frame.start() + index;
                         match self.find_open_upvalue(offset) {
                             None => {
-                                let upvalue = Upvalue::new_open(stack_frame.start() + index);
-                                self.open_upvalues.push_back(upvalue.clone());
+                                let upvalue = Upvalue::new_open(&self.ctx, stack_frame.start() + index);
+                                self.state.open_upvalues.push_back(upvalue.clone());
                                 upvalues.push(upvalue);
                             }
                             Some((_, upvalue)) => upvalues.push(upvalue),
@@ -806,8 +1365,8 @@ where
                     }
                 }
 
-                let closure = Value::FnClosure(FnClosure::new(fn_script.clone(), upvalues.into_boxed_slice()));
-                self.stack.push(closure);
+                let closure = Value::FnClosure(FnClosure::new(&self.ctx, fn_script, upvalues.into_boxed_slice()));
+                self.state.stack.push(closure);
             }
             _ => return Err(Error::new(TYPE_ASSERTION_FUNCTION_FAILURE)),
         }
@@ -815,37 +1374,111 @@ where
         Ok(())
     }
 
-    pub fn call(&mut self, cursor: &mut BytecodeCursor) -> Result<(), Error> {
-        let arg_count = cursor.read_u8() as usize;
-        self.call_fn(arg_count)
+    /// Call the function on the stack, returning whether a frame was pushed for it.
+    fn call(&mut self, frame_index: usize, cursor: &mut BytecodeCursor) -> Result<bool, Error> {
+        let arg_count = cursor.read_arg();
+        self.save_position(frame_index, cursor);
+
+        self.enter_fn(arg_count)
+    }
+
+    /// Move the function and arguments of a tail call into the current frame, returning whether it's now run in it.
+    /// Anything that isn't a script function, or a call with values left above the frame, is called normally.
+    fn tail_call(
+        &mut self,
+        frame_index: usize,
+        stack_frame: StackFrame,
+        cursor: &mut BytecodeCursor,
+    ) -> Result<bool, Error> {
+        let arg_count = cursor.read_arg();
+        self.save_position(frame_index, cursor);
+
+        let (function, receiver) = match self.state.stack.peek(arg_count) {
+            Value::FnBound(fn_bound) => (fn_bound.function(), Some(fn_bound.receiver())),
+            value => (value.clone(), None),
+        };
+        let slots = match &function {
+            Value::FnClosure(closure) => closure.fn_script().bytecode().slot_count(),
+            Value::FnScript(fn_script) => fn_script.bytecode().slot_count(),
+            _ => return self.enter_fn(arg_count),
+        };
+
+        if self.state.stack.stack_ptr() != stack_frame.end() + arg_count + 1 {
+            return self.enter_fn(arg_count);
+        }
+
+        // NOTE: Captured locals must be closed before their slots are overwritten by the callee.
+        self.close_frame_upvalues(stack_frame);
+
+        let mut args = self.state.stack.pop_count(arg_count + 1);
+
+        if let Some(receiver) = receiver {
+            args[0] = receiver;
+        }
+
+        // NOTE: Size the frame the same as a regular call would, then replace its contents with the arguments.
+        let reserved = if arg_count < slots { slots - arg_count } else { slots };
+        let stack_frame = self.state.stack.resize_frame(stack_frame, arg_count + 1 + reserved);
+        let values = args.into_iter().chain(std::iter::repeat(Value::Null));
+
+        for (slot, value) in self.state.stack[stack_frame].iter_mut().zip(values) {
+            *slot = value;
+        }
+
+        let frame = &mut self.state.frames[frame_index];
+        frame.function = function;
+        frame.stack_frame = stack_frame;
+        frame.position = 0;
+
+        Ok(true)
+    }
+
+    fn close_frame_upvalues(&mut self, stack_frame: StackFrame) {
+        let mut index = 0;
+
+        while index < self.state.open_upvalues.len() {
+            let offset = match *self.state.open_upvalues[index].state() {
+                UpvalueState::Open(offset) if stack_frame.range().contains(&offset) => Some(offset),
+                _ => None,
+            };
+
+            match offset {
+                Some(offset) => {
+                    if let Some(upvalue) = self.state.open_upvalues.remove(index) {
+                        upvalue.close(&self.ctx, self.state.stack[offset].clone());
+                    }
+                }
+                None => index += 1,
+            }
+        }
     }
 
     pub fn call_super(&mut self, cursor: &mut BytecodeCursor) -> Result<(), Error> {
-        let arg_count = cursor.read_u8() as usize;
-        let super_ = self.stack.pop().as_class()?;
-        let receiver = self.stack.peek(arg_count).clone();
+        let arg_count = cursor.read_arg();
+        let super_ = self.state.stack.pop().as_class()?;
+        let receiver = self.state.stack.peek(arg_count).clone();
         let result = self.call_class_constructor(arg_count, &super_, receiver)?;
 
-        self.stack.push(result);
+        self.state.stack.push(result);
 
         Ok(())
     }
 
     fn load_module(&mut self, bytecode: &Bytecode, cursor: &mut BytecodeCursor) -> Result<(), Error> {
-        let module_slot = cursor.read_u8() as usize;
-        let module_name = bytecode.constants()[module_slot].as_symbol()?;
-        let module = match self.loaded_modules.entry(module_name.clone()) {
-            Entry::Occupied(entry) => entry.get().clone(),
-            Entry::Vacant(entry) => {
-                let export = Value::Object(Object::new(self.module_class.clone()));
-                entry.insert(export.clone());
-
-                let module = self.module_loader.load_module(module_name)?;
-                self.run_module(module.bytecode, export)?
+        let module_slot = cursor.read_arg();
+        let module_name = self.symbol_constant(bytecode, module_slot)?;
+        let module = match self.state.loaded_modules.get(&module_name).cloned() {
+            Some(module) => module,
+            None => {
+                let export = Value::Object(Object::new(&self.ctx, self.state.module_class.clone()));
+                self.state.loaded_modules.insert(module_name, export.clone());
+
+                let module = self.state.module_loader.load_module(&self.ctx, module_name)?;
+                self.run(module.id, module.bytecode, export)?
             }
         };
 
-        self.stack.push(module);
+        self.state.stack.push(module);
 
         Ok(())
     }
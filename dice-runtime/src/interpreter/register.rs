@@ -0,0 +1,125 @@
+use dice_bytecode::{Register, RegisterBytecode, RegisterInstruction};
+use dice_core::error::{codes::TYPE_ASSERTION_BOOL_FAILURE, Error, ResultExt};
+
+use crate::{
+    interpreter::Interpreter,
+    module::ModuleLoader,
+    value::{Value, ValueKind},
+};
+
+impl<'gc, L> Interpreter<'gc, '_, L>
+where
+    L: ModuleLoader,
+{
+    pub(crate) fn execute_registers(&mut self, bytecode: &RegisterBytecode) -> Result<Value<'gc>, Error> {
+        let instructions = bytecode.instructions();
+        let mut registers = vec![Value::Unit; bytecode.register_count()];
+        let mut position = 0;
+        let mut last_position = 0;
+
+        // NOTE: Use IIFE to wrap the loop, to make building error traces easier.
+        (|| {
+            use RegisterInstruction::*;
+
+            loop {
+                // NOTE: The register machine never suspends, so only the limits are checked.
+                self.tick()?;
+
+                last_position = position;
+                let instruction = instructions[position];
+                position += 1;
+
+                match instruction {
+                    LoadNull { dst } => registers[dst as usize] = Value::Null,
+                    LoadUnit { dst } => registers[dst as usize] = Value::Unit,
+                    LoadBool { dst, value } => registers[dst as usize] = Value::Bool(value),
+                    LoadConst { dst, index } => {
+                        registers[dst as usize] = self.constant_value(&bytecode.constants()[index as usize])?
+                    }
+                    Move { dst, src } => registers[dst as usize] = registers[src as usize].clone(),
+                    Negate { dst, src } => self.unary_registers(&mut registers, dst, src, Self::neg)?,
+                    Not { dst, src } => self.unary_registers(&mut registers, dst, src, Self::not)?,
+                    Multiply { dst, lhs, rhs } => self.binary_registers(&mut registers, dst, lhs, rhs, Self::mul)?,
+                    Divide { dst, lhs, rhs } => self.binary_registers(&mut registers, dst, lhs, rhs, Self::div)?,
+                    Remainder { dst, lhs, rhs } => self.binary_registers(&mut registers, dst, lhs, rhs, Self::rem)?,
+                    // NOTE: Integer addition, subtraction and comparison drive most loops, so they skip the stack.
+                    Add { dst, lhs, rhs } => match (&registers[lhs as usize], &registers[rhs as usize]) {
+                        (Value::Int(lhs), Value::Int(rhs)) => registers[dst as usize] = Value::Int(lhs + rhs),
+                        _ => self.binary_registers(&mut registers, dst, lhs, rhs, Self::add)?,
+                    },
+                    Subtract { dst, lhs, rhs } => match (&registers[lhs as usize], &registers[rhs as usize]) {
+                        (Value::Int(lhs), Value::Int(rhs)) => registers[dst as usize] = Value::Int(lhs - rhs),
+                        _ => self.binary_registers(&mut registers, dst, lhs, rhs, Self::sub)?,
+                    },
+                    LessThan { dst, lhs, rhs } => match (&registers[lhs as usize], &registers[rhs as usize]) {
+                        (Value::Int(lhs), Value::Int(rhs)) => registers[dst as usize] = Value::Bool(lhs < rhs),
+                        _ => self.binary_registers(&mut registers, dst, lhs, rhs, Self::lt)?,
+                    },
+                    GreaterThan { dst, lhs, rhs } => self.binary_registers(&mut registers, dst, lhs, rhs, Self::gt)?,
+                    GreaterThanOrEqual { dst, lhs, rhs } => {
+                        self.binary_registers(&mut registers, dst, lhs, rhs, Self::gte)?
+                    }
+                    LessThanOrEqual { dst, lhs, rhs } => {
+                        self.binary_registers(&mut registers, dst, lhs, rhs, Self::lte)?
+                    }
+                    Equal { dst, lhs, rhs } => self.binary_registers(&mut registers, dst, lhs, rhs, Self::eq)?,
+                    NotEqual { dst, lhs, rhs } => self.binary_registers(&mut registers, dst, lhs, rhs, Self::neq)?,
+                    AssertBool { src } => {
+                        if registers[src as usize].kind() != ValueKind::Bool {
+                            return Err(Error::new(TYPE_ASSERTION_BOOL_FAILURE));
+                        }
+                    }
+                    Jump { target } => position = target as usize,
+                    JumpIfFalse { src, target } => {
+                        if !registers[src as usize].as_bool()? {
+                            position = target as usize;
+                        }
+                    }
+                    JumpIfTrue { src, target } => {
+                        if registers[src as usize].as_bool()? {
+                            position = target as usize;
+                        }
+                    }
+                    JumpIfNotNull { src, target } => {
+                        if registers[src as usize] != Value::Null {
+                            position = target as usize;
+                        }
+                    }
+                    Return { src } => return Ok(std::mem::take(&mut registers[src as usize])),
+                }
+            }
+        })()
+        .push_trace(|| bytecode.trace(last_position))
+    }
+
+    // NOTE: Anything off the fast path reuses the stack based operators, so both backends share the same semantics.
+    fn unary_registers(
+        &mut self,
+        registers: &mut [Value<'gc>],
+        dst: Register,
+        src: Register,
+        operator: fn(&mut Self) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        self.state.stack.push(registers[src as usize].clone());
+        operator(self)?;
+        registers[dst as usize] = self.state.stack.pop();
+
+        Ok(())
+    }
+
+    fn binary_registers(
+        &mut self,
+        registers: &mut [Value<'gc>],
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+        operator: fn(&mut Self) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        self.state.stack.push(registers[lhs as usize].clone());
+        self.state.stack.push(registers[rhs as usize].clone());
+        operator(self)?;
+        registers[dst as usize] = self.state.stack.pop();
+
+        Ok(())
+    }
+}
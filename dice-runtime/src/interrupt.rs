@@ -0,0 +1,22 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// Lets other threads interrupt the script a runtime is running.
+/// Interrupts sent while no script is running are discarded when the next one starts.
+#[derive(Clone, Debug, Default)]
+pub struct InterruptHandle {
+    interrupted: Arc<AtomicBool>,
+}
+
+impl InterruptHandle {
+    pub fn interrupt(&self) {
+        self.interrupted.store(true, Ordering::Relaxed);
+    }
+
+    /// Clear the interrupt, returning whether one was requested.
+    pub(crate) fn take(&self) -> bool {
+        self.interrupted.swap(false, Ordering::Relaxed)
+    }
+}
@@ -1,11 +1,16 @@
 pub use runtime::Runtime;
 
+pub mod api;
+pub mod interrupt;
+pub mod limits;
 pub mod module;
 pub mod runtime;
+pub mod snapshot;
 
-// mod classes;
+mod classes;
+mod inline_cache;
 mod interpreter;
 mod stack;
 pub mod type_id;
 mod upvalue;
-mod value;
+pub mod value;
@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+/// Bounds on the resources a single run of a script can use. Every limit is disabled by default.
+#[derive(Clone, Debug, Default)]
+pub struct Limits {
+    instructions: Option<u64>,
+    time: Option<Duration>,
+    call_depth: Option<usize>,
+    heap_size: Option<usize>,
+}
+
+impl Limits {
+    pub fn with_instructions(mut self, instructions: u64) -> Self {
+        self.instructions = Some(instructions);
+        self
+    }
+
+    pub fn with_time(mut self, time: Duration) -> Self {
+        self.time = Some(time);
+        self
+    }
+
+    pub fn with_call_depth(mut self, call_depth: usize) -> Self {
+        self.call_depth = Some(call_depth);
+        self
+    }
+
+    /// Limit the heap to the given number of bytes, which includes garbage that hasn't been collected yet.
+    pub fn with_heap_size(mut self, heap_size: usize) -> Self {
+        self.heap_size = Some(heap_size);
+        self
+    }
+
+    pub fn instructions(&self) -> Option<u64> {
+        self.instructions
+    }
+
+    pub fn time(&self) -> Option<Duration> {
+        self.time
+    }
+
+    pub fn call_depth(&self) -> Option<usize> {
+        self.call_depth
+    }
+
+    pub fn heap_size(&self) -> Option<usize> {
+        self.heap_size
+    }
+}
@@ -0,0 +1,148 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+use dice_bytecode::{Bytecode, BYTECODE_FORMAT_VERSION};
+use dice_compiler::compiler::COMPILER_BUILD_ID;
+use dice_core::source::Source;
+
+pub static DEFAULT_CACHE_DIRECTORY: &str = ".dice-cache";
+static CACHE_FILE_EXTENSION: &str = "dbc";
+
+/// An on-disk cache of compiled modules, keyed by the module's canonical path, its contents and the compiler build.
+#[derive(Debug, Clone)]
+pub struct ModuleCache {
+    directory: PathBuf,
+    build_id: &'static str,
+}
+
+impl ModuleCache {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            build_id: COMPILER_BUILD_ID,
+        }
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Look up the compiled bytecode for the given source, if a valid entry exists.
+    pub fn get(&self, source: &Source) -> Option<Bytecode> {
+        let data = std::fs::read(self.entry_path(source)).ok()?;
+        let bytecode = Bytecode::from_bytes(&data).ok()?;
+        bytecode.verify().ok()?;
+
+        // NOTE: The serialized bytecode carries its source, so a hash collision can never load the wrong module.
+        let is_same_source = bytecode.source().path() == source.path() && bytecode.source().source() == source.source();
+
+        is_same_source.then_some(bytecode)
+    }
+
+    /// Store the compiled bytecode for its source.  Failing to write the cache never fails loading the module, so
+    /// any IO errors are ignored.
+    pub fn insert(&self, bytecode: &Bytecode) {
+        let path = self.entry_path(bytecode.source());
+        let temp_path = path.with_extension("tmp");
+
+        // NOTE: Write to a temporary file first so a concurrent reader never observes a partially written entry.
+        let _ = std::fs::create_dir_all(&self.directory)
+            .and_then(|_| std::fs::write(&temp_path, bytecode.to_bytes()))
+            .and_then(|_| std::fs::rename(&temp_path, &path));
+    }
+
+    fn entry_path(&self, source: &Source) -> PathBuf {
+        // NOTE: The hasher only needs to be stable for a single build of the compiler, since the build is in the key.
+        let mut hasher = DefaultHasher::new();
+        source.path().hash(&mut hasher);
+        source.source().hash(&mut hasher);
+        self.build_id.hash(&mut hasher);
+        BYTECODE_FORMAT_VERSION.hash(&mut hasher);
+
+        self.directory
+            .join(format!("{:016x}", hasher.finish()))
+            .with_extension(CACHE_FILE_EXTENSION)
+    }
+}
+
+impl Default for ModuleCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_DIRECTORY)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use dice_compiler::compiler::Compiler;
+    use dice_core::source::SourceKind;
+
+    fn cache(name: &str) -> ModuleCache {
+        let directory = std::env::temp_dir().join(format!("dice-cache-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+
+        ModuleCache::new(directory)
+    }
+
+    fn source(contents: &str) -> Source {
+        Source::with_path(contents.to_owned(), "module.dm", SourceKind::Module)
+    }
+
+    #[test]
+    fn test_miss_then_hit() {
+        let cache = cache("hit");
+        let source = source("export let x = 1");
+
+        assert!(cache.get(&source).is_none());
+
+        cache.insert(&Compiler::compile_source(source.clone()).unwrap());
+        let bytecode = cache.get(&source).expect("The entry should be cached.");
+
+        assert_eq!(bytecode.source().source(), source.source());
+    }
+
+    #[test]
+    fn test_changed_source_misses() {
+        let cache = cache("source");
+        cache.insert(&Compiler::compile_source(source("export let x = 1")).unwrap());
+
+        assert!(cache.get(&source("export let x = 2")).is_none());
+    }
+
+    #[test]
+    fn test_other_builds_miss() {
+        let cache = cache("build");
+        let source = source("export let x = 1");
+        cache.insert(&Compiler::compile_source(source.clone()).unwrap());
+
+        let other_build = ModuleCache {
+            build_id: "other",
+            ..cache.clone()
+        };
+
+        assert!(other_build.get(&source).is_none());
+        assert!(cache.get(&source).is_some());
+    }
+
+    #[test]
+    fn test_corrupt_entry_misses() {
+        let cache = cache("corrupt");
+        let source = source("export let x = 1");
+        cache.insert(&Compiler::compile_source(source.clone()).unwrap());
+
+        let path = cache.entry_path(&source);
+        let mut data = std::fs::read(&path).unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 0xFF;
+        std::fs::write(&path, data).unwrap();
+
+        assert!(cache.get(&source).is_none());
+
+        // NOTE: Compiling the module again replaces the corrupt entry.
+        cache.insert(&Compiler::compile_source(source.clone()).unwrap());
+        assert!(cache.get(&source).is_some());
+    }
+}
@@ -9,19 +9,34 @@ use dice_core::{
     tags,
 };
 
-use crate::module::{Module, ModuleLoader};
+use crate::module::{cache::ModuleCache, Module, ModuleLoader};
 use crate::runtime::RuntimeContext;
 use crate::value::Symbol;
 
-#[derive(Default)]
-pub struct FileModuleLoader;
+pub struct FileModuleLoader {
+    cache: Option<ModuleCache>,
+}
+
+impl FileModuleLoader {
+    /// Create a loader that uses the given cache for compiled modules, or always compiles if there's none.
+    pub fn new(cache: Option<ModuleCache>) -> Self {
+        Self { cache }
+    }
+}
+
+// NOTE: Caching is opt-in, since the cache is written to disk next to wherever the host happens to run.
+impl Default for FileModuleLoader {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
 
 impl ModuleLoader for FileModuleLoader {
-    fn load_module(&mut self, ctx: &RuntimeContext, name: Symbol) -> Result<Module, Error> {
-        let name_str = ctx.interner.resolve(name).expect("symbol not found");
+    fn load_module(&mut self, ctx: &RuntimeContext<'_>, name: Symbol) -> Result<Module, Error> {
+        let name_str = ctx.resolve(name).to_string();
 
         (|| {
-            let path = dunce::canonicalize(name_str)?;
+            let path = dunce::canonicalize(&name_str)?;
             let working_dir = dunce::canonicalize(std::env::current_dir()?)?;
 
             // TODO: Have a way to set the modules root as a part of the runtime.
@@ -33,8 +48,19 @@ impl ModuleLoader for FileModuleLoader {
 
             let source = std::fs::read_to_string(&path)?;
             let source = Source::with_path(source, path.to_string_lossy(), SourceKind::Module);
-            let module = Compiler::compile_source(source)?;
-            let module = Module::new(name.clone(), module);
+            let module = match self.cache.as_ref().and_then(|cache| cache.get(&source)) {
+                Some(bytecode) => bytecode,
+                None => {
+                    let bytecode = Compiler::compile_source(source)?;
+
+                    if let Some(cache) = &self.cache {
+                        cache.insert(&bytecode);
+                    }
+
+                    bytecode
+                }
+            };
+            let module = Module::new(name, module);
 
             Ok(module)
         })()
@@ -6,6 +6,7 @@ use dice_core::error::Error;
 use crate::runtime::RuntimeContext;
 use crate::value::Symbol;
 
+pub mod cache;
 pub mod file_loader;
 
 #[derive(Clone, Collect)]
@@ -1,209 +1,365 @@
 use std::{
+    cell::Ref,
     collections::{HashMap, VecDeque},
     hash::BuildHasherDefault,
+    time::Instant,
 };
 
 use ahash::AHasher;
-use gc_arena::{Arena, Collect, Mutation, Rootable};
+use gc_arena::{Arena, Collect, Gc, Mutation, Rootable};
 
 use dice_bytecode::Bytecode;
-use dice_core::error::Error;
+#[cfg(feature = "register-vm")]
+use dice_bytecode::RegisterBytecode;
+use dice_core::{
+    error::{
+        codes::{GLOBAL_ALREADY_EXISTS, MODULE_ALREADY_EXISTS},
+        Error,
+    },
+    tags,
+};
 
-use crate::upvalue::Upvalue;
-use crate::value::{Class, SymbolInterner, Value, ValueKind, ValueMap};
 use crate::{
+    api,
+    classes::{any::new_any_class, module::new_module_class},
+    interpreter::{CallFrame, Execution, Interpreter},
+    interrupt::InterruptHandle,
+    limits::Limits,
     module::{file_loader::FileModuleLoader, ModuleLoader},
+    snapshot::Snapshot,
     stack::Stack,
+    upvalue::Upvalue,
+    value::{Class, Object, OwnedValue, String, Symbol, SymbolInterner, Value, ValueKind, ValueMap},
 };
 
+/// Everything needed to allocate values and intern symbols while the arena is being mutated.
+#[derive(Copy, Clone)]
 pub struct RuntimeContext<'gc> {
     pub mutation: &'gc Mutation<'gc>,
     pub interner: &'gc SymbolInterner,
-    pub state: &'gc State<'gc>,
 }
 
+impl<'gc> RuntimeContext<'gc> {
+    pub fn symbol(&self, name: impl AsRef<str>) -> Symbol {
+        self.interner.get_or_intern(name)
+    }
+
+    pub fn resolve(&self, symbol: Symbol) -> Ref<'gc, str> {
+        self.interner.resolve(symbol)
+    }
+
+    /// The symbol's name as a string, which shares its buffer with every other conversion of the same symbol.
+    pub fn string(&self, symbol: Symbol) -> String {
+        self.interner.string(symbol)
+    }
+
+    /// Create a string, sharing the buffer of short strings with every other string of the same contents.
+    pub fn intern_string(&self, value: &str) -> String {
+        if value.len() <= MAX_INTERNED_STRING_LENGTH {
+            self.string(self.symbol(value))
+        } else {
+            String::from(value)
+        }
+    }
+}
+
+// NOTE: Interned strings are never freed, so only short strings are worth interning.
+const MAX_INTERNED_STRING_LENGTH: usize = 32;
+// NOTE: Checking the arena's allocation debt is cheap, but not cheap enough to do on every instruction.
+const DEFAULT_COLLECTION_INTERVAL: usize = 1024;
+const SCRIPT_NAME: &str = "<script>";
+
+type StateArena<L> = Arena<Rootable![State<'_, L>]>;
+
 pub struct Runtime<L = FileModuleLoader>
 where
     L: ModuleLoader + 'static,
 {
-    pub(crate) state: Arena<Rootable![State<'_, L>]>,
-    pub(crate) interner: SymbolInterner,
+    arena: StateArena<L>,
 }
 
 #[derive(Collect)]
 #[collect(no_drop)]
 pub(crate) struct State<'gc, L = FileModuleLoader> {
-    pub(super) stack: Stack<'gc>,
+    pub(crate) stack: Stack<'gc>,
+    pub(crate) frames: Vec<CallFrame<'gc>>,
     pub(crate) open_upvalues: VecDeque<Upvalue<'gc>>,
     pub(crate) globals: ValueMap<'gc>,
     pub(crate) loaded_modules: ValueMap<'gc>,
     #[collect(require_static)]
     pub(crate) module_loader: L,
-    // pub(crate) any_class: Class<'gc>,
-    // pub(crate) module_class: Class<'gc>,
+    pub(crate) interner: Gc<'gc, SymbolInterner>,
+    pub(crate) any_class: Class<'gc>,
+    pub(crate) module_class: Class<'gc>,
     pub(crate) value_class_mapping: HashMap<ValueKind, Class<'gc>, BuildHasherDefault<AHasher>>,
+    pub(crate) collection_interval: usize,
+    #[collect(require_static)]
+    pub(crate) limits: Limits,
+    pub(crate) instructions_executed: u64,
+    #[collect(require_static)]
+    pub(crate) started_at: Instant,
+    #[collect(require_static)]
+    pub(crate) interrupt: InterruptHandle,
 }
 
 impl<'gc, L> State<'gc, L>
 where
     L: ModuleLoader,
 {
-    pub fn new(mutation: &Mutation<'gc>) -> Self {
-        // let any_class = Self::new_any_class(mutation);
-        // let module_class = Self::new_module_class(&any_class);
-        // let mut globals: ValueMap = ValueMap::default();
-        // globals.insert(any_class.name(), Value::Class(any_class.clone()));
-        // globals.insert(module_class.name(), Value::Class(module_class.clone()));
+    fn new(mutation: &'gc Mutation<'gc>) -> Self {
+        let interner = Gc::new(mutation, SymbolInterner::default());
+        let ctx = RuntimeContext {
+            mutation,
+            interner: Gc::as_ref(interner),
+        };
+        let any_class = new_any_class(&ctx);
+        let module_class = new_module_class(&ctx, &any_class);
+        let mut globals = ValueMap::default();
+        globals.insert(any_class.name(), Value::Class(any_class.clone()));
+        globals.insert(module_class.name(), Value::Class(module_class.clone()));
 
         Self {
             stack: Default::default(),
+            frames: Default::default(),
             open_upvalues: Default::default(),
             loaded_modules: Default::default(),
             module_loader: Default::default(),
             value_class_mapping: Default::default(),
-            globals: Default::default(),
-            // any_class,
-            // module_class,
+            collection_interval: DEFAULT_COLLECTION_INTERVAL,
+            limits: Default::default(),
+            instructions_executed: 0,
+            started_at: Instant::now(),
+            interrupt: Default::default(),
+            globals,
+            interner,
+            any_class,
+            module_class,
         }
     }
+
+    /// Start counting the resources used towards the limits from zero, and drop any interrupt meant for an earlier run.
+    fn reset_usage(&mut self) {
+        self.instructions_executed = 0;
+        self.started_at = Instant::now();
+        self.interrupt.take();
+    }
 }
 
-impl<'gc, L> Default for Runtime<L>
+impl<L> Default for Runtime<L>
 where
     L: ModuleLoader,
 {
     fn default() -> Self {
         let mut runtime = Self {
-            state: Arena::<Rootable![State<'_, L>]>::new(State::new),
-            interner: Default::default(),
+            arena: StateArena::new(|mutation| State::new(mutation)),
         };
 
-        // runtime.register_known_types();
+        runtime
+            .arena
+            .mutate_root(|mutation, state| Interpreter::new(mutation, state).register_known_types());
+
         runtime
     }
 }
 
 impl<L> Runtime<L>
 where
-    L: ModuleLoader + Default,
+    L: ModuleLoader,
 {
-    pub fn run(&mut self, bytecode: Bytecode) -> Result<Value, Error> {
-        self.state.mutate_root(|ctx, mut state| {
-            let stack_frame = state.stack.reserve_slots(bytecode.slot_count());
-            let result = self.execute(&bytecode, stack_frame, None)?;
+    /// Run the script, letting the arena collect garbage incrementally whenever it has work pending.
+    pub fn run(&mut self, bytecode: Bytecode) -> Result<OwnedValue, Error> {
+        let base = self.arena.mutate_root(|mutation, state| -> Result<_, Error> {
+            state.reset_usage();
+            let base = state.frames.len();
+            let mut interpreter = Interpreter::new(mutation, state);
+            let name = interpreter.ctx.symbol(SCRIPT_NAME);
+            interpreter.start(name, bytecode, Value::Null)?;
+
+            Ok(base)
+        })?;
+
+        loop {
+            let result = self.arena.mutate_root(|mutation, state| -> Result<_, Error> {
+                let mut interpreter = Interpreter::new(mutation, state);
+
+                match interpreter.execute(base, true)? {
+                    Execution::Complete(value) => Ok(Some(OwnedValue::from_value(&interpreter.ctx, &value))),
+                    Execution::Suspended => Ok(None),
+                }
+            })?;
+
+            match result {
+                Some(value) => return Ok(value),
+                None => self.arena.collect_debt(),
+            }
+        }
+    }
+
+    /// Set the limits checked while scripts run, which apply to each call of `run` or `mutate` separately.
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.arena.mutate_root(|_, state| state.limits = limits);
+    }
 
-            state.stack.release_stack_frame(stack_frame);
+    /// A handle other threads can use to interrupt the script being run.
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.arena.mutate(|_, state| state.interrupt.clone())
+    }
 
-            Ok(result)
+    /// Set how many values the stack can grow to hold, before scripts fail with a stack overflow.
+    pub fn set_max_stack_size(&mut self, values: usize) {
+        self.arena.mutate_root(|_, state| state.stack.set_max_size(values));
+    }
+
+    /// The number of bytes currently allocated in the runtime's heap.
+    pub fn heap_size(&self) -> usize {
+        self.arena.metrics().total_allocation()
+    }
+
+    /// Set how many instructions run between checks for pending garbage collection work.
+    pub fn set_collection_interval(&mut self, instructions: usize) {
+        self.arena
+            .mutate_root(|_, state| state.collection_interval = instructions.max(1));
+    }
+
+    /// Run code compiled for the register machine.
+    // NOTE: The register machine keeps its registers on the native stack, so it never suspends for collection.
+    #[cfg(feature = "register-vm")]
+    pub fn run_registers(&mut self, bytecode: RegisterBytecode) -> Result<OwnedValue, Error> {
+        self.arena.mutate_root(|mutation, state| {
+            state.reset_usage();
+            let mut interpreter = Interpreter::new(mutation, state);
+            let result = interpreter.execute_registers(&bytecode)?;
+
+            Ok(OwnedValue::from_value(&interpreter.ctx, &result))
         })
     }
 
-    pub(super) fn run_module(&mut self, bytecode: Bytecode, export: Value) -> Result<Value, Error> {
-        self.state.mutate_root(|ctx, mut state| {
-            let stack_frame = state.stack.reserve_slots(bytecode.slot_count());
-            state.stack[stack_frame.start()] = export;
-            let result = self.execute(&bytecode, stack_frame, None)?;
-            state.stack.release_stack_frame(stack_frame);
+    /// Give the function access to the runtime, to create values or call into scripts.
+    // NOTE: Values can't escape the closure, since they are only valid while the arena is being mutated.
+    pub fn mutate<R>(&mut self, function: impl for<'gc> FnOnce(&mut dyn api::Runtime<'gc>) -> R) -> R {
+        self.arena.mutate_root(|mutation, state| {
+            state.reset_usage();
+            function(&mut Interpreter::new(mutation, state))
+        })
+    }
+
+    pub fn load_prelude(&mut self, path: &str) -> Result<(), Error> {
+        self.mutate(|runtime| runtime.load_prelude(path))
+    }
+
+    /// Capture the globals and loaded modules, along with every value reachable from them.
+    pub fn snapshot(&self) -> Result<Snapshot, Error> {
+        self.arena.mutate(|mutation, state| {
+            let ctx = RuntimeContext {
+                mutation,
+                interner: Gc::as_ref(state.interner),
+            };
 
-            Ok(result)
+            Snapshot::capture(&ctx, state)
         })
     }
 
-    // pub(super) fn set_value_class(&mut self, value_kind: ValueKind, class: Class) {
-    //     self.state.mutate(|_, state| {
-    //         state.value_class_mapping.insert(value_kind, class.clone());
-    //         state.globals.insert(class.name(), Value::Class(class));
-    //     });
-    // }
+    /// Restore a snapshot, replacing any globals and loaded modules with the same names.
+    pub fn restore(&mut self, snapshot: &Snapshot) -> Result<(), Error> {
+        self.arena.mutate_root(|mutation, state| {
+            let ctx = RuntimeContext {
+                mutation,
+                interner: Gc::as_ref(state.interner),
+            };
+
+            snapshot.restore(&ctx, state)
+        })
+    }
 }
 
-// impl<L> dice_core::runtime::Runtime for Runtime<L>
-// where
-//     L: ModuleLoader,
-// {
-//     fn new_module(&mut self, name: &str) -> Result<Object, Error> {
-//         let module = Object::new(None);
-
-//         if self
-//             .loaded_modules
-//             .insert(name.into(), Value::Object(module.clone()))
-//             .is_some()
-//         {
-//             return Err(Error::new(MODULE_ALREADY_EXISTS).with_tags(tags! {
-//                 name => name.to_string()
-//             }));
-//         }
-
-//         Ok(module)
-//     }
-
-//     fn new_class(&mut self, name: &str) -> Result<Class, Error> {
-//         let class = Class::with_base(name.into(), self.any_class.clone());
-
-//         Ok(class)
-//     }
-
-//     fn new_object(&mut self) -> Result<Object, Error> {
-//         let object = Object::new(self.any_class.clone());
-
-//         Ok(object)
-//     }
-
-//     fn load_prelude(&mut self, path: &str) -> Result<(), Error> {
-//         let module = self.module_loader.load_module(path.into())?;
-//         let prelude = Value::Object(Object::new(self.module_class.clone()));
-//         // NOTE: Add the loaded prelude module as a registered module.
-//         self.loaded_modules.insert(module.id.clone(), prelude.clone());
-
-//         let prelude = self.run_module(module.bytecode, prelude)?;
-
-//         for (name, value) in &*prelude.as_object()?.fields() {
-//             self.globals.entry(name.clone()).or_insert_with(|| value.clone());
-//         }
-
-//         Ok(())
-//     }
-
-//     fn add_global(&mut self, name: &str, value: Value) -> Result<(), Error> {
-//         if self.globals.insert(name.into(), value).is_some() {
-//             return Err(Error::new(GLOBAL_ALREADY_EXISTS).with_tags(tags! {
-//                 name => name.to_string()
-//             }));
-//         }
-
-//         Ok(())
-//     }
-
-//     fn call_function(&mut self, target: Value, args: &[Value]) -> Result<Value, Error> {
-//         let arg_count = args.len();
-//         self.stack.push(target);
-//         self.stack.push_multiple(args);
-//         self.call_fn(arg_count)?;
-
-//         Ok(self.stack.pop())
-//     }
-
-//     fn any_class(&self) -> Result<Class, Error> {
-//         Ok(self.any_class.clone())
-//     }
-
-//     fn class_of(&self, value: &Value) -> Result<Class, Error> {
-//         let result = value
-//             .as_object()
-//             .ok()
-//             .and_then(|object| object.class())
-//             .or_else(|| self.value_class_mapping.get(&value.kind()).cloned())
-//             .unwrap_or_else(|| self.any_class.clone());
-
-//         Ok(result)
-//     }
-
-//     fn is_value_of_type(&self, value: &Value, class: &Class) -> Result<bool, Error> {
-//         value
-//             .as_object()
-//             .ok()
-//             .and_then(|object| object.class())
-//             .or_else(|| self.value_class_mapping.get(&value.kind()).cloned())
-//             .map_or(Ok(false), |instance_class| Ok(instance_class.is_class(&class)))
-//     }
-// }
+impl<'gc, L> api::Runtime<'gc> for Interpreter<'gc, '_, L>
+where
+    L: ModuleLoader,
+{
+    fn context(&self) -> RuntimeContext<'gc> {
+        self.ctx
+    }
+
+    fn new_module(&mut self, name: &str) -> Result<Object<'gc>, Error> {
+        let module = Object::new(&self.ctx, None);
+
+        if self
+            .state
+            .loaded_modules
+            .insert(self.ctx.symbol(name), Value::Object(module.clone()))
+            .is_some()
+        {
+            return Err(Error::new(MODULE_ALREADY_EXISTS).with_tags(tags! {
+                name => name.to_string()
+            }));
+        }
+
+        Ok(module)
+    }
+
+    fn new_class(&mut self, name: &str) -> Result<Class<'gc>, Error> {
+        let class = Class::with_base(&self.ctx, self.ctx.symbol(name), self.state.any_class.clone());
+
+        Ok(class)
+    }
+
+    fn new_object(&mut self) -> Result<Object<'gc>, Error> {
+        let object = Object::new(&self.ctx, self.state.any_class.clone());
+
+        Ok(object)
+    }
+
+    fn load_prelude(&mut self, path: &str) -> Result<(), Error> {
+        let module = self.state.module_loader.load_module(&self.ctx, self.ctx.symbol(path))?;
+        let prelude = Value::Object(Object::new(&self.ctx, self.state.module_class.clone()));
+        // NOTE: Add the loaded prelude module as a registered module.
+        self.state.loaded_modules.insert(module.id, prelude.clone());
+
+        let prelude = self.run(module.id, module.bytecode, prelude)?;
+
+        for (name, value) in prelude.as_object()?.fields().iter() {
+            self.state.globals.entry(*name).or_insert_with(|| value.clone());
+        }
+
+        Ok(())
+    }
+
+    fn add_global(&mut self, name: &str, value: Value<'gc>) -> Result<(), Error> {
+        if self.state.globals.insert(self.ctx.symbol(name), value).is_some() {
+            return Err(Error::new(GLOBAL_ALREADY_EXISTS).with_tags(tags! {
+                name => name.to_string()
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn call_function(&mut self, target: Value<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
+        let arg_count = args.len();
+        self.state.stack.push(target);
+        self.state.stack.push_multiple(args);
+        self.call_fn(arg_count)?;
+
+        Ok(self.state.stack.pop())
+    }
+
+    fn any_class(&self) -> Result<Class<'gc>, Error> {
+        Ok(self.state.any_class.clone())
+    }
+
+    fn class_of(&self, value: &Value<'gc>) -> Result<Class<'gc>, Error> {
+        let result = self
+            .class_of_value(value)
+            .unwrap_or_else(|| self.state.any_class.clone());
+
+        Ok(result)
+    }
+
+    fn is_value_of_type(&self, value: &Value<'gc>, class: &Class<'gc>) -> Result<bool, Error> {
+        let result = self
+            .class_of_value(value)
+            .is_some_and(|instance_class| instance_class.is_class(class));
+
+        Ok(result)
+    }
+}
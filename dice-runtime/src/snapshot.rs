@@ -0,0 +1,635 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use dice_bytecode::Bytecode;
+use dice_core::{
+    error::{
+        codes::{INVALID_SNAPSHOT, SNAPSHOT_GLOBAL_UNDEFINED, SNAPSHOT_UNSUPPORTED_VALUE},
+        Error,
+    },
+    protocol::class::NEW,
+    tags,
+};
+
+use crate::{
+    runtime::{RuntimeContext, State},
+    upvalue::{Upvalue, UpvalueState},
+    value::{Class, FnBound, FnClosure, FnScript, Map, MapKey, Object, Symbol, Value},
+};
+
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// The globals and loaded modules of a runtime, along with every value reachable from them, in a form that can be
+/// serialized and later restored into another runtime.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    version: u32,
+    functions: Vec<SnapshotFunction>,
+    // NOTE: Values that can be shared or form cycles are stored once here, and referred to by their index.
+    heap: Vec<SnapshotHeapValue>,
+    globals: Vec<(String, SnapshotValue)>,
+    modules: Vec<(String, SnapshotValue)>,
+}
+
+impl Snapshot {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Snapshots should always serialize.")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(|_| Error::new(INVALID_SNAPSHOT))
+    }
+
+    pub(crate) fn capture<'gc, L>(ctx: &RuntimeContext<'gc>, state: &State<'gc, L>) -> Result<Self, Error> {
+        let mut writer = SnapshotWriter::new(ctx, state);
+        let globals = writer.write_entries(&state.globals)?;
+        let modules = writer.write_entries(&state.loaded_modules)?;
+
+        Ok(Self {
+            version: SNAPSHOT_VERSION,
+            functions: writer.functions,
+            heap: writer.heap,
+            globals,
+            modules,
+        })
+    }
+
+    /// Restore the snapshot's values, replacing any globals and loaded modules with the same names.
+    pub(crate) fn restore<'gc, L>(&self, ctx: &RuntimeContext<'gc>, state: &mut State<'gc, L>) -> Result<(), Error> {
+        if self.version != SNAPSHOT_VERSION {
+            return Err(Error::new(INVALID_SNAPSHOT));
+        }
+
+        // NOTE: Every value is read before any global is replaced, so natives are looked up in the original globals.
+        let mut reader = SnapshotReader::new(ctx, state, self);
+        let globals = reader.read_entries(&self.globals)?;
+        let modules = reader.read_entries(&self.modules)?;
+        reader.fill()?;
+
+        state.globals.extend(globals);
+        state.loaded_modules.extend(modules);
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SnapshotFunction {
+    id: String,
+    name: String,
+    bytecode: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum SnapshotValue {
+    Null,
+    Unit,
+    Bool(bool),
+    Int(i64),
+    // NOTE: Stored as bits, since JSON can't represent NaN or the infinities.
+    Float(u64),
+    String(String),
+    Symbol(String),
+    Function(usize),
+    // NOTE: Native functions and the runtime's own classes are referred to by name and looked up when restoring.
+    Native(String),
+    BuiltinClass(String),
+    Reference(usize),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum SnapshotMapKey {
+    Null,
+    Unit,
+    Bool(bool),
+    Int(i64),
+    String(String),
+    Symbol(String),
+    Tuple(Vec<SnapshotMapKey>),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum SnapshotHeapValue {
+    Object {
+        class: Option<SnapshotValue>,
+        fields: Vec<(String, SnapshotValue)>,
+    },
+    Class {
+        name: String,
+        base: Option<SnapshotValue>,
+        // NOTE: Only the methods the class doesn't share with its base, which are copied again when restoring.
+        methods: Vec<(String, SnapshotValue)>,
+        fields: Vec<(String, SnapshotValue)>,
+    },
+    Array(Vec<SnapshotValue>),
+    Map(Vec<(SnapshotMapKey, SnapshotValue)>),
+    Closure {
+        function: usize,
+        upvalues: Vec<usize>,
+    },
+    Upvalue(SnapshotValue),
+    Bound {
+        receiver: SnapshotValue,
+        function: SnapshotValue,
+    },
+}
+
+struct SnapshotWriter<'gc, 'a, L> {
+    ctx: &'a RuntimeContext<'gc>,
+    state: &'a State<'gc, L>,
+    natives: HashMap<*const (), String>,
+    builtin_classes: HashMap<*const (), String>,
+    references: HashMap<*const (), usize>,
+    function_ids: HashMap<uuid::Uuid, usize>,
+    functions: Vec<SnapshotFunction>,
+    heap: Vec<SnapshotHeapValue>,
+}
+
+impl<'gc, 'a, L> SnapshotWriter<'gc, 'a, L> {
+    fn new(ctx: &'a RuntimeContext<'gc>, state: &'a State<'gc, L>) -> Self {
+        let natives = state
+            .globals
+            .iter()
+            .filter_map(|(name, value)| match value {
+                Value::FnNative(native) => Some((native.as_ptr(), ctx.resolve(*name).to_string())),
+                _ => None,
+            })
+            .collect();
+        let builtin_classes = builtin_classes(state)
+            .map(|class| (class.as_ptr(), ctx.resolve(class.name()).to_string()))
+            .collect();
+
+        Self {
+            ctx,
+            state,
+            natives,
+            builtin_classes,
+            references: HashMap::new(),
+            function_ids: HashMap::new(),
+            functions: Vec::new(),
+            heap: Vec::new(),
+        }
+    }
+
+    fn write_entries<'v>(
+        &mut self,
+        entries: impl IntoIterator<Item = (&'v Symbol, &'v Value<'gc>)>,
+    ) -> Result<Vec<(String, SnapshotValue)>, Error>
+    where
+        'gc: 'v,
+    {
+        let mut entries = entries
+            .into_iter()
+            .map(|(name, value)| Ok((self.ctx.resolve(*name).to_string(), self.write_value(value)?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        // NOTE: Sorted so the same runtime state always produces the same snapshot.
+        entries.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+
+        Ok(entries)
+    }
+
+    fn write_value(&mut self, value: &Value<'gc>) -> Result<SnapshotValue, Error> {
+        let value = match value {
+            Value::Null => SnapshotValue::Null,
+            Value::Unit => SnapshotValue::Unit,
+            Value::Bool(bool) => SnapshotValue::Bool(*bool),
+            Value::Int(int) => SnapshotValue::Int(*int),
+            Value::Float(float) => SnapshotValue::Float(float.to_bits()),
+            Value::String(string) => SnapshotValue::String(string.to_string()),
+            Value::Symbol(symbol) => SnapshotValue::Symbol(self.ctx.resolve(*symbol).to_string()),
+            Value::FnScript(fn_script) => SnapshotValue::Function(self.write_function(fn_script)),
+            Value::FnNative(native) => match self.natives.get(&native.as_ptr()) {
+                Some(name) => SnapshotValue::Native(name.clone()),
+                None => return Err(Error::new(SNAPSHOT_UNSUPPORTED_VALUE)),
+            },
+            Value::Class(class) => match self.builtin_classes.get(&class.as_ptr()) {
+                Some(name) => SnapshotValue::BuiltinClass(name.clone()),
+                None => self.write_reference(class.as_ptr(), |writer| writer.write_class(class))?,
+            },
+            Value::Object(object) => self.write_reference(object.as_ptr(), |writer| writer.write_object(object))?,
+            Value::Array(array) => self.write_reference(array.as_ptr(), |writer| {
+                let elements = array.elements().to_vec();
+                let elements = elements
+                    .iter()
+                    .map(|element| writer.write_value(element))
+                    .collect::<Result<_, _>>()?;
+
+                Ok(SnapshotHeapValue::Array(elements))
+            })?,
+            Value::Map(map) => self.write_reference(map.as_ptr(), |writer| {
+                let entries = map.entries().clone();
+                let entries = entries
+                    .iter()
+                    .map(|(key, value)| Ok((writer.write_map_key(key), writer.write_value(value)?)))
+                    .collect::<Result<_, Error>>()?;
+
+                Ok(SnapshotHeapValue::Map(entries))
+            })?,
+            Value::FnClosure(closure) => self.write_reference(closure.as_ptr(), |writer| {
+                let function = writer.write_function(closure.fn_script());
+                let upvalues = closure
+                    .upvalues()
+                    .iter()
+                    .map(|upvalue| writer.write_upvalue(upvalue))
+                    .collect::<Result<_, _>>()?;
+
+                Ok(SnapshotHeapValue::Closure { function, upvalues })
+            })?,
+            Value::FnBound(bound) => self.write_reference(bound.as_ptr(), |writer| {
+                Ok(SnapshotHeapValue::Bound {
+                    receiver: writer.write_value(&bound.receiver())?,
+                    function: writer.write_value(&bound.function())?,
+                })
+            })?,
+        };
+
+        Ok(value)
+    }
+
+    fn write_reference(
+        &mut self,
+        ptr: *const (),
+        write: impl FnOnce(&mut Self) -> Result<SnapshotHeapValue, Error>,
+    ) -> Result<SnapshotValue, Error> {
+        Ok(SnapshotValue::Reference(self.write_heap_value(ptr, write)?))
+    }
+
+    fn write_heap_value(
+        &mut self,
+        ptr: *const (),
+        write: impl FnOnce(&mut Self) -> Result<SnapshotHeapValue, Error>,
+    ) -> Result<usize, Error> {
+        if let Some(index) = self.references.get(&ptr) {
+            return Ok(*index);
+        }
+
+        // NOTE: The index is claimed before the contents are written, so cycles can refer back to it.
+        let index = self.heap.len();
+        self.heap.push(SnapshotHeapValue::Array(Vec::new()));
+        self.references.insert(ptr, index);
+        self.heap[index] = write(self)?;
+
+        Ok(index)
+    }
+
+    fn write_object(&mut self, object: &Object<'gc>) -> Result<SnapshotHeapValue, Error> {
+        let class = match object.class() {
+            Some(class) => Some(self.write_value(&Value::Class(class))?),
+            None => None,
+        };
+        let fields = self.write_entries(&object.fields())?;
+
+        Ok(SnapshotHeapValue::Object { class, fields })
+    }
+
+    fn write_class(&mut self, class: &Class<'gc>) -> Result<SnapshotHeapValue, Error> {
+        let base = class.base();
+        let methods = class
+            .methods()
+            .into_iter()
+            .filter(|(name, method)| {
+                let inherited = base.as_ref().and_then(|base| base.method(*name));
+                !matches!(inherited, Some(inherited) if is_same_method(method, &inherited))
+            })
+            .collect::<Vec<_>>();
+        let methods = self.write_entries(methods.iter().map(|(name, method)| (name, method)))?;
+        let base = match base {
+            Some(base) => Some(self.write_value(&Value::Class(base))?),
+            None => None,
+        };
+        let fields = self.write_entries(&class.fields())?;
+
+        Ok(SnapshotHeapValue::Class {
+            name: self.ctx.resolve(class.name()).to_string(),
+            base,
+            methods,
+            fields,
+        })
+    }
+
+    fn write_upvalue(&mut self, upvalue: &Upvalue<'gc>) -> Result<usize, Error> {
+        self.write_heap_value(upvalue.as_ptr(), |writer| {
+            let value = match &*upvalue.state() {
+                UpvalueState::Open(slot) => writer.state.stack[*slot].clone(),
+                UpvalueState::Closed(value) => value.clone(),
+            };
+
+            Ok(SnapshotHeapValue::Upvalue(writer.write_value(&value)?))
+        })
+    }
+
+    fn write_function(&mut self, fn_script: &FnScript) -> usize {
+        if let Some(index) = self.function_ids.get(&fn_script.id()) {
+            return *index;
+        }
+
+        let index = self.functions.len();
+        self.functions.push(SnapshotFunction {
+            id: fn_script.id().to_string(),
+            name: self.ctx.resolve(fn_script.name()).to_string(),
+            bytecode: fn_script.bytecode().to_bytes(),
+        });
+        self.function_ids.insert(fn_script.id(), index);
+
+        index
+    }
+
+    fn write_map_key(&self, key: &MapKey) -> SnapshotMapKey {
+        match key {
+            MapKey::Null => SnapshotMapKey::Null,
+            MapKey::Unit => SnapshotMapKey::Unit,
+            MapKey::Bool(bool) => SnapshotMapKey::Bool(*bool),
+            MapKey::Int(int) => SnapshotMapKey::Int(*int),
+            MapKey::String(string) => SnapshotMapKey::String(string.to_string()),
+            MapKey::Symbol(symbol) => SnapshotMapKey::Symbol(self.ctx.resolve(*symbol).to_string()),
+            MapKey::Tuple(items) => SnapshotMapKey::Tuple(items.iter().map(|item| self.write_map_key(item)).collect()),
+        }
+    }
+}
+
+struct SnapshotReader<'gc, 'a, L> {
+    ctx: &'a RuntimeContext<'gc>,
+    state: &'a State<'gc, L>,
+    snapshot: &'a Snapshot,
+    functions: Vec<Option<FnScript>>,
+    values: Vec<Option<Value<'gc>>>,
+    upvalues: Vec<Option<Upvalue<'gc>>>,
+    allocating: Vec<bool>,
+    filled: Vec<bool>,
+    pending: Vec<usize>,
+}
+
+impl<'gc, 'a, L> SnapshotReader<'gc, 'a, L> {
+    fn new(ctx: &'a RuntimeContext<'gc>, state: &'a State<'gc, L>, snapshot: &'a Snapshot) -> Self {
+        let heap_size = snapshot.heap.len();
+
+        Self {
+            ctx,
+            state,
+            snapshot,
+            functions: vec![None; snapshot.functions.len()],
+            values: vec![None; heap_size],
+            upvalues: vec![None; heap_size],
+            allocating: vec![false; heap_size],
+            filled: vec![false; heap_size],
+            pending: Vec::new(),
+        }
+    }
+
+    fn read_entries(&mut self, entries: &[(String, SnapshotValue)]) -> Result<Vec<(Symbol, Value<'gc>)>, Error> {
+        entries
+            .iter()
+            .map(|(name, value)| Ok((self.ctx.symbol(name), self.read_value(value)?)))
+            .collect()
+    }
+
+    fn read_value(&mut self, value: &SnapshotValue) -> Result<Value<'gc>, Error> {
+        let value = match value {
+            SnapshotValue::Null => Value::Null,
+            SnapshotValue::Unit => Value::Unit,
+            SnapshotValue::Bool(bool) => Value::Bool(*bool),
+            SnapshotValue::Int(int) => Value::Int(*int),
+            SnapshotValue::Float(bits) => Value::Float(f64::from_bits(*bits)),
+            SnapshotValue::String(string) => Value::with_string(string.as_str()),
+            SnapshotValue::Symbol(symbol) => Value::Symbol(self.ctx.symbol(symbol)),
+            SnapshotValue::Function(index) => Value::FnScript(self.read_function(*index)?),
+            SnapshotValue::Native(name) => match self.state.globals.get(&self.ctx.symbol(name)) {
+                Some(native @ Value::FnNative(_)) => native.clone(),
+                _ => {
+                    return Err(Error::new(SNAPSHOT_GLOBAL_UNDEFINED).with_tags(tags! {
+                        name => name.to_string()
+                    }))
+                }
+            },
+            SnapshotValue::BuiltinClass(name) => {
+                let name = self.ctx.symbol(name);
+                let class = builtin_classes(self.state)
+                    .find(|class| class.name() == name)
+                    .ok_or_else(|| Error::new(INVALID_SNAPSHOT))?;
+
+                Value::Class(class.clone())
+            }
+            SnapshotValue::Reference(index) => self.allocate(*index)?,
+        };
+
+        Ok(value)
+    }
+
+    fn read_class(&mut self, value: &SnapshotValue) -> Result<Class<'gc>, Error> {
+        match self.read_value(value)? {
+            Value::Class(class) => Ok(class),
+            _ => Err(Error::new(INVALID_SNAPSHOT)),
+        }
+    }
+
+    fn read_function(&mut self, index: usize) -> Result<FnScript, Error> {
+        if let Some(Some(function)) = self.functions.get(index) {
+            return Ok(function.clone());
+        }
+
+        let snapshot = self.snapshot;
+        let function = snapshot
+            .functions
+            .get(index)
+            .ok_or_else(|| Error::new(INVALID_SNAPSHOT))?;
+        let id = uuid::Uuid::parse_str(&function.id).map_err(|_| Error::new(INVALID_SNAPSHOT))?;
+        // NOTE: Snapshots can come from anywhere, so their bytecode is verified before the interpreter trusts it.
+        let bytecode = Bytecode::from_bytes(&function.bytecode).map_err(|_| Error::new(INVALID_SNAPSHOT))?;
+        bytecode.verify().map_err(|_| Error::new(INVALID_SNAPSHOT))?;
+        let function = FnScript::new(self.ctx, self.ctx.symbol(&function.name), bytecode, id);
+        self.functions[index] = Some(function.clone());
+
+        Ok(function)
+    }
+
+    fn heap_value(&self, index: usize) -> Result<&'a SnapshotHeapValue, Error> {
+        let snapshot = self.snapshot;
+
+        snapshot.heap.get(index).ok_or_else(|| Error::new(INVALID_SNAPSHOT))
+    }
+
+    /// Allocate the value at the given index, without filling in any of its contents that can change later.
+    // NOTE: Only the parts that are fixed when a value is created are read here, so only those can't form cycles.
+    fn allocate(&mut self, index: usize) -> Result<Value<'gc>, Error> {
+        let heap_value = self.heap_value(index)?;
+
+        if let Some(value) = &self.values[index] {
+            return Ok(value.clone());
+        }
+
+        if self.allocating[index] {
+            return Err(Error::new(INVALID_SNAPSHOT));
+        }
+
+        self.allocating[index] = true;
+
+        let value = match heap_value {
+            SnapshotHeapValue::Object { class, .. } => {
+                let class = match class {
+                    Some(class) => Some(self.read_class(class)?),
+                    None => None,
+                };
+
+                Value::Object(Object::new(self.ctx, class))
+            }
+            SnapshotHeapValue::Class { name, base, .. } => {
+                let name = self.ctx.symbol(name);
+                let class = match base {
+                    Some(base) => Class::with_base(self.ctx, name, self.read_class(base)?),
+                    None => Class::new(self.ctx, name),
+                };
+
+                Value::Class(class)
+            }
+            SnapshotHeapValue::Array(_) => Value::with_vec(self.ctx, Vec::new()),
+            SnapshotHeapValue::Map(_) => Value::Map(Map::new(self.ctx)),
+            SnapshotHeapValue::Closure { function, upvalues } => {
+                let function = self.read_function(*function)?;
+                let upvalues = upvalues
+                    .iter()
+                    .map(|upvalue| self.allocate_upvalue(*upvalue))
+                    .collect::<Result<_, _>>()?;
+
+                Value::FnClosure(FnClosure::new(self.ctx, function, upvalues))
+            }
+            SnapshotHeapValue::Bound { receiver, function } => {
+                let receiver = self.read_value(receiver)?;
+                let function = self.read_value(function)?;
+
+                Value::FnBound(FnBound::new(self.ctx, receiver, function))
+            }
+            SnapshotHeapValue::Upvalue(_) => return Err(Error::new(INVALID_SNAPSHOT)),
+        };
+
+        self.values[index] = Some(value.clone());
+        self.pending.push(index);
+
+        Ok(value)
+    }
+
+    fn allocate_upvalue(&mut self, index: usize) -> Result<Upvalue<'gc>, Error> {
+        if !matches!(self.heap_value(index)?, SnapshotHeapValue::Upvalue(_)) {
+            return Err(Error::new(INVALID_SNAPSHOT));
+        }
+
+        if let Some(upvalue) = &self.upvalues[index] {
+            return Ok(upvalue.clone());
+        }
+
+        let upvalue = Upvalue::new_closed(self.ctx, Value::Null);
+        self.upvalues[index] = Some(upvalue.clone());
+        self.pending.push(index);
+
+        Ok(upvalue)
+    }
+
+    /// Fill in the contents of every value that was allocated, which may allocate more values as it goes.
+    fn fill(&mut self) -> Result<(), Error> {
+        while let Some(index) = self.pending.pop() {
+            self.fill_value(index)?;
+        }
+
+        Ok(())
+    }
+
+    fn fill_value(&mut self, index: usize) -> Result<(), Error> {
+        if self.filled[index] {
+            return Ok(());
+        }
+
+        self.filled[index] = true;
+
+        match self.heap_value(index)? {
+            SnapshotHeapValue::Object { fields, .. } => {
+                if let Some(Value::Object(object)) = self.values[index].clone() {
+                    self.fill_fields(&object, fields)?;
+                }
+            }
+            SnapshotHeapValue::Class {
+                base, methods, fields, ..
+            } => {
+                let class = self.read_class(&SnapshotValue::Reference(index))?;
+
+                // NOTE: The base is filled in first, so methods it gained after being allocated are inherited too.
+                if let Some(base) = base {
+                    if let SnapshotValue::Reference(base_index) = base {
+                        self.fill_value(*base_index)?;
+                    }
+
+                    let new = self.ctx.symbol(NEW);
+                    for (name, method) in self.read_class(base)?.methods() {
+                        if name != new {
+                            class.set_method(self.ctx, name, method);
+                        }
+                    }
+                }
+
+                for (name, method) in self.read_entries(methods)? {
+                    class.set_method(self.ctx, name, method);
+                }
+
+                self.fill_fields(&class, fields)?;
+            }
+            SnapshotHeapValue::Array(elements) => {
+                if let Some(Value::Array(array)) = self.values[index].clone() {
+                    for element in elements {
+                        let element = self.read_value(element)?;
+                        array.push(self.ctx, element);
+                    }
+                }
+            }
+            SnapshotHeapValue::Map(entries) => {
+                if let Some(Value::Map(map)) = self.values[index].clone() {
+                    for (key, value) in entries {
+                        let key = self.read_map_key(key);
+                        let value = self.read_value(value)?;
+                        map.insert(self.ctx, key, value);
+                    }
+                }
+            }
+            SnapshotHeapValue::Upvalue(value) => {
+                if let Some(upvalue) = self.upvalues[index].clone() {
+                    let value = self.read_value(value)?;
+                    upvalue.close(self.ctx, value);
+                }
+            }
+            SnapshotHeapValue::Closure { .. } | SnapshotHeapValue::Bound { .. } => {}
+        }
+
+        Ok(())
+    }
+
+    fn fill_fields(&mut self, object: &Object<'gc>, fields: &[(String, SnapshotValue)]) -> Result<(), Error> {
+        for (name, value) in self.read_entries(fields)? {
+            object.set_field(self.ctx, name, value);
+        }
+
+        Ok(())
+    }
+
+    fn read_map_key(&self, key: &SnapshotMapKey) -> MapKey {
+        match key {
+            SnapshotMapKey::Null => MapKey::Null,
+            SnapshotMapKey::Unit => MapKey::Unit,
+            SnapshotMapKey::Bool(bool) => MapKey::Bool(*bool),
+            SnapshotMapKey::Int(int) => MapKey::Int(*int),
+            SnapshotMapKey::String(string) => MapKey::String(string.as_str().into()),
+            SnapshotMapKey::Symbol(symbol) => MapKey::Symbol(self.ctx.symbol(symbol)),
+            SnapshotMapKey::Tuple(items) => MapKey::Tuple(items.iter().map(|item| self.read_map_key(item)).collect()),
+        }
+    }
+}
+
+fn builtin_classes<'gc, 's, L>(state: &'s State<'gc, L>) -> impl Iterator<Item = &'s Class<'gc>> {
+    std::iter::once(&state.any_class)
+        .chain(std::iter::once(&state.module_class))
+        .chain(state.value_class_mapping.values())
+}
+
+fn is_same_method<'gc>(lhs: &Value<'gc>, rhs: &Value<'gc>) -> bool {
+    match (lhs, rhs) {
+        (Value::FnNative(lhs), Value::FnNative(rhs)) => lhs.as_ptr() == rhs.as_ptr(),
+        _ => lhs == rhs,
+    }
+}
@@ -15,6 +15,10 @@ impl StackFrame {
         self.start
     }
 
+    pub fn end(self) -> usize {
+        self.end
+    }
+
     pub fn range(self) -> Range<usize> {
         self.start..self.end
     }
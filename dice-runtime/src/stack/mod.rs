@@ -2,33 +2,44 @@ use std::ops::{Index, IndexMut};
 
 use gc_arena::Collect;
 
+use dice_core::error::{codes::STACK_OVERFLOW, Error};
+
 pub use frame::*;
 
 use crate::value::Value;
 
 mod frame;
 
-// NOTE: Allocate 1MB of stack space, this is 65,536 values when sizeof(Value) == 16
-const MAX_STACK_SIZE: usize = (1024 * 1024) / std::mem::size_of::<Value>();
-const MAX_STACK_COUNT: () = assert_eq!(MAX_STACK_SIZE, 65536);
+// NOTE: Allow up to 1MB of stack space by default, this is 65,536 values when sizeof(Value) == 16
+pub const DEFAULT_MAX_STACK_SIZE: usize = (1024 * 1024) / std::mem::size_of::<Value>();
+const INITIAL_STACK_SIZE: usize = 1024;
 
+/// A stack of values which grows as needed, up to its maximum size.
 #[derive(Collect)]
 #[collect(no_drop)]
 pub struct Stack<'gc> {
     values: Vec<Value<'gc>>,
     stack_ptr: usize,
+    max_size: usize,
 }
 
 impl<'gc> Stack<'gc> {
     #[inline]
     pub fn push(&mut self, value: Value<'gc>) {
-        self.values[self.stack_ptr] = value;
+        // NOTE: Pushes are bounded by the code of the function being run, so only reserving slots checks the maximum.
+        if self.stack_ptr < self.values.len() {
+            self.values[self.stack_ptr] = value;
+        } else {
+            self.values.push(value);
+        }
+
         self.stack_ptr = self.stack_ptr.wrapping_add(1);
     }
 
     pub fn push_multiple(&mut self, values: &[Value<'gc>]) {
         let stack_ptr_start = self.stack_ptr;
         self.stack_ptr += values.len();
+        self.grow_to(self.stack_ptr);
         let splice_range = (stack_ptr_start..self.stack_ptr).zip(values).rev();
 
         for (index, value) in splice_range {
@@ -54,15 +65,18 @@ impl<'gc> Stack<'gc> {
         result
     }
 
-    pub fn reserve_slots(&mut self, count: usize) -> StackFrame {
+    pub fn reserve_slots(&mut self, count: usize) -> Result<StackFrame, Error> {
         let start = self.stack_ptr;
         let new_stack_ptr = self.stack_ptr.wrapping_add(count);
 
-        self.stack_ptr = new_stack_ptr;
+        if new_stack_ptr > self.max_size {
+            return Err(Error::new(STACK_OVERFLOW));
+        }
 
-        debug_assert!(self.stack_ptr < MAX_STACK_SIZE, "Stack Overflowed");
+        self.grow_to(new_stack_ptr);
+        self.stack_ptr = new_stack_ptr;
 
-        StackFrame::new(start, new_stack_ptr)
+        Ok(StackFrame::new(start, new_stack_ptr))
     }
 
     pub fn release_stack_frame(&mut self, frame: StackFrame) {
@@ -74,17 +88,65 @@ impl<'gc> Stack<'gc> {
         self.stack_ptr = new_stack_ptr;
 
         // NOTE: If the stack ptr is greater than the stack size, the stack ptr underflowed.
-        debug_assert!(self.stack_ptr < MAX_STACK_SIZE, "Stack Underflowed")
+        debug_assert!(self.stack_ptr <= self.values.len(), "Stack Underflowed")
+    }
+
+    /// Drop every value above the given stack pointer.
+    pub fn truncate(&mut self, stack_ptr: usize) {
+        if stack_ptr < self.stack_ptr {
+            for value in &mut self.values[stack_ptr..self.stack_ptr] {
+                *value = Value::Null;
+            }
+
+            self.stack_ptr = stack_ptr;
+        }
+    }
+
+    /// Grow or shrink the frame on top of the stack to the given length, clearing any slots it releases.
+    pub fn resize_frame(&mut self, frame: StackFrame, length: usize) -> StackFrame {
+        debug_assert_eq!(
+            frame.end(),
+            self.stack_ptr,
+            "Only the frame on top of the stack can be resized."
+        );
+
+        let resized = StackFrame::new(frame.start(), frame.start().wrapping_add(length));
+
+        if resized.end() < frame.end() {
+            for value in &mut self.values[resized.end()..frame.end()] {
+                *value = Value::Null;
+            }
+        }
+
+        self.grow_to(resized.end());
+        self.stack_ptr = resized.end();
+
+        resized
+    }
+
+    pub fn set_max_size(&mut self, max_size: usize) {
+        self.max_size = max_size;
+    }
+
+    fn grow_to(&mut self, length: usize) {
+        if length > self.values.len() {
+            self.values.resize(length, Value::Null);
+        }
+    }
+
+    #[inline]
+    pub fn stack_ptr(&self) -> usize {
+        self.stack_ptr
     }
 
     // NOTE: Returns the value offset from the top of the stack.
     #[inline]
-    pub fn peek_mut(&mut self, offset: usize) -> &mut Value {
+    pub fn peek_mut(&mut self, offset: usize) -> &mut Value<'gc> {
         &mut self.values[self.stack_ptr.wrapping_sub(offset).wrapping_sub(1)]
     }
 
     #[inline]
-    pub fn peek(&self, offset: usize) -> &Value {
+    pub fn peek(&self, offset: usize) -> &Value<'gc> {
         &self.values[self.stack_ptr.wrapping_sub(offset).wrapping_sub(1)]
     }
 
@@ -133,8 +195,9 @@ impl IndexMut<StackFrame> for Stack<'_> {
 impl Default for Stack<'_> {
     fn default() -> Self {
         Self {
-            values: vec![Value::Null; MAX_STACK_SIZE],
+            values: vec![Value::Null; INITIAL_STACK_SIZE],
             stack_ptr: 0,
+            max_size: DEFAULT_MAX_STACK_SIZE,
         }
     }
 }
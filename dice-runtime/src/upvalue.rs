@@ -20,15 +20,23 @@ impl<'gc> Upvalue<'gc> {
         Self(Gc::new(ctx.mutation, RefLock::new(UpvalueState::Open(slot))))
     }
 
+    pub fn new_closed(ctx: &RuntimeContext<'gc>, value: Value<'gc>) -> Self {
+        Self(Gc::new(ctx.mutation, RefLock::new(UpvalueState::Closed(value))))
+    }
+
     pub fn close(&self, ctx: &RuntimeContext<'gc>, value: Value<'gc>) {
         *self.0.borrow_mut(ctx.mutation) = UpvalueState::Closed(value);
     }
 
-    pub fn state_mut(&self, ctx: &RuntimeContext<'gc>) -> RefMut<'gc, UpvalueState> {
+    pub fn state_mut(&self, ctx: &RuntimeContext<'gc>) -> RefMut<'gc, UpvalueState<'gc>> {
         self.0.borrow_mut(ctx.mutation)
     }
 
-    pub fn state(&self) -> Ref<'gc, UpvalueState> {
+    pub fn state(&self) -> Ref<'gc, UpvalueState<'gc>> {
         self.0.borrow()
     }
+
+    pub(crate) fn as_ptr(&self) -> *const () {
+        Gc::as_ptr(self.0) as *const ()
+    }
 }
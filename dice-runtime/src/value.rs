@@ -8,7 +8,8 @@ pub use class::*;
 use dice_core::error::{
     codes::{
         INVALID_ARRAY_CONVERSION, INVALID_BOOL_CONVERSION, INVALID_CLASS_CONVERSION, INVALID_FLOAT_CONVERSION,
-        INVALID_INT_CONVERSION, INVALID_OBJECT_CONVERSION, INVALID_STRING_CONVERSION, INVALID_SYMBOL_CONVERSION,
+        INVALID_INT_CONVERSION, INVALID_MAP_CONVERSION, INVALID_OBJECT_CONVERSION, INVALID_STRING_CONVERSION,
+        INVALID_SYMBOL_CONVERSION,
     },
     Error,
 };
@@ -16,7 +17,9 @@ pub use fn_bound::*;
 pub use fn_closure::*;
 pub use fn_native::*;
 pub use fn_script::*;
+pub use map::*;
 pub use object::*;
+pub use owned::*;
 pub use string::*;
 pub use symbol::*;
 
@@ -28,7 +31,10 @@ mod fn_bound;
 mod fn_closure;
 mod fn_native;
 mod fn_script;
+mod map;
 mod object;
+mod owned;
+mod shape;
 mod string;
 mod symbol;
 
@@ -36,7 +42,9 @@ pub type ValueMap<'gc> = HashMap<Symbol, Value<'gc>, BuildHasherDefault<AHasher>
 
 #[derive(Clone, Collect)]
 #[collect(no_drop)]
+#[derive(Default)]
 pub enum Value<'gc> {
+    #[default]
     Null,
     Unit,
     Bool(bool),
@@ -44,26 +52,34 @@ pub enum Value<'gc> {
     Float(f64),
     FnScript(FnScript),
     FnClosure(FnClosure<'gc>),
-    FnNative(FnNative<'static>),
+    FnNative(FnNative),
     FnBound(FnBound<'gc>),
     Array(Array<'gc>),
+    Map(Map<'gc>),
     String(String),
     Symbol(Symbol),
     Object(Object<'gc>),
     Class(Class<'gc>),
 }
 
+// NOTE: Every variant holds at most a single pointer or a primitive, which keeps values at two words on the stack.
+// Strings are a pointer to a shared buffer rather than a buffer of their own for the same reason.
+const _: () = assert!(
+    std::mem::size_of::<Value>() == 16,
+    "Values should stay two words in size."
+);
+
 impl<'gc> Value<'gc> {
     pub fn with_string(string: impl Into<String>) -> Self {
         Self::String(string.into())
     }
 
-    pub fn with_symbol(string: impl Into<Symbol>) -> Self {
-        Self::Symbol(string.into())
+    pub fn with_symbol(symbol: Symbol) -> Self {
+        Self::Symbol(symbol)
     }
 
-    pub fn with_native_fn(native_fn: impl Into<NativeFn<'static>>) -> Self {
-        Self::FnNative(FnNative::new(native_fn.into()))
+    pub fn with_native_fn(native_fn: NativeFn) -> Self {
+        Self::FnNative(FnNative::new(native_fn))
     }
 
     pub fn with_vec(ctx: &RuntimeContext<'gc>, vec: Vec<Value<'gc>>) -> Self {
@@ -98,6 +114,13 @@ impl<'gc> Value<'gc> {
         }
     }
 
+    pub fn as_map(&self) -> Result<&Map<'gc>, Error> {
+        match self {
+            Value::Map(map) => Ok(map),
+            _ => Err(Error::new(INVALID_MAP_CONVERSION)),
+        }
+    }
+
     pub fn as_string(&self) -> Result<&String, Error> {
         match self {
             Value::String(string) => Ok(string),
@@ -107,8 +130,7 @@ impl<'gc> Value<'gc> {
 
     pub fn as_symbol(&self) -> Result<Symbol, Error> {
         match self {
-            Value::Symbol(symbol) => Ok(symbol.clone()),
-            // Value::String(string) => Ok(string.into()),
+            Value::Symbol(symbol) => Ok(*symbol),
             _ => Err(Error::new(INVALID_SYMBOL_CONVERSION)),
         }
     }
@@ -118,6 +140,7 @@ impl<'gc> Value<'gc> {
             Value::Object(object) => Ok(object),
             Value::Class(class) => Ok(&(**class)),
             Value::Array(array) => Ok(&(**array)),
+            Value::Map(map) => Ok(&(**map)),
             _ => Err(Error::new(INVALID_OBJECT_CONVERSION)),
         }
     }
@@ -141,6 +164,7 @@ impl<'gc> Value<'gc> {
             Value::FnNative(_) => ValueKind::Function,
             Value::FnBound(_) => ValueKind::Function,
             Value::Array(_) => ValueKind::Array,
+            Value::Map(_) => ValueKind::Map,
             Value::String(_) => ValueKind::String,
             Value::Symbol(_) => ValueKind::Symbol,
             Value::Object(_) => ValueKind::Object,
@@ -149,12 +173,6 @@ impl<'gc> Value<'gc> {
     }
 }
 
-impl<'gc> Default for Value<'gc> {
-    fn default() -> Self {
-        Value::Null
-    }
-}
-
 impl<'gc> PartialEq for Value<'gc> {
     #[inline(always)]
     fn eq(&self, other: &Self) -> bool {
@@ -167,6 +185,7 @@ impl<'gc> PartialEq for Value<'gc> {
             (Value::FnClosure(lhs), Value::FnClosure(rhs)) => lhs == rhs,
             (Value::FnScript(lhs), Value::FnScript(rhs)) => lhs == rhs,
             (Value::Array(lhs), Value::Array(rhs)) => lhs == rhs,
+            (Value::Map(lhs), Value::Map(rhs)) => lhs == rhs,
             (Value::String(lhs), Value::String(rhs)) => lhs == rhs,
             (Value::Symbol(lhs), Value::Symbol(rhs)) => lhs == rhs,
             (Value::Object(lhs), Value::Object(rhs)) => lhs == rhs,
@@ -176,33 +195,12 @@ impl<'gc> PartialEq for Value<'gc> {
     }
 }
 
-impl<'gc> From<NativeFn<'static>> for Value<'gc> {
-    fn from(value: NativeFn<'static>) -> Self {
+impl<'gc> From<NativeFn> for Value<'gc> {
+    fn from(value: NativeFn) -> Self {
         Value::with_native_fn(value)
     }
 }
 
-// impl<'gc> Display for Value<'gc> {
-//     fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-//         match self {
-//             Value::Null => write!(fmt, "null"),
-//             Value::Unit => write!(fmt, "Unit"),
-//             Value::Bool(bool) => bool.fmt(fmt),
-//             Value::Int(int) => int.fmt(fmt),
-//             Value::Float(float) => float.fmt(fmt),
-//             Value::FnClosure(func) => func.fmt(fmt),
-//             Value::FnScript(func) => func.fmt(fmt),
-//             Value::FnNative(func) => func.fmt(fmt),
-//             Value::FnBound(func) => func.fmt(fmt),
-//             Value::Array(list) => list.fmt(fmt),
-//             Value::String(string) => string.fmt(fmt),
-//             Value::Symbol(string) => string.fmt(fmt),
-//             Value::Object(object) => object.fmt(fmt),
-//             Value::Class(class) => class.fmt(fmt),
-//         }
-//     }
-// }
-
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Collect)]
 #[collect(require_static)]
 #[repr(u8)]
@@ -214,6 +212,7 @@ pub enum ValueKind {
     Float,
     Function,
     Array,
+    Map,
     String,
     Symbol,
     Object,
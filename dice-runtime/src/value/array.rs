@@ -3,7 +3,7 @@ use std::{
     ops::Deref,
 };
 
-use gc_arena::{Collect, Gc, lock::RefLock};
+use gc_arena::{lock::RefLock, Collect, Gc};
 
 use crate::{
     runtime::RuntimeContext,
@@ -17,11 +17,11 @@ pub struct Array<'gc> {
 }
 
 impl<'gc> Array<'gc> {
-    pub fn elements(&self) -> Ref<'gc, [Value]> {
+    pub fn elements(&self) -> Ref<'gc, [Value<'gc>]> {
         Ref::map(self.inner.array.borrow(), |array| array.as_slice())
     }
 
-    pub fn elements_mut(&self, ctx: &RuntimeContext<'gc>) -> RefMut<'gc, [Value]> {
+    pub fn elements_mut(&self, ctx: &RuntimeContext<'gc>) -> RefMut<'gc, [Value<'gc>]> {
         RefMut::map(self.inner.array.borrow_mut(ctx.mutation), |array| array.as_mut_slice())
     }
 
@@ -33,6 +33,10 @@ impl<'gc> Array<'gc> {
         self.inner.array.borrow_mut(ctx.mutation).pop()
     }
 
+    pub(crate) fn as_ptr(&self) -> *const () {
+        Gc::as_ptr(self.inner) as *const ()
+    }
+
     pub fn from_vec(ctx: &RuntimeContext<'gc>, value: Vec<Value<'gc>>) -> Self {
         Self {
             inner: Gc::new(
@@ -46,17 +50,6 @@ impl<'gc> Array<'gc> {
     }
 }
 
-// impl Display for Array<'_> {
-//     fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-//         let items = self
-//             .inner
-//             .array
-//             .borrow()
-//             .iter()
-//             .map(|value| value.to_string())
-//             .collect::<Vec<_>>()
-//             .join(", ");
-
 //         write!(fmt, "[{}]", items)
 //     }
 // }
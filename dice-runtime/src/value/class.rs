@@ -5,15 +5,15 @@ use std::{
 };
 
 use ahash::AHasher;
-use gc_arena::{Collect, Gc, lock::RefLock};
+use gc_arena::{lock::RefLock, Collect, Gc};
 
 use dice_core::protocol::class::NEW;
 
+use crate::type_id::TypeId;
 use crate::{
     runtime::RuntimeContext,
-    value::{Object, symbol::Symbol, Value, ValueKind, ValueMap},
+    value::{shape::Shape, symbol::Symbol, Object, Value, ValueKind},
 };
-use crate::type_id::TypeId;
 
 #[derive(Clone, PartialEq, Eq, Collect)]
 #[collect(no_drop)]
@@ -30,7 +30,8 @@ impl<'gc> Class<'gc> {
         let inner = ClassInner {
             instance_type_id,
             type_ids,
-            methods: Gc::new(ctx.mutation, RefLock::new(HashMap::default())),
+            methods: Gc::new(ctx.mutation, RefLock::new(ClassMethods::default())),
+            instance_shape: Shape::root(),
             object: Object::new(ctx, None),
             name,
             base: None,
@@ -42,14 +43,14 @@ impl<'gc> Class<'gc> {
     }
 
     pub fn with_base(ctx: &RuntimeContext<'gc>, name: Symbol, base: Class<'gc>) -> Self {
-        let methods = base
-            .inner
-            .methods
-            .borrow()
-            .iter()
-            .filter(|(name, _)| ctx.interner.resolve(**name) != Some(NEW))
-            .map(|(name, value)| (name.clone(), value.clone()))
-            .collect::<HashMap<_, _, _>>();
+        let new = ctx.symbol(NEW);
+        let mut methods = ClassMethods::default();
+        for (name, value) in base.methods() {
+            if name != new {
+                methods.insert(name, value);
+            }
+        }
+
         let instance_type_id = TypeId::new();
         let mut type_ids: HashSet<_, _> = base.inner.type_ids.clone();
         type_ids.insert(instance_type_id);
@@ -59,6 +60,7 @@ impl<'gc> Class<'gc> {
             type_ids,
             name,
             methods: Gc::new(ctx.mutation, RefLock::new(methods)),
+            instance_shape: Shape::root(),
             object: base.inner.object.deep_clone(ctx),
             base: Some(base),
         };
@@ -68,8 +70,8 @@ impl<'gc> Class<'gc> {
         }
     }
 
-    pub fn derive(&self, ctx: &RuntimeContext<'gc>, name: impl Into<Symbol>) -> Self {
-        Self::with_base(ctx, name.into(), self.clone())
+    pub fn derive(&self, ctx: &RuntimeContext<'gc>, name: Symbol) -> Self {
+        Self::with_base(ctx, name, self.clone())
     }
 
     pub fn is_class(&self, class: &Class) -> bool {
@@ -79,48 +81,72 @@ impl<'gc> Class<'gc> {
     }
 
     pub fn name(&self) -> Symbol {
-        self.inner.name.clone()
+        self.inner.name
     }
 
     pub fn instance_type_id(&self) -> TypeId {
         self.inner.instance_type_id
     }
 
-    pub fn method(&self, name: impl Into<Symbol>) -> Option<Value<'gc>> {
-        let name = name.into();
-        self.inner.methods.borrow().get(&name).cloned()
+    /// The shape instances start with, so instances that add the same fields share their shapes.
+    pub(crate) fn instance_shape(&self) -> Shape {
+        self.inner.instance_shape.clone()
     }
 
-    pub fn set_method(&self, ctx: RuntimeContext<'gc>, name: impl Into<Symbol>, method: impl Into<Value<'gc>>) {
-        let method = method.into();
+    pub fn method(&self, name: Symbol) -> Option<Value<'gc>> {
+        let methods = self.inner.methods.borrow();
+
+        methods.slots.get(&name).map(|slot| methods.values[*slot].clone())
+    }
+
+    /// The slot the method is stored in, which stays the same for as long as the class exists.
+    pub(crate) fn method_slot(&self, name: Symbol) -> Option<usize> {
+        self.inner.methods.borrow().slots.get(&name).copied()
+    }
+
+    pub(crate) fn method_at(&self, slot: usize) -> Value<'gc> {
+        self.inner.methods.borrow().values[slot].clone()
+    }
+
+    /// Changes every time a method is set, so anything cached about the class' methods can be invalidated.
+    pub(crate) fn method_version(&self) -> u64 {
+        self.inner.methods.borrow().version
+    }
 
+    pub fn set_method(&self, ctx: &RuntimeContext<'gc>, name: Symbol, method: Value<'gc>) {
         if method.kind() != ValueKind::Function {
             panic!("Provided value is not a function.");
         }
 
-        self.inner.methods.borrow_mut(ctx.mutation).insert(name.into(), method);
+        self.inner.methods.borrow_mut(ctx.mutation).insert(name, method);
     }
 
     pub fn methods(&self) -> Vec<(Symbol, Value<'gc>)> {
         // TODO: Make this handle multiple, conflicting methods when traits are added.
-        self.inner
-            .methods
-            .borrow()
+        let methods = self.inner.methods.borrow();
+
+        methods
+            .slots
             .iter()
-            .map(|(key, value)| (key.clone(), value.clone()))
+            .map(|(key, slot)| (*key, methods.values[*slot].clone()))
             .collect::<Vec<_>>()
     }
 
     pub fn base(&self) -> Option<Class<'gc>> {
         self.inner.base.clone()
     }
+
+    pub(crate) fn as_ptr(&self) -> *const () {
+        Gc::as_ptr(self.inner) as *const ()
+    }
 }
 
 #[derive(Collect)]
 #[collect(no_drop)]
 struct ClassInner<'gc> {
     name: Symbol,
-    methods: Gc<'gc, RefLock<ValueMap<'gc>>>,
+    methods: Gc<'gc, RefLock<ClassMethods<'gc>>>,
+    instance_shape: Shape,
     object: Object<'gc>,
     #[collect(require_static)]
     instance_type_id: TypeId,
@@ -129,6 +155,29 @@ struct ClassInner<'gc> {
     base: Option<Class<'gc>>,
 }
 
+#[derive(Default, Collect)]
+#[collect(no_drop)]
+struct ClassMethods<'gc> {
+    #[collect(require_static)]
+    slots: HashMap<Symbol, usize, BuildHasherDefault<AHasher>>,
+    values: Vec<Value<'gc>>,
+    version: u64,
+}
+
+impl<'gc> ClassMethods<'gc> {
+    fn insert(&mut self, name: Symbol, method: Value<'gc>) {
+        match self.slots.get(&name) {
+            Some(slot) => self.values[*slot] = method,
+            None => {
+                self.slots.insert(name, self.values.len());
+                self.values.push(method);
+            }
+        }
+
+        self.version += 1;
+    }
+}
+
 impl<'gc> Deref for Class<'gc> {
     type Target = Object<'gc>;
 
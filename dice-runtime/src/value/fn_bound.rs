@@ -1,6 +1,6 @@
-use gc_arena::{Collect, Gc, Mutation};
+use gc_arena::{Collect, Gc};
 
-use crate::value::Value;
+use crate::{runtime::RuntimeContext, value::Value};
 
 #[derive(Clone, Collect)]
 #[collect(no_drop)]
@@ -9,9 +9,9 @@ pub struct FnBound<'gc> {
 }
 
 impl<'gc> FnBound<'gc> {
-    pub fn new(mutation: &Mutation<'gc>, receiver: Value<'gc>, function: Value<'gc>) -> Self {
+    pub fn new(ctx: &RuntimeContext<'gc>, receiver: Value<'gc>, function: Value<'gc>) -> Self {
         Self {
-            inner: Gc::new(mutation, FnBoundInner { receiver, function }),
+            inner: Gc::new(ctx.mutation, FnBoundInner { receiver, function }),
         }
     }
 
@@ -22,6 +22,10 @@ impl<'gc> FnBound<'gc> {
     pub fn function(&self) -> Value<'gc> {
         self.inner.function.clone()
     }
+
+    pub(crate) fn as_ptr(&self) -> *const () {
+        Gc::as_ptr(self.inner) as *const ()
+    }
 }
 
 impl PartialEq for FnBound<'_> {
@@ -30,21 +34,9 @@ impl PartialEq for FnBound<'_> {
     }
 }
 
-// impl Display for FnBound<'_> {
-//     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-//         write!(f, "FnBound{{{}}}", self.inner.function)
-//     }
-// }
-
 #[derive(Collect)]
 #[collect(no_drop)]
 struct FnBoundInner<'gc> {
     receiver: Value<'gc>,
     function: Value<'gc>,
 }
-
-// impl Debug for FnBoundInner<'_> {
-//     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-//         std::fmt::Debug::fmt(&self.function, f)
-//     }
-// }
@@ -1,6 +1,6 @@
 use super::FnScript;
-use crate::upvalue::Upvalue;
-use gc_arena::{Collect, Gc, Mutation};
+use crate::{runtime::RuntimeContext, upvalue::Upvalue};
+use gc_arena::{Collect, Gc};
 
 #[derive(Clone, Collect)]
 #[collect(no_drop)]
@@ -9,9 +9,9 @@ pub struct FnClosure<'gc> {
 }
 
 impl<'gc> FnClosure<'gc> {
-    pub fn new(mutation: &Mutation<'gc>, fn_script: FnScript, upvalues: Box<[Upvalue<'gc>]>) -> Self {
+    pub fn new(ctx: &RuntimeContext<'gc>, fn_script: FnScript, upvalues: Box<[Upvalue<'gc>]>) -> Self {
         Self {
-            inner: Gc::new(mutation, FnClosureInner { fn_script, upvalues }),
+            inner: Gc::new(ctx.mutation, FnClosureInner { fn_script, upvalues }),
         }
     }
 
@@ -20,15 +20,13 @@ impl<'gc> FnClosure<'gc> {
     }
 
     pub fn upvalues(&self) -> &[Upvalue<'gc>] {
-        &*self.inner.upvalues
+        &self.inner.upvalues
     }
-}
 
-// impl Debug for FnClosure<'_> {
-//     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-//         write!(f, "{:?}", self.inner)
-//     }
-// }
+    pub(crate) fn as_ptr(&self) -> *const () {
+        Gc::as_ptr(self.inner) as *const ()
+    }
+}
 
 impl PartialEq for FnClosure<'_> {
     fn eq(&self, other: &Self) -> bool {
@@ -37,21 +35,9 @@ impl PartialEq for FnClosure<'_> {
     }
 }
 
-// impl Display for FnClosure<'_> {
-//     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-//         write!(f, "closure{{{}}}", self.inner.fn_script)
-//     }
-// }
-
 #[derive(Collect)]
 #[collect(no_drop)]
 struct FnClosureInner<'gc> {
     fn_script: FnScript,
     upvalues: Box<[Upvalue<'gc>]>,
 }
-
-// impl Debug for FnClosureInner<'_> {
-//     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-//         write!(f, "Closure{{{}}}", self.fn_script)
-//     }
-// }
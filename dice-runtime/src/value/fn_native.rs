@@ -7,37 +7,49 @@ use gc_arena::Collect;
 
 use dice_core::error::Error;
 
-use crate::value::Value;
+use crate::{api::Runtime, value::Value};
 
-// pub type NativeFn<'gc> = Box<dyn Fn(&mut dyn Runtime<'gc>, &[Value]) -> Result<Value<'gc>, Error>>;
-pub type NativeFn<'gc> = Box<dyn Fn(&[Value]) -> Result<Value<'gc>, Error>>;
+pub type NativeFn = Box<dyn for<'gc> Fn(&mut dyn Runtime<'gc>, &[Value<'gc>]) -> Result<Value<'gc>, Error>>;
+
+/// Box a closure as a native function.
+// NOTE: Passing the closure through the bound lets the compiler infer a signature that works for any arena lifetime.
+pub fn native_fn<F>(function: F) -> NativeFn
+where
+    F: for<'gc> Fn(&mut dyn Runtime<'gc>, &[Value<'gc>]) -> Result<Value<'gc>, Error> + 'static,
+{
+    Box::new(function)
+}
 
 #[derive(Clone, Collect)]
 #[collect(require_static)]
-pub struct FnNative<'gc> {
-    inner: Rc<NativeFn<'gc>>,
+pub struct FnNative {
+    inner: Rc<NativeFn>,
 }
 
-impl<'gc> FnNative<'gc> {
-    pub fn new(native_fn: NativeFn<'gc>) -> Self {
+impl FnNative {
+    pub fn new(native_fn: NativeFn) -> Self {
         Self {
             inner: Rc::new(native_fn),
         }
     }
 
     #[inline]
-    pub fn call(&self, args: &[Value]) -> Result<Value<'gc>, Error> {
-        (*self.inner)(args)
+    pub fn call<'gc>(&self, runtime: &mut dyn Runtime<'gc>, args: &[Value<'gc>]) -> Result<Value<'gc>, Error> {
+        (*self.inner)(runtime, args)
+    }
+
+    pub(crate) fn as_ptr(&self) -> *const () {
+        Rc::as_ptr(&self.inner) as *const ()
     }
 }
 
-impl Display for FnNative<'_> {
+impl Display for FnNative {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "native_fn")
     }
 }
 
-impl Debug for FnNative<'_> {
+impl Debug for FnNative {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "native_fn")
     }
@@ -1,5 +1,6 @@
-use super::Symbol;
-use dice_bytecode::Bytecode;
+use super::{String, Symbol};
+use crate::{inline_cache::InlineCaches, runtime::RuntimeContext};
+use dice_bytecode::{Bytecode, ConstantValue};
 use gc_arena::Collect;
 use std::rc::Rc;
 
@@ -10,12 +11,21 @@ pub struct FnScript {
 }
 
 impl FnScript {
-    pub fn new(name: impl Into<Symbol>, bytecode: Bytecode, id: uuid::Uuid) -> Self {
+    pub fn new(ctx: &RuntimeContext<'_>, name: Symbol, bytecode: Bytecode, id: uuid::Uuid) -> Self {
+        let inline_caches = InlineCaches::new(bytecode.cache_count());
+        let constants = bytecode
+            .constants()
+            .iter()
+            .map(|constant| PreparedConstant::new(ctx, constant))
+            .collect();
+
         Self {
             inner: Rc::new(FnScriptInner {
                 bytecode,
-                name: name.into(),
+                name,
                 id,
+                inline_caches,
+                constants,
             }),
         }
     }
@@ -25,7 +35,20 @@ impl FnScript {
     }
 
     pub fn name(&self) -> Symbol {
-        self.inner.name.clone()
+        self.inner.name
+    }
+
+    pub fn id(&self) -> uuid::Uuid {
+        self.inner.id
+    }
+
+    pub(crate) fn inline_caches(&self) -> &InlineCaches {
+        &self.inner.inline_caches
+    }
+
+    /// The value of a string or function constant, which was created along with the function.
+    pub(crate) fn prepared_constant(&self, index: usize) -> Option<&PreparedConstant> {
+        self.inner.constants.get(index).and_then(Option::as_ref)
     }
 }
 
@@ -35,12 +58,6 @@ impl PartialEq for FnScript {
     }
 }
 
-// impl Display for FnScript {
-//     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-//         write!(f, "{}", self.inner.name)
-//     }
-// }
-
 #[derive(Debug, Collect)]
 #[collect(no_drop)]
 struct FnScriptInner {
@@ -49,4 +66,31 @@ struct FnScriptInner {
     bytecode: Bytecode,
     #[collect(require_static)]
     id: uuid::Uuid,
+    #[collect(require_static)]
+    inline_caches: InlineCaches,
+    #[collect(require_static)]
+    constants: Box<[Option<PreparedConstant>]>,
+}
+
+/// A constant that would otherwise be interned or wrapped again every time it's loaded.
+#[derive(Debug)]
+pub(crate) enum PreparedConstant {
+    String(String),
+    FnScript(FnScript),
+}
+
+impl PreparedConstant {
+    fn new(ctx: &RuntimeContext<'_>, constant: &ConstantValue) -> Option<Self> {
+        match constant {
+            ConstantValue::String(string) => Some(Self::String(ctx.intern_string(string))),
+            // NOTE: Sharing the function between every closure created from it also shares its inline caches.
+            ConstantValue::Function(function) => Some(Self::FnScript(FnScript::new(
+                ctx,
+                ctx.symbol(&function.name),
+                function.bytecode.clone(),
+                function.id,
+            ))),
+            _ => None,
+        }
+    }
 }
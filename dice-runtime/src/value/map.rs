@@ -0,0 +1,142 @@
+use std::{
+    cell::{Ref, RefMut},
+    collections::HashMap,
+    hash::BuildHasherDefault,
+    ops::Deref,
+    rc::Rc,
+};
+
+use ahash::AHasher;
+use dice_core::error::{codes::INVALID_MAP_KEY_CONVERSION, Error};
+use gc_arena::{lock::RefLock, Collect, Gc};
+
+use crate::{
+    runtime::RuntimeContext,
+    value::{Object, String, Symbol, Value},
+};
+
+// NOTE: Arrays can contain themselves, so the nesting of a key is capped rather than followed without end.
+const MAX_KEY_DEPTH: usize = 64;
+
+pub type MapEntries<'gc> = HashMap<MapKey, Value<'gc>, BuildHasherDefault<AHasher>>;
+
+/// A hashable snapshot of a value, used to key the entries of a map.
+// NOTE: Arrays are converted to tuples when used as a key, so later changes to the array don't affect the map.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Collect)]
+#[collect(require_static)]
+pub enum MapKey {
+    Null,
+    Unit,
+    Bool(bool),
+    Int(i64),
+    String(String),
+    Symbol(Symbol),
+    Tuple(Rc<[MapKey]>),
+}
+
+impl MapKey {
+    pub fn from_value(value: &Value) -> Result<Self, Error> {
+        Self::from_value_at_depth(value, 0)
+    }
+
+    fn from_value_at_depth(value: &Value, depth: usize) -> Result<Self, Error> {
+        let key = match value {
+            Value::Null => MapKey::Null,
+            Value::Unit => MapKey::Unit,
+            Value::Bool(bool) => MapKey::Bool(*bool),
+            Value::Int(int) => MapKey::Int(*int),
+            Value::String(string) => MapKey::String(string.clone()),
+            Value::Symbol(symbol) => MapKey::Symbol(*symbol),
+            Value::Array(_) if depth >= MAX_KEY_DEPTH => return Err(Error::new(INVALID_MAP_KEY_CONVERSION)),
+            Value::Array(array) => MapKey::Tuple(
+                array
+                    .elements()
+                    .iter()
+                    .map(|element| MapKey::from_value_at_depth(element, depth + 1))
+                    .collect::<Result<Rc<[_]>, _>>()?,
+            ),
+            _ => return Err(Error::new(INVALID_MAP_KEY_CONVERSION)),
+        };
+
+        Ok(key)
+    }
+
+    pub fn to_value<'gc>(&self, ctx: &RuntimeContext<'gc>) -> Value<'gc> {
+        match self {
+            MapKey::Null => Value::Null,
+            MapKey::Unit => Value::Unit,
+            MapKey::Bool(bool) => Value::Bool(*bool),
+            MapKey::Int(int) => Value::Int(*int),
+            MapKey::String(string) => Value::String(string.clone()),
+            MapKey::Symbol(symbol) => Value::Symbol(*symbol),
+            MapKey::Tuple(items) => Value::with_vec(ctx, items.iter().map(|item| item.to_value(ctx)).collect()),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Collect)]
+#[collect(no_drop)]
+pub struct Map<'gc> {
+    inner: Gc<'gc, MapInner<'gc>>,
+}
+
+impl<'gc> Map<'gc> {
+    pub fn new(ctx: &RuntimeContext<'gc>) -> Self {
+        Self::from_entries(ctx, MapEntries::default())
+    }
+
+    pub fn from_entries(ctx: &RuntimeContext<'gc>, entries: MapEntries<'gc>) -> Self {
+        Self {
+            inner: Gc::new(
+                ctx.mutation,
+                MapInner {
+                    entries: Gc::new(ctx.mutation, RefLock::new(entries)),
+                    object: Object::new(ctx, None),
+                },
+            ),
+        }
+    }
+
+    pub fn entries(&self) -> Ref<'gc, MapEntries<'gc>> {
+        self.inner.entries.borrow()
+    }
+
+    pub fn entries_mut(&self, ctx: &RuntimeContext<'gc>) -> RefMut<'gc, MapEntries<'gc>> {
+        self.inner.entries.borrow_mut(ctx.mutation)
+    }
+
+    pub fn get(&self, key: &MapKey) -> Option<Value<'gc>> {
+        self.entries().get(key).cloned()
+    }
+
+    pub fn insert(&self, ctx: &RuntimeContext<'gc>, key: MapKey, value: Value<'gc>) -> Option<Value<'gc>> {
+        self.entries_mut(ctx).insert(key, value)
+    }
+
+    pub fn remove(&self, ctx: &RuntimeContext<'gc>, key: &MapKey) -> Option<Value<'gc>> {
+        self.entries_mut(ctx).remove(key)
+    }
+
+    pub fn contains(&self, key: &MapKey) -> bool {
+        self.entries().contains_key(key)
+    }
+
+    pub(crate) fn as_ptr(&self) -> *const () {
+        Gc::as_ptr(self.inner) as *const ()
+    }
+}
+
+impl<'gc> Deref for Map<'gc> {
+    type Target = Object<'gc>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner.object
+    }
+}
+
+#[derive(Clone, PartialEq, Collect)]
+#[collect(no_drop)]
+struct MapInner<'gc> {
+    entries: Gc<'gc, RefLock<MapEntries<'gc>>>,
+    object: Object<'gc>,
+}
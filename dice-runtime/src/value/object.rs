@@ -1,12 +1,13 @@
-use std::cell::Ref;
-
-use gc_arena::{Collect, Gc, lock::RefLock, Mutation};
+use gc_arena::{lock::RefLock, Collect, Gc};
 
+use crate::type_id::TypeId;
 use crate::{
     runtime::RuntimeContext,
-    value::{Class, Symbol, Value, ValueMap},
+    value::{
+        shape::{Shape, MAX_SHAPE_FIELDS},
+        Class, Symbol, Value, ValueMap,
+    },
 };
-use crate::type_id::TypeId;
 
 #[derive(Clone, Collect)]
 #[collect(no_drop)]
@@ -19,12 +20,21 @@ impl<'gc> Object<'gc> {
     where
         N: Into<Option<Class<'gc>>>,
     {
+        let class = class.into();
+        let shape = class.as_ref().map_or_else(Shape::empty, Class::instance_shape);
+
         Self {
             inner: Gc::new(
                 ctx.mutation,
                 ObjectInner {
-                    class: class.into(),
-                    fields: Gc::new(ctx.mutation, RefLock::new(ValueMap::default())),
+                    class,
+                    fields: Gc::new(
+                        ctx.mutation,
+                        RefLock::new(Fields::Shaped {
+                            shape,
+                            values: Vec::new(),
+                        }),
+                    ),
                 },
             ),
         }
@@ -36,7 +46,7 @@ impl<'gc> Object<'gc> {
                 ctx.mutation,
                 ObjectInner {
                     class: self.inner.class.clone(),
-                    fields: self.inner.fields.clone(),
+                    fields: Gc::new(ctx.mutation, RefLock::new(self.inner.fields.borrow().clone())),
                 },
             ),
         }
@@ -64,19 +74,80 @@ impl<'gc> Object<'gc> {
         self.inner.class.clone()
     }
 
-    pub fn set_field(&self, mutation: &Mutation<'gc>, field_name: impl Into<Symbol>, value: impl Into<Value<'gc>>) {
-        self.inner
-            .fields
-            .borrow_mut(mutation)
-            .insert(field_name.into(), value.into());
+    pub fn set_field(&self, ctx: &RuntimeContext<'gc>, field_name: Symbol, value: Value<'gc>) {
+        let mut fields = self.inner.fields.borrow_mut(ctx.mutation);
+
+        match &mut *fields {
+            Fields::Shaped { shape, values } => match shape.slot(field_name) {
+                Some(slot) => values[slot] = value,
+                None if shape.len() < MAX_SHAPE_FIELDS => {
+                    *shape = shape.with_field(field_name);
+                    values.push(value);
+                }
+                None => {
+                    let mut dictionary = shape
+                        .fields()
+                        .iter()
+                        .copied()
+                        .zip(values.drain(..))
+                        .collect::<ValueMap>();
+                    dictionary.insert(field_name, value);
+                    *fields = Fields::Dictionary(dictionary);
+                }
+            },
+            Fields::Dictionary(dictionary) => {
+                dictionary.insert(field_name, value);
+            }
+        }
     }
 
     pub fn field(&self, field_name: Symbol) -> Option<Value<'gc>> {
-        self.inner.fields.borrow().get(&field_name).cloned()
+        match &*self.inner.fields.borrow() {
+            Fields::Shaped { shape, values } => shape.slot(field_name).map(|slot| values[slot].clone()),
+            Fields::Dictionary(dictionary) => dictionary.get(&field_name).cloned(),
+        }
+    }
+
+    /// A copy of the object's fields.
+    pub fn fields(&self) -> ValueMap<'gc> {
+        match &*self.inner.fields.borrow() {
+            Fields::Shaped { shape, values } => shape.fields().iter().copied().zip(values.iter().cloned()).collect(),
+            Fields::Dictionary(dictionary) => dictionary.clone(),
+        }
+    }
+
+    /// The id of the object's shape, or `None` if it has too many fields to have one.
+    pub(crate) fn shape_id(&self) -> Option<u64> {
+        match &*self.inner.fields.borrow() {
+            Fields::Shaped { shape, .. } => Some(shape.id()),
+            Fields::Dictionary(_) => None,
+        }
+    }
+
+    /// The slot the field is stored in, which is the same for every object with the same shape.
+    pub(crate) fn field_slot(&self, field_name: Symbol) -> Option<usize> {
+        match &*self.inner.fields.borrow() {
+            Fields::Shaped { shape, .. } => shape.slot(field_name),
+            Fields::Dictionary(_) => None,
+        }
+    }
+
+    pub(crate) fn field_at(&self, slot: usize) -> Value<'gc> {
+        match &*self.inner.fields.borrow() {
+            Fields::Shaped { values, .. } => values[slot].clone(),
+            Fields::Dictionary(_) => unreachable!("Only objects with a shape have slots."),
+        }
     }
 
-    pub fn fields(&self) -> Ref<'_, ValueMap<'gc>> {
-        self.inner.fields.borrow()
+    pub(crate) fn set_field_at(&self, ctx: &RuntimeContext<'gc>, slot: usize, value: Value<'gc>) {
+        match &mut *self.inner.fields.borrow_mut(ctx.mutation) {
+            Fields::Shaped { values, .. } => values[slot] = value,
+            Fields::Dictionary(_) => unreachable!("Only objects with a shape have slots."),
+        }
+    }
+
+    pub(crate) fn as_ptr(&self) -> *const () {
+        Gc::as_ptr(self.inner) as *const ()
     }
 }
 
@@ -89,27 +160,17 @@ impl PartialEq for Object<'_> {
     }
 }
 
-// impl Display for Object<'_> {
-//     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-//         write!(f, "Object")?;
-
-//         if let Some(ref class) = self.inner.class {
-//             write!(f, "<{}>", class.name())?;
-//         }
-
-//         write!(f, " {{ ")?;
-//         for (name, field) in self.inner.fields.borrow().iter() {
-//             write!(f, "{}: {}, ", name, field)?;
-//         }
-//         write!(f, "}}")?;
-
-//         Ok(())
-//     }
-// }
-
 #[derive(Collect)]
 #[collect(no_drop)]
 struct ObjectInner<'gc> {
     class: Option<Class<'gc>>,
-    fields: Gc<'gc, RefLock<ValueMap<'gc>>>,
+    fields: Gc<'gc, RefLock<Fields<'gc>>>,
+}
+
+// NOTE: Objects with a shape keep their values in slots, in the order the fields were added.
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+enum Fields<'gc> {
+    Shaped { shape: Shape, values: Vec<Value<'gc>> },
+    Dictionary(ValueMap<'gc>),
 }
@@ -0,0 +1,117 @@
+use std::fmt::{Display, Formatter};
+
+use crate::{runtime::RuntimeContext, value::Value};
+
+/// A copy of a value that lives outside of the garbage collected arena, so it can be handed back to the host.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OwnedValue {
+    Null,
+    Unit,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Function(String),
+    Array(Vec<OwnedValue>),
+    Map(Vec<(OwnedValue, OwnedValue)>),
+    String(String),
+    Symbol(String),
+    Object {
+        class: Option<String>,
+        fields: Vec<(String, OwnedValue)>,
+    },
+    Class(String),
+}
+
+impl OwnedValue {
+    pub fn from_value<'gc>(ctx: &RuntimeContext<'gc>, value: &Value<'gc>) -> Self {
+        match value {
+            Value::Null => OwnedValue::Null,
+            Value::Unit => OwnedValue::Unit,
+            Value::Bool(bool) => OwnedValue::Bool(*bool),
+            Value::Int(int) => OwnedValue::Int(*int),
+            Value::Float(float) => OwnedValue::Float(*float),
+            Value::FnScript(fn_script) => OwnedValue::Function(ctx.resolve(fn_script.name()).to_string()),
+            Value::FnClosure(closure) => {
+                OwnedValue::Function(format!("closure{{{}}}", &*ctx.resolve(closure.fn_script().name())))
+            }
+            Value::FnNative(fn_native) => OwnedValue::Function(fn_native.to_string()),
+            Value::FnBound(fn_bound) => match Self::from_value(ctx, &fn_bound.function()) {
+                OwnedValue::Function(name) => OwnedValue::Function(format!("FnBound{{{}}}", name)),
+                function => function,
+            },
+            Value::Array(array) => OwnedValue::Array(
+                array
+                    .elements()
+                    .iter()
+                    .map(|element| Self::from_value(ctx, element))
+                    .collect(),
+            ),
+            Value::Map(map) => OwnedValue::Map(
+                map.entries()
+                    .iter()
+                    .map(|(key, value)| (Self::from_value(ctx, &key.to_value(ctx)), Self::from_value(ctx, value)))
+                    .collect(),
+            ),
+            Value::String(string) => OwnedValue::String(string.to_string()),
+            Value::Symbol(symbol) => OwnedValue::Symbol(ctx.resolve(*symbol).to_string()),
+            Value::Object(object) => {
+                let mut fields = object
+                    .fields()
+                    .iter()
+                    .map(|(name, field)| (ctx.resolve(*name).to_string(), Self::from_value(ctx, field)))
+                    .collect::<Vec<_>>();
+                // NOTE: Sort the fields, so the same object always converts to the same value.
+                fields.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+
+                OwnedValue::Object {
+                    class: object.name().map(|name| ctx.resolve(name).to_string()),
+                    fields,
+                }
+            }
+            Value::Class(class) => OwnedValue::Class(ctx.resolve(class.name()).to_string()),
+        }
+    }
+}
+
+impl Display for OwnedValue {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OwnedValue::Null => write!(fmt, "null"),
+            OwnedValue::Unit => write!(fmt, "Unit"),
+            OwnedValue::Bool(bool) => bool.fmt(fmt),
+            OwnedValue::Int(int) => int.fmt(fmt),
+            OwnedValue::Float(float) => float.fmt(fmt),
+            OwnedValue::Function(name) => name.fmt(fmt),
+            OwnedValue::Array(elements) => {
+                let items = elements.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+
+                write!(fmt, "[{}]", items)
+            }
+            OwnedValue::Map(entries) => {
+                let items = entries
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", key, value))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                write!(fmt, "#[{}]", items)
+            }
+            OwnedValue::String(string) => string.fmt(fmt),
+            OwnedValue::Symbol(symbol) => symbol.fmt(fmt),
+            OwnedValue::Object { class, fields } => {
+                write!(fmt, "Object")?;
+
+                if let Some(class) = class {
+                    write!(fmt, "<{}>", class)?;
+                }
+
+                write!(fmt, " {{ ")?;
+                for (name, field) in fields {
+                    write!(fmt, "{}: {}, ", name, field)?;
+                }
+                write!(fmt, "}}")
+            }
+            OwnedValue::Class(name) => name.fmt(fmt),
+        }
+    }
+}
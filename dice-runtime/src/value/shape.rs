@@ -0,0 +1,93 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    hash::BuildHasherDefault,
+    rc::Rc,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use ahash::AHasher;
+use gc_arena::Collect;
+
+use crate::value::Symbol;
+
+// NOTE: Objects that grow past this many fields are switched to a dictionary, so objects used as maps don't keep
+// adding shapes to the transition tree.
+pub(crate) const MAX_SHAPE_FIELDS: usize = 32;
+
+static NEXT_SHAPE_ID: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    static EMPTY_SHAPE: Shape = Shape::root();
+}
+
+/// The field layout shared by every object that had the same fields added in the same order.
+#[derive(Clone, Collect)]
+#[collect(require_static)]
+pub(crate) struct Shape {
+    inner: Rc<ShapeInner>,
+}
+
+impl Shape {
+    /// A shape with no fields, which starts a new transition tree.
+    pub(crate) fn root() -> Self {
+        Self::with_fields(Vec::new())
+    }
+
+    /// The shape with no fields shared by objects that don't have a class.
+    pub(crate) fn empty() -> Self {
+        EMPTY_SHAPE.with(Shape::clone)
+    }
+
+    fn with_fields(fields: Vec<Symbol>) -> Self {
+        let slots = fields.iter().enumerate().map(|(slot, name)| (*name, slot)).collect();
+
+        Self {
+            inner: Rc::new(ShapeInner {
+                // NOTE: Ids are never reused, unlike addresses, so caches can't mistake a new shape for a freed one.
+                id: NEXT_SHAPE_ID.fetch_add(1, Ordering::Relaxed),
+                fields,
+                slots,
+                transitions: Default::default(),
+            }),
+        }
+    }
+
+    pub(crate) fn id(&self) -> u64 {
+        self.inner.id
+    }
+
+    pub(crate) fn slot(&self, name: Symbol) -> Option<usize> {
+        self.inner.slots.get(&name).copied()
+    }
+
+    /// The names of the fields, in the order of their slots.
+    pub(crate) fn fields(&self) -> &[Symbol] {
+        &self.inner.fields
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.inner.fields.len()
+    }
+
+    /// The shape objects of this shape have after adding the field, which is shared with every object that adds it.
+    pub(crate) fn with_field(&self, name: Symbol) -> Shape {
+        if let Some(shape) = self.inner.transitions.borrow().get(&name) {
+            return shape.clone();
+        }
+
+        let mut fields = self.inner.fields.clone();
+        fields.push(name);
+        let shape = Self::with_fields(fields);
+        self.inner.transitions.borrow_mut().insert(name, shape.clone());
+
+        shape
+    }
+}
+
+struct ShapeInner {
+    id: u64,
+    fields: Vec<Symbol>,
+    slots: HashMap<Symbol, usize, BuildHasherDefault<AHasher>>,
+    transitions: RefCell<HashMap<Symbol, Shape, BuildHasherDefault<AHasher>>>,
+}
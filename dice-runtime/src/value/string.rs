@@ -39,13 +39,13 @@ impl Deref for String {
     type Target = str;
 
     fn deref(&self) -> &Self::Target {
-        &*self.inner
+        &self.inner
     }
 }
 
 impl AsRef<str> for String {
     fn as_ref(&self) -> &str {
-        &*self.inner
+        &self.inner
     }
 }
 
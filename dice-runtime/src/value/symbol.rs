@@ -1,21 +1,51 @@
 use gc_arena::Collect;
-use std::hash::Hash;
-use string_interner::{DefaultBackend, DefaultSymbol, StringInterner};
+use std::{
+    cell::{Ref, RefCell},
+    hash::Hash,
+};
+use string_interner::{DefaultBackend, DefaultSymbol, StringInterner, Symbol as _};
 
-#[derive(Default)]
+use super::String;
+
+// NOTE: The interner lives inside the arena and is shared through the runtime context, so it uses interior mutability.
+#[derive(Default, Collect)]
+#[collect(require_static)]
 pub struct SymbolInterner {
-    interner: StringInterner<DefaultBackend>,
+    interner: RefCell<StringInterner<DefaultBackend>>,
+    // NOTE: Strings converted from symbols, indexed by symbol, so each name is only copied the first time.
+    strings: RefCell<Vec<Option<String>>>,
 }
 
 impl SymbolInterner {
-    pub fn get_or_intern(&mut self, value: impl AsRef<str>) -> Symbol {
+    pub fn get_or_intern(&self, value: impl AsRef<str>) -> Symbol {
         Symbol {
-            inner: self.interner.get_or_intern(value),
+            inner: self.interner.borrow_mut().get_or_intern(value),
         }
     }
 
-    pub fn resolve(&self, symbol: Symbol) -> Option<&str> {
-        self.interner.resolve(symbol.inner)
+    pub fn resolve(&self, symbol: Symbol) -> Ref<'_, str> {
+        Ref::map(self.interner.borrow(), |interner| {
+            interner
+                .resolve(symbol.inner)
+                .expect("Symbols are always resolved by the interner that created them.")
+        })
+    }
+
+    pub fn string(&self, symbol: Symbol) -> String {
+        let index = symbol.inner.to_usize();
+
+        if let Some(Some(string)) = self.strings.borrow().get(index) {
+            return string.clone();
+        }
+
+        let string = String::from(&*self.resolve(symbol));
+        let mut strings = self.strings.borrow_mut();
+        if strings.len() <= index {
+            strings.resize(index + 1, None);
+        }
+        strings[index] = Some(string.clone());
+
+        string
     }
 }
 
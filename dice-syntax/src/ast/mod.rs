@@ -30,6 +30,14 @@ impl SyntaxTree {
         self.nodes.get(id).expect("Node should always exist.")
     }
 
+    /// Get a mutable reference to a node, allowing passes to rewrite the tree in place.
+    // NOTE: If the nodes are shared with a child tree, they're cloned first, leaving the child tree untouched.
+    pub fn get_mut(&mut self, id: SyntaxNodeId) -> &mut SyntaxNode {
+        Rc::make_mut(&mut self.nodes)
+            .get_mut(id)
+            .expect("Node should always exist.")
+    }
+
     pub fn child(&self, id: SyntaxNodeId) -> SyntaxTree {
         self.nodes
             .get(id)
@@ -53,12 +61,14 @@ pub enum SyntaxNode {
     LitBool(LitBool),
     LitList(LitList),
     LitObject(LitObject),
+    LitMap(LitMap),
     LitAnonymousFn(LitAnonymousFn),
 
     // Member access
     FieldAccess(FieldAccess),
     SuperAccess(SuperAccess),
     Index(Index),
+    Slice(Slice),
 
     // Operators
     Prefix(Prefix),
@@ -75,6 +85,7 @@ pub enum SyntaxNode {
     ClassDecl(ClassDecl),
     ImportDecl(ImportDecl),
     ExportDecl(ExportDecl),
+    TypeAlias(TypeAlias),
 
     // Control flow
     IfExpression(IfExpression),
@@ -101,10 +112,12 @@ impl SyntaxNode {
             SyntaxNode::LitBool(LitBool { span, .. }) => *span,
             SyntaxNode::LitList(LitList { span, .. }) => *span,
             SyntaxNode::LitObject(LitObject { span, .. }) => *span,
+            SyntaxNode::LitMap(LitMap { span, .. }) => *span,
             SyntaxNode::LitAnonymousFn(LitAnonymousFn { span, .. }) => *span,
             SyntaxNode::FieldAccess(FieldAccess { span, .. }) => *span,
             SyntaxNode::SuperAccess(SuperAccess { span, .. }) => *span,
             SyntaxNode::Index(Index { span, .. }) => *span,
+            SyntaxNode::Slice(Slice { span, .. }) => *span,
             SyntaxNode::Prefix(Prefix { span, .. }) => *span,
             SyntaxNode::Binary(Binary { span, .. }) => *span,
             SyntaxNode::Is(Is { span, .. }) => *span,
@@ -117,6 +130,7 @@ impl SyntaxNode {
             SyntaxNode::ClassDecl(ClassDecl { span, .. }) => *span,
             SyntaxNode::ImportDecl(ImportDecl { span, .. }) => *span,
             SyntaxNode::ExportDecl(ExportDecl { span, .. }) => *span,
+            SyntaxNode::TypeAlias(TypeAlias { span, .. }) => *span,
             SyntaxNode::IfExpression(IfExpression { span, .. }) => *span,
             SyntaxNode::WhileLoop(WhileLoop { span, .. }) => *span,
             SyntaxNode::ForLoop(ForLoop { span, .. }) => *span,
@@ -24,6 +24,12 @@ pub struct LitObject {
     pub span: Span,
 }
 
+#[derive(Debug, Clone)]
+pub struct LitMap {
+    pub entries: Vec<(SyntaxNodeId, SyntaxNodeId)>,
+    pub span: Span,
+}
+
 #[derive(Debug, Clone)]
 pub struct LitIdent {
     pub identifier: String,
@@ -125,6 +131,15 @@ pub struct Index {
     pub span: Span,
 }
 
+#[derive(Debug, Clone)]
+pub struct Slice {
+    pub expression: SyntaxNodeId,
+    pub start: Option<SyntaxNodeId>,
+    pub end: Option<SyntaxNodeId>,
+    pub is_inclusive: bool,
+    pub span: Span,
+}
+
 #[derive(Debug, Clone)]
 pub struct Is {
     pub value: SyntaxNodeId,
@@ -227,11 +242,74 @@ pub struct FnArg {
 
 #[derive(Debug, Clone)]
 pub struct TypeAnnotation {
-    pub name: LitIdent,
+    pub kind: TypeAnnotationKind,
     pub is_nullable: bool,
     pub span: Span,
 }
 
+impl TypeAnnotation {
+    pub fn named(name: LitIdent, is_nullable: bool, span: Span) -> Self {
+        Self {
+            kind: TypeAnnotationKind::Named(name),
+            is_nullable,
+            span,
+        }
+    }
+
+    /// Returns true if null satisfies the annotation, either directly or through one of its union members.
+    pub fn accepts_null(&self) -> bool {
+        match &self.kind {
+            TypeAnnotationKind::Union(members) => self.is_nullable || members.iter().any(TypeAnnotation::accepts_null),
+            _ => self.is_nullable,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum TypeAnnotationKind {
+    Named(LitIdent),
+    Array(Box<TypeAnnotation>),
+    Function {
+        args: Vec<TypeAnnotation>,
+        return_: Option<Box<TypeAnnotation>>,
+    },
+    Union(Vec<TypeAnnotation>),
+}
+
+impl Display for TypeAnnotation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            TypeAnnotationKind::Named(name) => write!(f, "{}", name.identifier)?,
+            TypeAnnotationKind::Array(element) => write!(f, "Array<{}>", element)?,
+            TypeAnnotationKind::Function { args, return_ } => {
+                let args = args.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+                write!(f, "Function({})", args)?;
+
+                if let Some(return_) = return_ {
+                    write!(f, " -> {}", return_)?;
+                }
+            }
+            TypeAnnotationKind::Union(members) => {
+                let members = members.iter().map(ToString::to_string).collect::<Vec<_>>().join(" | ");
+                write!(f, "{}", members)?;
+            }
+        }
+
+        if self.is_nullable {
+            write!(f, "?")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TypeAlias {
+    pub name: LitIdent,
+    pub type_: TypeAnnotation,
+    pub span: Span,
+}
+
 #[derive(Debug, Clone)]
 pub struct ExportDecl {
     pub export: SyntaxNodeId,
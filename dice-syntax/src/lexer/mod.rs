@@ -259,7 +259,8 @@ pub mod test {
         assert_next_token!(tokens, TokenKind::In);
         assert_next_token!(tokens, TokenKind::Operator);
         assert_next_token!(tokens, TokenKind::Class);
-        assert_next_token!(tokens, TokenKind::Reserved);
+        assert_next_token!(tokens, TokenKind::Identifier);
+        assert_next_token!(tokens, TokenKind::Type);
         assert_next_token!(tokens, TokenKind::Is);
         assert_next_token!(tokens, TokenKind::Reserved);
         assert_next_token!(tokens, TokenKind::Import);
@@ -206,7 +206,9 @@ pub enum TokenKind {
     Export,
     #[token("super")]
     Super,
-    #[regex("await|async|yield|do|const|match|enum|trait|type|try|when|default|switch")]
+    #[token("type")]
+    Type,
+    #[regex("await|async|yield|do|const|match|enum|trait|try|when|default|switch")]
     Reserved,
 
     // Literals,
@@ -291,6 +293,7 @@ impl Display for TokenKind {
             TokenKind::From => write!(f, "from"),
             TokenKind::Export => write!(f, "export"),
             TokenKind::Super => write!(f, "super"),
+            TokenKind::Type => write!(f, "type"),
             TokenKind::Reserved => write!(f, "reserved"),
             TokenKind::Identifier => write!(f, "identifier"),
             TokenKind::Integer => write!(f, "integer"),
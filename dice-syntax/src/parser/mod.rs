@@ -10,23 +10,26 @@ use dice_core::error::{
     context::{Context, ContextKind, IMPORT_REQUIRES_ITEMS_TO_BE_IMPORTED, IMPORT_REQUIRES_ITEMS_TO_BE_IMPORTED_HELP},
     Error, ResultExt,
 };
-use dice_core::protocol::error::{IS_OK, RESULT};
+use dice_core::protocol::{
+    error::{IS_OK, RESULT},
+    types::{ARRAY, FUNCTION},
+};
 use dice_core::source::Source;
 use dice_core::span::Span;
 use dice_core::tags;
 
 use crate::{
-    ClassDecl,
-    ErrorPropagate,
-    FieldAccess, FnArg, ForLoop, ImportDecl, Index, Is, lexer::Token, Loop, NullPropagate, OpDecl, OverloadedOperator,
-    parser::rules::{ParseResult, ParserRules, Precedence}, SuperAccess, SuperCall, TypeAnnotation, VarDeclKind,
+    lexer::Token,
+    parser::rules::{ParseResult, ParserRules, Precedence},
+    ClassDecl, ErrorPropagate, FieldAccess, FnArg, ForLoop, ImportDecl, Index, Is, Loop, NullPropagate, OpDecl,
+    OverloadedOperator, Slice, SuperAccess, SuperCall, TypeAlias, TypeAnnotation, TypeAnnotationKind, VarDeclKind,
 };
 
 use super::{
-    Assignment,
-    AssignmentOperator, Binary, BinaryOperator, Block, Break, Continue, ExportDecl, FnCall, FnDecl, IfExpression,
-    lexer::{Lexer, TokenKind}, LitAnonymousFn, LitBool, LitFloat, LitIdent, LitInt, LitList, LitNull, LitObject, LitString, LitUnit,
-    Prefix, Return, SyntaxNode, SyntaxNodeId, SyntaxTree, UnaryOperator, VarDecl, WhileLoop,
+    lexer::{Lexer, TokenKind},
+    Assignment, AssignmentOperator, Binary, BinaryOperator, Block, Break, Continue, ExportDecl, FnCall, FnDecl,
+    IfExpression, LitAnonymousFn, LitBool, LitFloat, LitIdent, LitInt, LitList, LitMap, LitNull, LitObject, LitString,
+    LitUnit, Prefix, Return, SyntaxNode, SyntaxNodeId, SyntaxTree, UnaryOperator, VarDecl, WhileLoop,
 };
 
 mod rules;
@@ -74,6 +77,7 @@ impl<'a> Parser<'a> {
                 TokenKind::Class => self.class_decl()?,
                 TokenKind::Import => self.import_decl()?,
                 TokenKind::Export => self.export_decl()?,
+                TokenKind::Type => self.type_alias()?,
                 TokenKind::Return | TokenKind::Break | TokenKind::Continue => self.control_flow()?,
                 _ => self.expression()?,
             };
@@ -106,7 +110,7 @@ impl<'a> Parser<'a> {
         let next_token = self.lexer.peek()?;
         let rule = self.rules.for_token(&next_token).with_source(|| self.source.clone())?;
         // TODO: Handle prefix precedence.
-        let mut node = rule
+        let node = rule
             .prefix
             .map(|(prefix, _)| prefix(self, precedence <= Precedence::Assignment))
             .unwrap_or_else({
@@ -114,8 +118,12 @@ impl<'a> Parser<'a> {
                 || self.unexpected_token(next_token.kind, self.rules.prefix_tokens(), next_token.span)
             })?;
 
+        self.parse_infix(node, precedence, next_token.span)
+    }
+
+    // NOTE: Continue parsing postfix and infix operators, using the already parsed node as the left-hand side.
+    fn parse_infix(&mut self, mut node: SyntaxNodeId, precedence: Precedence, span_start: Span) -> ParseResult {
         loop {
-            let span_start = next_token.span;
             let next_token = self.lexer.peek()?;
             let rule = self.rules.for_token(&next_token).with_source(|| self.source.clone())?;
 
@@ -366,6 +374,12 @@ impl<'a> Parser<'a> {
             kind => self.unexpected_token(kind, &[TokenKind::Identifier, TokenKind::LeftCurly], next_token.span)?,
         };
 
+        let type_ = if matches!(kind, VarDeclKind::Singular(_)) && self.lexer.peek()?.kind == TokenKind::Colon {
+            Some(self.parse_type_annotation(TokenKind::Colon, true)?)
+        } else {
+            None
+        };
+
         self.lexer.consume(TokenKind::Assign)?;
         let expr = self.expression()?;
         let span_end = self.lexer.current().span;
@@ -373,7 +387,7 @@ impl<'a> Parser<'a> {
             kind,
             is_mutable,
             expr,
-            type_: None,
+            type_,
             span: span_start + span_end,
         });
 
@@ -384,7 +398,7 @@ impl<'a> Parser<'a> {
         let span_start = self.lexer.peek()?.span;
         let args = self.parse_args(TokenKind::Pipe, TokenKind::Pipe)?;
 
-        if args.len() > (u8::MAX as usize) {
+        if args.len() > (u32::MAX as usize) {
             return Err(Error::new(FUNCTION_HAS_TOO_MANY_ARGUMENTS)
                 .with_source(self.source.clone())
                 .with_span(span_start + self.lexer.current().span));
@@ -409,7 +423,7 @@ impl<'a> Parser<'a> {
         let name = LitIdent::synthesize(name, name_token.span);
         let args = self.parse_args(TokenKind::LeftParen, TokenKind::RightParen)?;
 
-        if args.len() > (u8::MAX as usize) {
+        if args.len() > (u32::MAX as usize) {
             return Err(Error::new(FUNCTION_HAS_TOO_MANY_ARGUMENTS)
                 .with_source(self.source.clone())
                 .with_span(span_start + self.lexer.current().span));
@@ -483,7 +497,7 @@ impl<'a> Parser<'a> {
 
     fn parse_return(&mut self) -> Result<Option<TypeAnnotation>, Error> {
         if self.lexer.peek()?.kind == TokenKind::Arrow {
-            self.parse_type_annotation(TokenKind::Arrow).map(Some)
+            self.parse_type_annotation(TokenKind::Arrow, true).map(Some)
         } else {
             Ok(None)
         }
@@ -498,8 +512,9 @@ impl<'a> Parser<'a> {
             let (token, name) = self.lexer.consume_ident()?;
             let span = token.span;
 
+            // NOTE: Anonymous functions delimit their args with pipes, so unions are not allowed in that position.
             let type_ = if self.lexer.peek()?.kind == TokenKind::Colon {
-                Some(self.parse_type_annotation(TokenKind::Colon)?)
+                Some(self.parse_type_annotation(TokenKind::Colon, close_token_kind != TokenKind::Pipe)?)
             } else {
                 None
             };
@@ -522,29 +537,112 @@ impl<'a> Parser<'a> {
         Ok(args)
     }
 
-    fn parse_type_annotation(&mut self, delimiter: TokenKind) -> Result<TypeAnnotation, Error> {
+    fn parse_type_annotation(&mut self, delimiter: TokenKind, allow_union: bool) -> Result<TypeAnnotation, Error> {
         let span_start = self.lexer.consume(delimiter)?.span;
+        let type_ = self.parse_type(allow_union)?;
+
+        Ok(TypeAnnotation {
+            span: span_start + type_.span,
+            ..type_
+        })
+    }
+
+    fn parse_type(&mut self, allow_union: bool) -> Result<TypeAnnotation, Error> {
+        let first = self.parse_type_member()?;
+
+        if !allow_union || self.lexer.peek()?.kind != TokenKind::Pipe {
+            return Ok(first);
+        }
+
+        let span_start = first.span;
+        let mut members = vec![first];
+
+        while self.lexer.peek()?.kind == TokenKind::Pipe {
+            self.lexer.consume(TokenKind::Pipe)?;
+            members.push(self.parse_type_member()?);
+        }
+
+        let span_end = self.lexer.current().span;
+
+        Ok(TypeAnnotation {
+            kind: TypeAnnotationKind::Union(members),
+            is_nullable: false,
+            span: span_start + span_end,
+        })
+    }
+
+    fn parse_type_member(&mut self) -> Result<TypeAnnotation, Error> {
         let (name_token, name) = self.lexer.consume_ident()?;
-        let ident_span = name_token.span;
+        let span_start = name_token.span;
+        let next_kind = self.lexer.peek()?.kind;
+        let kind = if name == ARRAY && next_kind == TokenKind::Less {
+            self.lexer.consume(TokenKind::Less)?;
+            let element = self.parse_type(true)?;
+            self.lexer.consume(TokenKind::Greater)?;
+
+            TypeAnnotationKind::Array(Box::new(element))
+        } else if name == FUNCTION && next_kind == TokenKind::LeftParen {
+            self.lexer.consume(TokenKind::LeftParen)?;
+
+            let mut args = Vec::new();
+
+            while self.lexer.peek()?.kind != TokenKind::RightParen {
+                args.push(self.parse_type(true)?);
+
+                let next = self.lexer.peek()?;
+                if next.kind == TokenKind::Comma {
+                    self.lexer.next()?;
+                } else if next.kind != TokenKind::RightParen {
+                    self.unexpected_token(next.kind, &[TokenKind::RightParen], next.span)?;
+                }
+            }
+
+            self.lexer.consume(TokenKind::RightParen)?;
+
+            // NOTE: The return type only binds a single member, so `Function() -> Int | Float` is a union of a
+            // function and a float.
+            let return_ = if self.lexer.peek()?.kind == TokenKind::Arrow {
+                self.lexer.consume(TokenKind::Arrow)?;
+                Some(Box::new(self.parse_type_member()?))
+            } else {
+                None
+            };
+
+            TypeAnnotationKind::Function { args, return_ }
+        } else {
+            TypeAnnotationKind::Named(LitIdent::synthesize(name, span_start))
+        };
+
         let is_nullable = if self.lexer.peek()?.kind == TokenKind::QuestionMark {
             self.lexer.consume(TokenKind::QuestionMark)?;
             true
         } else {
             false
         };
-        let name = LitIdent {
-            identifier: name,
-            span: ident_span,
-        };
         let span_end = self.lexer.current().span;
 
         Ok(TypeAnnotation {
-            name,
+            kind,
             is_nullable,
             span: span_start + span_end,
         })
     }
 
+    fn type_alias(&mut self) -> ParseResult {
+        let span_start = self.lexer.consume(TokenKind::Type)?.span;
+        let (name_token, name) = self.lexer.consume_ident()?;
+        let name = LitIdent::synthesize(name, name_token.span);
+        let type_ = self.parse_type_annotation(TokenKind::Assign, true)?;
+        let span_end = self.lexer.current().span;
+        let node = SyntaxNode::TypeAlias(TypeAlias {
+            name,
+            type_,
+            span: span_start + span_end,
+        });
+
+        Ok(self.arena.alloc(node))
+    }
+
     fn parse_fields(&mut self, open_token_kind: TokenKind, close_token_kind: TokenKind) -> Result<Vec<String>, Error> {
         self.lexer.consume(open_token_kind)?;
 
@@ -655,7 +753,8 @@ impl<'a> Parser<'a> {
     }
 
     fn is_operator(&mut self, lhs: SyntaxNodeId, _: bool, span_start: Span) -> ParseResult {
-        let type_annotation = self.parse_type_annotation(TokenKind::Is)?;
+        // NOTE: Unions are not allowed here, as `x is Int || y` would otherwise be ambiguous.
+        let type_annotation = self.parse_type_annotation(TokenKind::Is, false)?;
         let node = SyntaxNode::Is(Is {
             value: lhs,
             type_: type_annotation,
@@ -727,11 +826,11 @@ impl<'a> Parser<'a> {
             kind: VarDeclKind::Singular(String::from(result_var)),
             is_mutable: false,
             expr: expression,
-            type_: Some(TypeAnnotation {
-                name: LitIdent::synthesize(result_ty, span_start),
-                is_nullable: false,
-                span: span_start,
-            }),
+            type_: Some(TypeAnnotation::named(
+                LitIdent::synthesize(result_ty, span_start),
+                false,
+                span_start,
+            )),
             span,
         });
         let result_temp = self.arena.alloc(result_temp);
@@ -782,7 +881,21 @@ impl<'a> Parser<'a> {
 
     fn index_access(&mut self, expression: SyntaxNodeId, can_assign: bool, span_start: Span) -> ParseResult {
         self.lexer.consume(TokenKind::LeftSquare)?;
-        let index_expression = self.expression()?;
+
+        /* NOTE: Parse the index at a higher precedence than ranges first, so a range inside of the square brackets
+         * can be turned into a slice with optional bounds.  Otherwise parsing continues with the rest of the index.
+         */
+        let next_token = self.lexer.peek()?;
+        if let TokenKind::RangeExclusive | TokenKind::RangeInclusive = next_token.kind {
+            return self.slice_access(expression, None, can_assign, span_start);
+        }
+
+        let start = self.parse_precedence(Precedence::Range.increment())?;
+        if let TokenKind::RangeExclusive | TokenKind::RangeInclusive = self.lexer.peek()?.kind {
+            return self.slice_access(expression, Some(start), can_assign, span_start);
+        }
+
+        let index_expression = self.parse_infix(start, Precedence::Assignment, next_token.span)?;
         let span_end = self.lexer.consume(TokenKind::RightSquare)?.span;
 
         let node = SyntaxNode::Index(Index {
@@ -795,6 +908,36 @@ impl<'a> Parser<'a> {
         self.parse_assignment(lhs_expression, can_assign, span_start)
     }
 
+    fn slice_access(
+        &mut self,
+        expression: SyntaxNodeId,
+        start: Option<SyntaxNodeId>,
+        can_assign: bool,
+        span_start: Span,
+    ) -> ParseResult {
+        let is_inclusive = self
+            .lexer
+            .consume_one_of(&[TokenKind::RangeExclusive, TokenKind::RangeInclusive])?
+            .kind
+            == TokenKind::RangeInclusive;
+        let end = match self.lexer.peek()?.kind {
+            TokenKind::RightSquare => None,
+            _ => Some(self.parse_precedence(Precedence::Range.increment())?),
+        };
+        let span_end = self.lexer.consume(TokenKind::RightSquare)?.span;
+
+        let node = SyntaxNode::Slice(Slice {
+            expression,
+            start,
+            end,
+            is_inclusive,
+            span: span_start + span_end,
+        });
+        let lhs_expression = self.arena.alloc(node);
+
+        self.parse_assignment(lhs_expression, can_assign, span_start)
+    }
+
     fn field_access(&mut self, lhs: SyntaxNodeId, can_assign: bool, span_start: Span) -> ParseResult {
         self.lexer.consume(TokenKind::Dot)?;
 
@@ -941,6 +1084,11 @@ impl<'a> Parser<'a> {
 
     fn object(&mut self, _: bool) -> ParseResult {
         let span_start = self.lexer.consume(TokenKind::Object)?.span;
+
+        if self.lexer.peek()?.kind == TokenKind::LeftSquare {
+            return self.map(span_start);
+        }
+
         self.lexer.consume(TokenKind::LeftCurly)?;
 
         let mut properties = Vec::new();
@@ -978,6 +1126,36 @@ impl<'a> Parser<'a> {
         Ok(node)
     }
 
+    fn map(&mut self, span_start: Span) -> ParseResult {
+        self.lexer.consume(TokenKind::LeftSquare)?;
+
+        let mut entries = Vec::new();
+
+        while self.lexer.peek()?.kind != TokenKind::RightSquare {
+            let key = self.parse_precedence(Precedence::Assignment)?;
+            self.lexer.consume(TokenKind::Colon)?;
+            let value = self.parse_precedence(Precedence::Assignment)?;
+
+            let next = self.lexer.peek()?;
+            if next.kind == TokenKind::Comma {
+                self.lexer.next()?;
+            } else if next.kind != TokenKind::RightSquare {
+                self.unexpected_token(next.kind, &[TokenKind::RightSquare], next.span)?;
+            }
+
+            entries.push((key, value));
+        }
+
+        let span_end = self.lexer.consume(TokenKind::RightSquare)?.span;
+
+        let node = self.arena.alloc(SyntaxNode::LitMap(LitMap {
+            entries,
+            span: span_start + span_end,
+        }));
+
+        Ok(node)
+    }
+
     fn list(&mut self, _: bool) -> ParseResult {
         let span_start = self.lexer.consume(TokenKind::LeftSquare)?.span;
 
@@ -1,5 +1,5 @@
 use criterion::{black_box, criterion_group, Criterion};
-use dice::{Dice, Runtime};
+use dice::Dice;
 use std::time::Duration;
 
 fn loop_in_place_addition(criterion: &mut Criterion) {
@@ -72,6 +72,33 @@ fn loop_closure_call(criterion: &mut Criterion) {
     });
 }
 
+fn loop_method_call(criterion: &mut Criterion) {
+    let mut dice = Dice::default();
+
+    criterion.bench_function("method-call", |bencher| {
+        bencher.iter(|| {
+            dice.run_script(black_box(
+                "class Counter { fn new(self) { self.count = 0 } fn increment(self) { self.count += 1 } }
+                let counter = Counter() while counter.count < 100000 { counter.increment() }",
+            ))
+            .unwrap()
+        })
+    });
+}
+
+fn loop_string_constant(criterion: &mut Criterion) {
+    let mut dice = Dice::default();
+
+    criterion.bench_function("string-constant", |bencher| {
+        bencher.iter(|| {
+            dice.run_script(black_box(
+                "let mut x = 0 let mut s = \"\" while x < 100000 { s = \"a short string\" x += 1 }",
+            ))
+            .unwrap()
+        })
+    });
+}
+
 fn closure_called_by_another_function_in_parent_scope(criterion: &mut Criterion) {
     let mut dice = Dice::default();
 
@@ -105,6 +132,8 @@ criterion_group!(
         loop_addition_with_assignment,
         loop_function_call,
         loop_closure_call,
+        loop_method_call,
+        loop_string_constant,
         range_for_loop_addition_with_assignment,
         iterator_for_loop_addition_with_assignment
 );
@@ -1,17 +1,31 @@
 use dice_compiler::compiler::Compiler;
+#[cfg(feature = "register-vm")]
+use dice_compiler::register_compiler::RegisterCompiler;
 use dice_core::source::{Source, SourceKind};
 use dice_runtime::runtime;
 
-pub use dice_core::{error, protocol, runtime::Runtime, tags, value};
+pub use dice_compiler::compiler::CompilerOptions;
+pub use dice_core::{error, protocol, tags};
+pub use dice_runtime::{api::Runtime, interrupt::InterruptHandle, limits::Limits, snapshot::Snapshot, value};
 
 pub struct Dice {
     runtime: runtime::Runtime,
+    compiler_options: CompilerOptions,
 }
 
 impl Dice {
-    pub fn run_script(&mut self, input: impl Into<String>) -> Result<value::Value, error::Error> {
+    pub fn run_script(&mut self, input: impl Into<String>) -> Result<value::OwnedValue, error::Error> {
         let source = Source::new(input.into(), SourceKind::Script);
-        let bytecode = Compiler::compile_source(source)?;
+
+        // NOTE: Scripts the register backend can't compile fall back to the stack backend, which also reports errors.
+        #[cfg(feature = "register-vm")]
+        if let Ok(bytecode) =
+            RegisterCompiler::compile_source_with_options(source.clone(), self.compiler_options.clone())
+        {
+            return self.runtime.run_registers(bytecode);
+        }
+
+        let bytecode = Compiler::compile_source_with_options(source, self.compiler_options.clone())?;
         let value = self.runtime.run(bytecode)?;
 
         Ok(value)
@@ -19,12 +33,30 @@ impl Dice {
 
     pub fn disassemble_script(&self, input: impl Into<String>) -> Result<String, error::Error> {
         let source = Source::new(input.into(), SourceKind::Script);
-        let bytecode = Compiler::compile_source(source)?;
+        let bytecode = Compiler::compile_source_with_options(source, self.compiler_options.clone())?;
+
+        Ok(bytecode.disassemble().to_string())
+    }
+
+    /// Disassemble the script into JSON, for tools that want to inspect the generated bytecode.
+    pub fn disassemble_script_to_json(&self, input: impl Into<String>) -> Result<String, error::Error> {
+        let source = Source::new(input.into(), SourceKind::Script);
+        let bytecode = Compiler::compile_source_with_options(source, self.compiler_options.clone())?;
+
+        Ok(bytecode.disassemble().to_json())
+    }
+
+    /// Set the options scripts are compiled with, such as whether type annotations are also checked at compile time.
+    pub fn set_compiler_options(&mut self, options: CompilerOptions) {
+        self.compiler_options = options;
+    }
 
-        Ok(bytecode.to_string())
+    /// A handle other threads can use to interrupt the script being run.
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.runtime.interrupt_handle()
     }
 
-    pub fn runtime(&mut self) -> &mut impl Runtime {
+    pub fn runtime(&mut self) -> &mut runtime::Runtime {
         &mut self.runtime
     }
 }
@@ -32,7 +64,11 @@ impl Dice {
 impl Default for Dice {
     fn default() -> Self {
         let runtime = runtime::Runtime::default();
+        let compiler_options = CompilerOptions::default();
 
-        Self { runtime }
+        Self {
+            runtime,
+            compiler_options,
+        }
     }
 }
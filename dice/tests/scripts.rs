@@ -1,4 +1,19 @@
-use dice::{error::Error, value::Value, Dice};
+use std::{collections::HashMap, time::Duration};
+
+use dice::{
+    error::{
+        codes::{
+            CALL_DEPTH_LIMIT_EXCEEDED, HEAP_LIMIT_EXCEEDED, INDEX_OUT_OF_BOUNDS, INSTRUCTION_LIMIT_EXCEEDED,
+            INVALID_MAP_KEY_CONVERSION, INVALID_SNAPSHOT, JUMP_TOO_FAR, SCRIPT_INTERRUPTED, STACK_OVERFLOW,
+            TIME_LIMIT_EXCEEDED, TYPE_MISMATCH,
+        },
+        Error,
+    },
+    value::OwnedValue,
+    CompilerOptions, Dice, Limits, Snapshot,
+};
+use dice_bytecode::{Bytecode, Instruction};
+use dice_core::source::{Source, SourceKind};
 
 #[test]
 fn test_lazy_and_both_true() -> Result<(), Error> {
@@ -7,7 +22,7 @@ fn test_lazy_and_both_true() -> Result<(), Error> {
 
     assert!(matches! {
         result,
-        Value::Bool(true)
+        OwnedValue::Bool(true)
     });
 
     Ok(())
@@ -20,7 +35,7 @@ fn test_lazy_and_lhs_true() -> Result<(), Error> {
 
     assert!(matches! {
         result,
-        Value::Bool(false)
+        OwnedValue::Bool(false)
     });
 
     Ok(())
@@ -33,7 +48,7 @@ fn test_lazy_and_rhs_true() -> Result<(), Error> {
 
     assert!(matches! {
         result,
-        Value::Bool(false)
+        OwnedValue::Bool(false)
     });
 
     Ok(())
@@ -46,7 +61,7 @@ fn test_lazy_and_both_false() -> Result<(), Error> {
 
     assert!(matches! {
         result,
-        Value::Bool(false)
+        OwnedValue::Bool(false)
     });
 
     Ok(())
@@ -79,7 +94,7 @@ fn test_lazy_or_both_true() -> Result<(), Error> {
 
     assert!(matches! {
         result,
-        Value::Bool(true)
+        OwnedValue::Bool(true)
     });
 
     Ok(())
@@ -92,7 +107,7 @@ fn test_lazy_or_lhs_true() -> Result<(), Error> {
 
     assert!(matches! {
         result,
-        Value::Bool(true)
+        OwnedValue::Bool(true)
     });
 
     Ok(())
@@ -105,7 +120,7 @@ fn test_lazy_or_rhs_true() -> Result<(), Error> {
 
     assert!(matches! {
         result,
-        Value::Bool(true)
+        OwnedValue::Bool(true)
     });
 
     Ok(())
@@ -118,7 +133,7 @@ fn test_lazy_or_both_false() -> Result<(), Error> {
 
     assert!(matches! {
         result,
-        Value::Bool(false)
+        OwnedValue::Bool(false)
     });
 
     Ok(())
@@ -153,7 +168,7 @@ fn test_multiplication() -> Result<(), Error> {
 
     assert!(matches! {
         result,
-        Value::Int(125)
+        OwnedValue::Int(125)
     });
 
     Ok(())
@@ -166,7 +181,7 @@ fn test_multiplication_parens_lhs() -> Result<(), Error> {
 
     assert!(matches! {
         result,
-        Value::Int(125)
+        OwnedValue::Int(125)
     });
 
     Ok(())
@@ -179,7 +194,7 @@ fn test_multiplication_parens_rhs() -> Result<(), Error> {
 
     assert!(matches! {
         result,
-        Value::Int(125)
+        OwnedValue::Int(125)
     });
 
     Ok(())
@@ -192,7 +207,7 @@ fn test_addition() -> Result<(), Error> {
 
     assert!(matches! {
         result,
-        Value::Int(15)
+        OwnedValue::Int(15)
     });
 
     Ok(())
@@ -205,7 +220,7 @@ fn test_precedence() -> Result<(), Error> {
 
     assert!(matches! {
         result,
-        Value::Int(30)
+        OwnedValue::Int(30)
     });
 
     Ok(())
@@ -216,7 +231,7 @@ fn test_subtraction() -> Result<(), Error> {
     let mut runtime = Dice::default();
     let result = runtime.run_script("5-2")?;
 
-    assert_eq!(result, Value::Int(3));
+    assert_eq!(result, OwnedValue::Int(3));
 
     Ok(())
 }
@@ -226,7 +241,7 @@ fn test_add_negative() -> Result<(), Error> {
     let mut runtime = Dice::default();
     let result = runtime.run_script("5+-2")?;
 
-    assert_eq!(result, Value::Int(3));
+    assert_eq!(result, OwnedValue::Int(3));
 
     Ok(())
 }
@@ -236,7 +251,7 @@ fn test_negate() -> Result<(), Error> {
     let mut runtime = Dice::default();
     let result = runtime.run_script("- -5")?;
 
-    assert_eq!(result, Value::Int(5));
+    assert_eq!(result, OwnedValue::Int(5));
 
     Ok(())
 }
@@ -246,7 +261,7 @@ fn test_not() -> Result<(), Error> {
     let mut runtime = Dice::default();
     let result = runtime.run_script("!true")?;
 
-    assert_eq!(result, Value::Bool(false));
+    assert_eq!(result, OwnedValue::Bool(false));
 
     Ok(())
 }
@@ -256,7 +271,7 @@ fn test_equality() -> Result<(), Error> {
     let mut runtime = Dice::default();
     let result = runtime.run_script("2 + 3 == 5")?;
 
-    assert_eq!(result, Value::Bool(true));
+    assert_eq!(result, OwnedValue::Bool(true));
 
     Ok(())
 }
@@ -583,38 +598,104 @@ fn test_equality() -> Result<(), Error> {
 //     Ok(())
 // }
 //
-// #[test]
-// fn test_list_index() -> Result<(), Error> {
-//     let mut runtime = Dice::default();
-//     let result = runtime.run_script(r#"[5][0]"#)?;
-//     let actual = *result.value::<i64>().unwrap();
-//
-//     assert_eq!(5, actual);
-//
-//     Ok(())
-// }
-//
-// #[test]
-// fn test_list_negative_index() -> Result<(), Error> {
-//     let mut runtime = Dice::default();
-//     let result = runtime.run_script(r#"[5][-1]"#)?;
-//     let actual = *result.value::<i64>().unwrap();
-//
-//     assert_eq!(5, actual);
-//
-//     Ok(())
-// }
-//
-// #[test]
-// fn test_list_negative_index_out_of_bounds() -> Result<(), Error> {
-//     let mut runtime = Dice::default();
-//     let result = runtime.run_script(r#"[5][-2]"#);
-//
-//     assert!(matches!(result, Err(Error::IndexOutOfBounds(1, -1))));
-//
-//     Ok(())
-// }
-//
+#[test]
+fn test_list_index() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(r#"[5][0]"#)?;
+
+    assert_eq!(result, OwnedValue::Int(5));
+
+    Ok(())
+}
+
+#[test]
+fn test_list_negative_index() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(r#"[5][-1]"#)?;
+
+    assert_eq!(result, OwnedValue::Int(5));
+
+    Ok(())
+}
+
+#[test]
+fn test_list_negative_index_out_of_bounds() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let error = runtime.run_script(r#"[5][-2]"#).unwrap_err();
+
+    assert_eq!(error.error_code(), INDEX_OUT_OF_BOUNDS);
+    assert_eq!(error.tag("index"), Some("-2"));
+    assert_eq!(error.tag("length"), Some("1"));
+
+    Ok(())
+}
+
+#[test]
+fn test_string_index_out_of_bounds() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let error = runtime.run_script(r#""héllo"[5]"#).unwrap_err();
+
+    assert_eq!(error.error_code(), INDEX_OUT_OF_BOUNDS);
+    assert_eq!(error.tag("index"), Some("5"));
+    assert_eq!(error.tag("length"), Some("5"));
+
+    Ok(())
+}
+
+#[test]
+fn test_list_slice_out_of_bounds() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let error = runtime.run_script(r#"[1, 2, 3][1..5]"#).unwrap_err();
+
+    assert_eq!(error.error_code(), INDEX_OUT_OF_BOUNDS);
+    assert_eq!(error.tag("index"), Some("5"));
+    assert_eq!(error.tag("length"), Some("3"));
+
+    let error = runtime.run_script(r#"[1, 2, 3][-4..]"#).unwrap_err();
+
+    assert_eq!(error.error_code(), INDEX_OUT_OF_BOUNDS);
+    assert_eq!(error.tag("index"), Some("-4"));
+    assert_eq!(error.tag("length"), Some("3"));
+
+    let error = runtime.run_script(r#"[1, 2, 3][..=3]"#).unwrap_err();
+
+    assert_eq!(error.error_code(), INDEX_OUT_OF_BOUNDS);
+    assert_eq!(error.tag("index"), Some("3"));
+    assert_eq!(error.tag("length"), Some("3"));
+
+    Ok(())
+}
+
+#[test]
+fn test_list_slice() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(r#"let xs = [1, 2, 3, 4][1..3] xs[0] * 10 + xs[-1]"#)?;
+
+    assert_eq!(result, OwnedValue::Int(23));
+
+    Ok(())
+}
+
+#[test]
+fn test_list_slice_open_bounds() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(r#"let xs = [1, 2, 3, 4] xs[..2][1] + xs[2..][1] + xs[..=-2][2]"#)?;
+
+    assert_eq!(result, OwnedValue::Int(9));
+
+    Ok(())
+}
+
+#[test]
+fn test_string_slice_is_utf8_safe() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(r#""héllo wörld"[0..5] == "héllo" && "héllo"[-1] == "o""#)?;
+
+    assert_eq!(result, OwnedValue::Bool(true));
+
+    Ok(())
+}
+
 // #[test]
 // fn test_variable_decl() -> Result<(), Error> {
 //     let mut runtime = Dice::default();
@@ -682,7 +763,37 @@ fn test_while_loop() -> Result<(), Error> {
     "#;
     let result = runtime.run_script(script)?;
 
-    assert_eq!(result, Value::Int(10));
+    assert_eq!(result, OwnedValue::Int(10));
+
+    Ok(())
+}
+
+#[test]
+fn test_while_loop_counting_floats() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script("let mut x = 0.0 while x < 10.0 { x = x + 0.5 } x")?;
+
+    assert_eq!(result, OwnedValue::Float(10.0));
+
+    Ok(())
+}
+
+#[test]
+fn test_while_loop_counting_with_overloaded_operators() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let script = r#"
+        class Counter {
+            fn new(self, count) { self.count = count }
+            op +(self, amount) { Counter(self.count + amount) }
+            op <(self, limit) { self.count < limit }
+        }
+        let mut x = Counter(0)
+        while x < 10 { x = x + 1 }
+        x.count
+    "#;
+    let result = runtime.run_script(script)?;
+
+    assert_eq!(result, OwnedValue::Int(10));
 
     Ok(())
 }
@@ -698,3 +809,559 @@ fn test_while_loop() -> Result<(), Error> {
 //     Ok(())
 // }
 //
+
+#[test]
+fn test_large_branch() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let elements = (0..4000).map(|value| value.to_string()).collect::<Vec<_>>().join(", ");
+    let result = runtime.run_script(format!("let t = false if t {{ [{}][0] }} else {{ 5 }}", elements))?;
+
+    assert_eq!(result, OwnedValue::Int(5));
+
+    Ok(())
+}
+
+#[test]
+fn test_branch_too_large_to_jump_over() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let elements = (0..12000).map(|value| value.to_string()).collect::<Vec<_>>().join(", ");
+    let result = runtime.run_script(format!("let t = false if t {{ [{}][0] }} else {{ 5 }}", elements));
+
+    assert!(matches!(result, Err(error) if error.error_code() == JUMP_TOO_FAR));
+
+    Ok(())
+}
+
+#[test]
+fn test_type_alias_union_accepts_each_member() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let script = r#"
+        type Damage = Int | Float
+        fn double(x: Damage) -> Damage { x + x }
+        double(2)
+        double(2.5)
+    "#;
+    let result = runtime.run_script(script)?;
+
+    assert_eq!(result, OwnedValue::Float(5.0));
+
+    Ok(())
+}
+
+#[test]
+fn test_type_alias_union_rejects_other_types() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(r#"type Damage = Int | Float let x: Damage = "crit""#);
+
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_array_type_annotation_checks_elements() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script("let xs: Array<Int> = [1, 2, 3] xs[2]")?;
+
+    assert_eq!(result, OwnedValue::Int(3));
+
+    let result = runtime.run_script("let ys: Array<Int> = [1, 2.0]");
+
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_function_type_annotation_requires_callable() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result =
+        runtime.run_script("fn apply(f: Function(Int) -> Bool, x: Int) -> Bool { f(x) } apply(|x| x > 1, 2)")?;
+
+    assert_eq!(result, OwnedValue::Bool(true));
+
+    let result = runtime.run_script("let f: Function(Int) -> Bool = 1");
+
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_pipeline_into_placeholder_args() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script("fn sub(x, y) { x - y } 10 |> sub(_, 3) |> sub(1, _)")?;
+
+    assert_eq!(result, OwnedValue::Int(-6));
+
+    Ok(())
+}
+
+#[test]
+fn test_pipeline_repeated_placeholder() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script("fn add(x, y) { x + y } 3 |> add(_, _)")?;
+
+    assert_eq!(result, OwnedValue::Int(6));
+
+    Ok(())
+}
+
+#[test]
+fn test_map_literal_index() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(r#"let m = #[1: "one", "two": 2, [3, 4]: 7] m[[3, 4]] + m["two"]"#)?;
+
+    assert_eq!(result, OwnedValue::Int(9));
+
+    Ok(())
+}
+
+#[test]
+fn test_map_store_index() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(r#"let m = #[] m[true] = 5 m[true] = m[true] + 1 m[true]"#)?;
+
+    assert_eq!(result, OwnedValue::Int(6));
+
+    Ok(())
+}
+
+#[test]
+fn test_map_methods() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(
+        r#"
+        let m = #[1: 10, 2: 20]
+        let removed = m.remove(1)
+        removed + m.values()[0] + m.keys().length() + m.entries()[0][0] + (if m.contains(1) { 100 } else { 0 })
+        "#,
+    )?;
+
+    assert_eq!(result, OwnedValue::Int(33));
+
+    Ok(())
+}
+
+#[test]
+fn test_map_rejects_unhashable_keys() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(r#"#[1.5: 1]"#);
+
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_map_rejects_arrays_that_contain_themselves() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(r#"let xs = [1] xs.push(xs) #[xs: 1]"#);
+
+    assert!(matches!(result, Err(error) if error.error_code() == INVALID_MAP_KEY_CONVERSION));
+
+    let result = runtime.run_script(r#"let m = #[[[1], 2]: 3] m[[[1], 2]]"#)?;
+
+    assert_eq!(result, OwnedValue::Int(3));
+
+    Ok(())
+}
+
+#[test]
+fn test_garbage_is_collected_while_script_runs() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(
+        r#"
+        let mut x = 0
+        while x < 100000 {
+            let garbage = [x, x + 1, x + 2]
+            x += 1
+        }
+        x
+        "#,
+    )?;
+
+    assert_eq!(result, OwnedValue::Int(100000));
+    // NOTE: Without collecting during the loop, every array would still be allocated once the script finishes.
+    assert!(runtime.runtime().heap_size() < 1024 * 1024);
+
+    Ok(())
+}
+
+#[test]
+fn test_instruction_limit() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    runtime
+        .runtime()
+        .set_limits(Limits::default().with_instructions(10_000));
+    let result = runtime.run_script(r#"while true {}"#);
+
+    assert!(matches!(result, Err(error) if error.error_code() == INSTRUCTION_LIMIT_EXCEEDED));
+
+    Ok(())
+}
+
+#[test]
+fn test_instruction_limit_allows_scripts_within_it() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    runtime
+        .runtime()
+        .set_limits(Limits::default().with_instructions(10_000));
+    let result = runtime.run_script(r#"let mut x = 0 while x < 100 { x += 1 } x"#)?;
+
+    assert_eq!(result, OwnedValue::Int(100));
+
+    Ok(())
+}
+
+#[test]
+fn test_time_limit() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    runtime
+        .runtime()
+        .set_limits(Limits::default().with_time(Duration::from_millis(50)));
+    let result = runtime.run_script(r#"while true {}"#);
+
+    assert!(matches!(result, Err(error) if error.error_code() == TIME_LIMIT_EXCEEDED));
+
+    Ok(())
+}
+
+#[test]
+fn test_call_depth_limit() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    runtime.runtime().set_limits(Limits::default().with_call_depth(50));
+    let result = runtime.run_script(r#"fn recurse(n) { recurse(n + 1) + 1 } recurse(0)"#);
+
+    assert!(matches!(result, Err(error) if error.error_code() == CALL_DEPTH_LIMIT_EXCEEDED));
+
+    Ok(())
+}
+
+#[test]
+fn test_heap_limit() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    runtime
+        .runtime()
+        .set_limits(Limits::default().with_heap_size(1024 * 1024));
+    let result = runtime.run_script(r#"let values = [] while true { values.push([1, 2, 3]) }"#);
+
+    assert!(matches!(result, Err(error) if error.error_code() == HEAP_LIMIT_EXCEEDED));
+
+    Ok(())
+}
+
+#[test]
+fn test_runtime_error_trace() {
+    let mut runtime = Dice::default();
+    let script = "fn inner(x) {\n    x[5]\n}\nfn outer() {\n    inner([1]) + 1\n}\nouter()";
+    let error = runtime.run_script(script).unwrap_err().to_string();
+    let (_, trace) = error
+        .split_once("Trace (most recent call last):")
+        .expect("Runtime errors should have a trace.");
+    let frames = trace
+        .lines()
+        .filter(|line| line.trim_start().starts_with("at "))
+        .collect::<Vec<_>>();
+
+    assert_eq!(frames.len(), 3);
+    assert!(frames[0].contains("<script>") && frames[0].ends_with("(<Script>:7:1)"));
+    assert!(frames[1].contains("outer") && frames[1].ends_with("(<Script>:5:5)"));
+    assert!(frames[2].contains("inner") && frames[2].ends_with("(<Script>:2:5)"));
+    assert!(trace.contains("5    | inner([1]) + 1"));
+    assert!(trace.contains("2    | x[5]"));
+}
+
+#[test]
+fn test_deep_recursion_overflows_the_stack() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(r#"fn recurse(n) { recurse(n + 1) + 1 } recurse(0)"#);
+
+    assert!(matches!(&result, Err(error) if error.error_code() == STACK_OVERFLOW));
+    // NOTE: Only the innermost and outermost calls are kept in the trace.
+    assert!(matches!(&result, Err(error) if error.trace().len() < 64));
+
+    Ok(())
+}
+
+#[test]
+fn test_recursive_constructor_overflows_the_stack() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(r#"class A { fn new(self) { A() } } A()"#);
+
+    assert!(matches!(result, Err(error) if error.error_code() == STACK_OVERFLOW));
+
+    Ok(())
+}
+
+#[test]
+fn test_scripts_run_after_a_stack_overflow() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(r#"fn r(n) { 1 + r(n) } r(0)"#);
+
+    assert!(matches!(result, Err(error) if error.error_code() == STACK_OVERFLOW));
+    assert_eq!(runtime.run_script("2 + 2")?, OwnedValue::Int(4));
+
+    let result = runtime.run_script(r#"class A { fn new(self) { A() } } A()"#);
+
+    assert!(matches!(result, Err(error) if error.error_code() == STACK_OVERFLOW));
+    assert_eq!(runtime.run_script("2 + 2")?, OwnedValue::Int(4));
+
+    Ok(())
+}
+
+#[test]
+fn test_stack_can_grow_past_its_initial_size() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(r#"fn count(n) { if n == 0 { 0 } else { count(n - 1) + 1 } } count(5000)"#)?;
+
+    assert_eq!(result, OwnedValue::Int(5000));
+
+    Ok(())
+}
+
+#[test]
+fn test_tail_recursion_reuses_the_frame() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(r#"fn count(n) { if n == 0 { return 0 } count(n - 1) } count(200000)"#)?;
+
+    assert_eq!(result, OwnedValue::Int(0));
+
+    Ok(())
+}
+
+#[test]
+fn test_mutual_tail_recursion_reuses_the_frame() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(
+        r#"
+        fn is_even(n) { if n == 0 { true } else { is_odd(n - 1) } }
+        fn is_odd(n) { if n == 0 { false } else { is_even(n - 1) } }
+        is_even(200000) && !is_odd(200000)
+        "#,
+    )?;
+
+    assert_eq!(result, OwnedValue::Bool(true));
+
+    Ok(())
+}
+
+#[test]
+fn test_failing_scripts_release_their_stack() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+
+    for _ in 0..70_000 {
+        let result = runtime.run_script("[1][5]");
+
+        assert!(matches!(result, Err(error) if error.error_code() == INDEX_OUT_OF_BOUNDS));
+    }
+
+    assert_eq!(runtime.run_script("1 + 1")?, OwnedValue::Int(2));
+
+    Ok(())
+}
+
+#[test]
+fn test_interrupt_from_another_thread() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let handle = runtime.interrupt_handle();
+    let interrupter = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        handle.interrupt();
+    });
+    let result = runtime.run_script(r#"while true {}"#);
+    interrupter.join().unwrap();
+
+    assert!(matches!(&result, Err(error) if error.error_code() == SCRIPT_INTERRUPTED));
+    assert!(matches!(&result, Err(error) if !error.trace().is_empty()));
+
+    // NOTE: The interrupt is cleared once it stops a script.
+    assert_eq!(runtime.run_script("1 + 1")?, OwnedValue::Int(2));
+
+    Ok(())
+}
+
+#[test]
+fn test_interrupt_while_idle_is_discarded() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    assert_eq!(runtime.run_script("1 + 1")?, OwnedValue::Int(2));
+
+    runtime.interrupt_handle().interrupt();
+    let result = runtime.run_script(r#"let mut x = 0 while x < 5000 { x += 1 } x"#)?;
+
+    assert_eq!(result, OwnedValue::Int(5000));
+
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_restores_into_a_fresh_runtime() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    runtime.runtime().load_prelude("tests/scripts/snapshot.dm")?;
+    runtime.run_script("counter.increment()")?;
+    let snapshot = Snapshot::from_json(&runtime.runtime().snapshot()?.to_json())?;
+
+    let mut restored = Dice::default();
+    restored.runtime().restore(&snapshot)?;
+
+    // NOTE: Both closures still share the same upvalue.
+    assert_eq!(restored.run_script("counter.increment()")?, OwnedValue::Int(2));
+    assert_eq!(restored.run_script("counter.get()")?, OwnedValue::Int(2));
+    assert_eq!(
+        restored.run_script("Square().describe() + Square().area()")?,
+        OwnedValue::Int(56)
+    );
+    assert_eq!(restored.run_script("Square() is Shape")?, OwnedValue::Bool(true));
+    assert_eq!(
+        restored.run_script("node.next.next.name = \"changed\"\nnode.name")?,
+        OwnedValue::String("changed".to_owned())
+    );
+    assert_eq!(restored.run_script("values[1] * 2.0")?, OwnedValue::Float(5.0));
+
+    // NOTE: The original runtime is unaffected by the restored copy.
+    assert_eq!(runtime.run_script("counter.get()")?, OwnedValue::Int(1));
+
+    Ok(())
+}
+
+#[test]
+fn test_malformed_snapshot() {
+    let result = Snapshot::from_json(r#"{ "version": 1 }"#);
+
+    assert!(matches!(result, Err(error) if error.error_code() == INVALID_SNAPSHOT));
+}
+
+#[test]
+fn test_snapshot_with_tampered_bytecode() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    runtime.runtime().load_prelude("tests/scripts/snapshot.dm")?;
+    let json = runtime.runtime().snapshot()?.to_json();
+
+    // NOTE: Swap the first function's bytecode for bytecode that's well-formed, but underflows the stack.
+    let tampered = Bytecode::new(
+        vec![Instruction::Add.into()].into_boxed_slice(),
+        0,
+        0,
+        0,
+        Box::new([]),
+        "tampered",
+        Source::new("", SourceKind::Script),
+        HashMap::new(),
+    )
+    .to_bytes()
+    .iter()
+    .map(|byte| byte.to_string())
+    .collect::<Vec<_>>()
+    .join(", ");
+    let start = json
+        .find(r#""bytecode": ["#)
+        .expect("The snapshot should contain functions.")
+        + 13;
+    let end = start + json[start..].find(']').expect("The bytecode should be an array.");
+    let json = format!("{}{}{}", &json[..start], tampered, &json[end..]);
+
+    let mut restored = Dice::default();
+    let result = restored.runtime().restore(&Snapshot::from_json(&json)?);
+
+    assert!(matches!(result, Err(error) if error.error_code() == INVALID_SNAPSHOT));
+
+    Ok(())
+}
+
+#[test]
+fn test_method_lookups_at_the_same_call_site() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(
+        r#"
+        class A { fn value(self) { 1 } }
+        class B { fn value(self) { 2 } }
+        fn get(x) { x.value() }
+        let shadowed = A()
+        shadowed.value = || 10
+        get(A()) + get(B()) + get(A()) + get(shadowed)
+        "#,
+    )?;
+
+    assert_eq!(result, OwnedValue::Int(14));
+
+    Ok(())
+}
+
+#[test]
+fn test_fields_added_in_different_orders() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(
+        r#"
+        fn sum(o) { o.a + o.b }
+        sum(#{ a: 1, b: 2 }) + sum(#{ b: 10, a: 20 }) + sum(#{ a: 100, c: 0, b: 200 })
+        "#,
+    )?;
+
+    assert_eq!(result, OwnedValue::Int(333));
+
+    Ok(())
+}
+
+#[test]
+fn test_object_with_many_fields() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let fields = (0..40).map(|n| format!("f{}: {}", n, n)).collect::<Vec<_>>().join(", ");
+    let result = runtime.run_script(format!(
+        "let o = #{{ {} }}\no.f35 = 100\no.extra = 1000\no.f0 + o.f35 + o.f39 + o.extra",
+        fields
+    ))?;
+
+    assert_eq!(result, OwnedValue::Int(1139));
+
+    Ok(())
+}
+
+#[test]
+fn test_symbols_converted_to_strings() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(
+        r#"
+        class Point { fn new(self) { self.x = 1 } }
+        Point().fields()[0] + Point.name() + "literal"
+        "#,
+    )?;
+
+    assert_eq!(result, OwnedValue::String("xPointliteral".to_owned()));
+
+    Ok(())
+}
+
+#[test]
+fn test_closures_created_from_the_same_function() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    let result = runtime.run_script(
+        r#"
+        fn make(prefix) { |o| prefix + o.x + "!" }
+        let a = make("a")
+        let b = make("b")
+        a(#{ x: "1" }) + b(#{ y: 0, x: "2" }) + a(#{ x: "3" })
+        "#,
+    )?;
+
+    assert_eq!(result, OwnedValue::String("a1!b2!a3!".to_owned()));
+
+    Ok(())
+}
+
+#[test]
+fn test_type_check_compiler_option() -> Result<(), Error> {
+    let mut runtime = Dice::default();
+    runtime.set_compiler_options(CompilerOptions {
+        type_check: true,
+        ..Default::default()
+    });
+    let result = runtime.run_script(r#"fn f(x: Int) -> Int { x } f("a")"#);
+
+    assert!(matches!(result, Err(error) if error.error_code() == TYPE_MISMATCH));
+
+    let result = runtime.run_script("fn f(x: Int) -> Int { x } f(1)")?;
+
+    assert_eq!(result, OwnedValue::Int(1));
+
+    Ok(())
+}